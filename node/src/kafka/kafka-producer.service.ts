@@ -1,16 +1,107 @@
 import { Inject, Injectable } from '@nestjs/common';
+import { ConfigService } from '@nestjs/config';
 import { ClientKafka } from '@nestjs/microservices';
+import { createHmac, createPrivateKey, randomUUID, sign } from 'crypto';
 
 @Injectable()
 export class KafkaProducerService {
-  public constructor(@Inject('KAFKA_SERVICE') private client: ClientKafka) {}
+  public constructor(
+    @Inject('KAFKA_SERVICE') private client: ClientKafka,
+    private configService: ConfigService,
+  ) {}
 
   // Fire-and-forget
   public emit(topic: string, payload: Record<string, any>, key?: string) {
-    const message = key
-      ? { key, value: JSON.stringify(payload) }
-      : JSON.stringify(payload);
+    const headers = this.sign(payload);
+    const message =
+      key || headers
+        ? { key, value: JSON.stringify(payload), headers }
+        : JSON.stringify(payload);
 
     return this.client.emit(topic, message);
   }
+
+  /**
+   * Sign `<timestamp>.<nonce>.<canonical payload>` (compact JSON with sorted keys)
+   * with the key from NODE_REWARD_SIGNING_KEY_ID, NODE_REWARD_SIGNING_ALGORITHM and
+   * NODE_REWARD_SIGNING_KEY, so the Rust worker can verify the message was produced
+   * by Node, and reject it if it is replayed.
+   *
+   * @param payload
+   * @returns Record<string, string> | undefined - The signature headers, or undefined if not configured.
+   */
+  private sign(
+    payload: Record<string, any>,
+  ): Record<string, string> | undefined {
+    const keyId = this.configService.get<string>('NODE_REWARD_SIGNING_KEY_ID');
+    const key = this.configService.get<string>('NODE_REWARD_SIGNING_KEY');
+    if (!keyId || !key) {
+      return undefined;
+    }
+
+    const algorithm =
+      this.configService.get<string>('NODE_REWARD_SIGNING_ALGORITHM') ||
+      'hmac-sha256';
+    const timestamp = Math.floor(Date.now() / 1000).toString();
+    const nonce = randomUUID();
+    const data = Buffer.from(
+      `${timestamp}.${nonce}.${canonicalJson(payload)}`,
+    );
+
+    let signature: Buffer;
+    switch (algorithm) {
+      case 'hmac-sha256':
+        signature = createHmac('sha256', Buffer.from(key, 'base64'))
+          .update(data)
+          .digest();
+        break;
+      case 'ed25519':
+        signature = sign(
+          null,
+          data,
+          createPrivateKey({
+            key: Buffer.from(key, 'base64'),
+            format: 'der',
+            type: 'pkcs8',
+          }),
+        );
+        break;
+      default:
+        throw new Error(
+          `NODE_REWARD_SIGNING_ALGORITHM must be hmac-sha256 or ed25519, not ${algorithm}`,
+        );
+    }
+
+    return {
+      'x-signature': signature.toString('base64'),
+      'x-signature-key-id': keyId,
+      'x-signature-timestamp': timestamp,
+      'x-signature-nonce': nonce,
+    };
+  }
+}
+
+/**
+ * Serialize a value as compact JSON with the object keys sorted.
+ *
+ * @param value
+ * @returns string
+ */
+function canonicalJson(value: any): string {
+  if (Array.isArray(value)) {
+    return `[${value.map((item) => canonicalJson(item ?? null)).join(',')}]`;
+  }
+
+  if (value !== null && typeof value === 'object') {
+    const fields = Object.keys(value)
+      .filter((field) => value[field] !== undefined)
+      .sort()
+      .map(
+        (field) => `${JSON.stringify(field)}:${canonicalJson(value[field])}`,
+      );
+
+    return `{${fields.join(',')}}`;
+  }
+
+  return JSON.stringify(value);
 }
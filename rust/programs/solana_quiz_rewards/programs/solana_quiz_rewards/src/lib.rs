@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, TransferChecked};
+use anchor_lang::system_program::{self, Transfer};
+use anchor_lang::Discriminator;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 
 declare_id!("Ej4LLtFBrg8SXuSusmm5nHyqaMn4BZ31hyPGtLfQmA1P");
 
@@ -7,12 +9,22 @@ declare_id!("Ej4LLtFBrg8SXuSusmm5nHyqaMn4BZ31hyPGtLfQmA1P");
 pub mod solana_quiz_rewards {
     use super::*;
 
+    // Set (or rotate) the authority allowed to apply quiz rewards, signed by the upgrade authority
+    pub fn set_reward_authority(ctx: Context<SetRewardAuthority>, authority: Pubkey) -> Result<()> {
+        ctx.accounts.config.authority = authority;
+
+        msg!("✅ Reward authority set to {}", authority);
+
+        Ok(())
+    }
+
     // Initialize a user's quiz data account
     pub fn initialize(ctx: Context<InitializeUser>) -> Result<()> {
         let user_data = &mut ctx.accounts.quiz_user_data;
 
         // Only set defaults if not yet initialized
         if user_data.user_wallet == Pubkey::default() {
+            user_data.version = QuizUserData::VERSION;
             user_data.user_wallet = ctx.accounts.user.key();
             user_data.last_quiz_day = 0;
             user_data.streak = 0;
@@ -27,22 +39,78 @@ pub mod solana_quiz_rewards {
         Ok(())
     }
 
-    // Update quiz results and manage streak
+    // Migrate a user's quiz data account to the current layout version
+    pub fn migrate_user_data(ctx: Context<MigrateUserData>) -> Result<()> {
+        let account_info = ctx.accounts.quiz_user_data.to_account_info();
+
+        // Nothing to migrate if the account does not exist yet
+        if account_info.data_is_empty() {
+            return Ok(());
+        }
+
+        require_keys_eq!(
+            *account_info.owner,
+            crate::ID,
+            QuizError::InvalidUserDataAccount
+        );
+
+        let user_data = {
+            let data = account_info.try_borrow_data()?;
+            QuizUserData::migrate(&data)?
+        };
+
+        // Already on the current layout
+        let Some(user_data) = user_data else {
+            return Ok(());
+        };
+
+        // Top up rent for the larger account
+        let new_len = 8 + QuizUserData::SIZE;
+        let required_lamports = Rent::get()?.minimum_balance(new_len);
+        let missing_lamports = required_lamports.saturating_sub(account_info.lamports());
+
+        if missing_lamports > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: account_info.clone(),
+            };
+            let cpi_program = ctx.accounts.system_program.to_account_info();
+            system_program::transfer(CpiContext::new(cpi_program, cpi_accounts), missing_lamports)?;
+        }
+
+        account_info.resize(new_len)?;
+
+        let mut data = account_info.try_borrow_mut_data()?;
+        user_data.try_serialize(&mut &mut data[..])?;
+
+        msg!(
+            "✅ Migrated QuizUserData for {} to version {}",
+            user_data.user_wallet,
+            user_data.version
+        );
+
+        Ok(())
+    }
+
+    // Update quiz results and manage streak (earned tokens in base units of the reward mint)
     pub fn update_quiz_results(
         ctx: Context<UpdateQuizResults>,
+        quiz_id: u64,
         total_questions: u64,
         correct_answers: u64,
         earned_tokens: u64,
         streak_days: u8,
     ) -> Result<()> {
         let user_data = &mut ctx.accounts.quiz_user_data;
+        let receipt = &mut ctx.accounts.quiz_reward_receipt;
 
         let now_ts = Clock::get()?.unix_timestamp;
         let current_day = (now_ts / 86400) as u64;
 
         msg!(
-            "INPUT | User: {}, Current Day: {}, Total Questions: {}, Correct Answers: {}, Earned Tokens: {}, Streak Days: {}",
+            "INPUT | User: {}, Quiz: {}, Current Day: {}, Total Questions: {}, Correct Answers: {}, Earned Tokens: {}, Streak Days: {}",
             user_data.user_wallet,
+            quiz_id,
             current_day,
             total_questions,
             correct_answers,
@@ -50,16 +118,26 @@ pub mod solana_quiz_rewards {
             streak_days
         );
 
-        // Prevent multiple quizzes in one day
-        if user_data.last_quiz_day == current_day {
+        // Prevent applying the same quiz twice
+        if receipt.user_wallet != Pubkey::default() {
             msg!(
-                "User {:?} already finished quiz today ({})",
+                "User {:?} already received reward for quiz {}",
                 user_data.user_wallet,
-                current_day,
+                quiz_id,
             );
-            return Err(error!(QuizError::AlreadyPlayedToday));
+            return Err(error!(QuizError::RewardAlreadyApplied));
         }
 
+        receipt.user_wallet = ctx.accounts.user.key();
+        receipt.quiz_id = quiz_id;
+        receipt.total_questions = total_questions;
+        receipt.correct_answers = correct_answers;
+        receipt.earned_tokens = earned_tokens;
+        receipt.transfer_amount = 0;
+        receipt.created_at = now_ts;
+
+        let before = user_data.stats();
+
         msg!(
             "BEFORE | User: {}, Last Quiz Day: {}, Streak: {}, Total Quizzes: {}, Total Questions: {}, Correct Answers: {}, Earned Tokens: {}",
             user_data.user_wallet,
@@ -77,26 +155,32 @@ pub mod solana_quiz_rewards {
         user_data.correct_answers += correct_answers;
         user_data.earned_tokens += earned_tokens;
 
-        // Reset streak if goal reached
-        if user_data.streak == streak_days {
-            user_data.streak = 0;
-        }
+        // Only the first perfect quiz of a day moves the streak forward
+        let is_new_day = user_data.last_quiz_day != current_day;
 
         if total_questions == correct_answers {
-            // Increment streak if consecutive day, else reset to 1
-            if user_data.last_quiz_day + 1 == current_day {
-                user_data.streak += 1;
-            } else {
-                user_data.streak = 1;
+            if is_new_day {
+                // Reset streak if goal reached
+                if user_data.streak == streak_days {
+                    user_data.streak = 0;
+                }
+
+                // Increment streak if consecutive day, else reset to 1
+                if user_data.last_quiz_day + 1 == current_day {
+                    user_data.streak += 1;
+                } else {
+                    user_data.streak = 1;
+                }
             }
         } else {
             user_data.streak = 0;
         }
 
         // Emit event if streak goal achieved
-        if user_data.streak == streak_days {
+        if is_new_day && user_data.streak == streak_days {
             emit!(StreakAchieved {
                 user: user_data.user_wallet,
+                quiz_id,
                 streak: user_data.streak,
             });
         }
@@ -114,13 +198,42 @@ pub mod solana_quiz_rewards {
             user_data.earned_tokens
         );
 
+        // Emit event with stats before and after the update
+        emit!(QuizResultsUpdated {
+            user: user_data.user_wallet,
+            quiz_id,
+            total_questions,
+            correct_answers,
+            earned_tokens,
+            before,
+            after: user_data.stats(),
+        });
+
         Ok(())
     }
 
-    // Transfer SPL tokens via CPI
-    pub fn transfer_tokens(ctx: Context<TransferTokens>, amount: u64) -> Result<()> {
+    // Transfer SPL / Token-2022 tokens via CPI (amount in base units)
+    pub fn transfer_tokens(ctx: Context<TransferTokens>, quiz_id: u64, amount: u64) -> Result<()> {
+        let receipt = &mut ctx.accounts.quiz_reward_receipt;
+
+        // Prevent paying out the same quiz twice
+        if receipt.transfer_amount != 0 {
+            msg!(
+                "User {:?} already received tokens for quiz {}",
+                receipt.user_wallet,
+                quiz_id,
+            );
+            return Err(error!(QuizError::RewardAlreadyApplied));
+        }
+
+        // A zero amount would leave the receipt unpaid
+        if amount == 0 {
+            return Err(error!(QuizError::InvalidTransferAmount));
+        }
+
         let decimals = ctx.accounts.mint.decimals;
-        let transfer_amount = amount * 10_u64.pow(decimals as u32);
+
+        receipt.transfer_amount = amount;
 
         let cpi_accounts = TransferChecked {
             from: ctx.accounts.sender_token_account.to_account_info(),
@@ -133,13 +246,39 @@ pub mod solana_quiz_rewards {
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
 
         // Perform the token transfer
-        token::transfer_checked(cpi_ctx, transfer_amount, decimals)?;
+        token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
 
-        msg!("✅ Transferred {} tokens", amount);
+        msg!("✅ Transferred {} base units", amount);
         Ok(())
     }
 }
 
+#[derive(Accounts)]
+pub struct SetRewardAuthority<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + RewardsConfig::SIZE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, RewardsConfig>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::SolanaQuizRewards>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(payer.key())
+            @ QuizError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct InitializeUser<'info> {
     #[account(mut)]
@@ -161,52 +300,297 @@ pub struct InitializeUser<'info> {
 }
 
 #[derive(Accounts)]
+pub struct MigrateUserData<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: only pass the user's public key
+    pub user: UncheckedAccount<'info>,
+
+    /// CHECK: legacy layouts cannot be deserialized as QuizUserData, validated in the handler
+    #[account(mut, seeds = [b"user_data_v2", user.key().as_ref()], bump)]
+    pub quiz_user_data: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(quiz_id: u64)]
 pub struct UpdateQuizResults<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // Only the reward authority creates receipts, so nobody can claim a quiz in advance
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump, has_one = authority @ QuizError::Unauthorized)]
+    pub config: Account<'info, RewardsConfig>,
+
     /// CHECK: only pass the user's public key
     pub user: UncheckedAccount<'info>,
 
     #[account(mut, seeds = [b"user_data_v2", user.key().as_ref()], bump)]
     pub quiz_user_data: Account<'info, QuizUserData>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + QuizRewardReceipt::SIZE,
+        seeds = [b"quiz_receipt", user.key().as_ref(), &quiz_id.to_le_bytes()],
+        bump
+    )]
+    pub quiz_reward_receipt: Account<'info, QuizRewardReceipt>,
+
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
+#[instruction(quiz_id: u64)]
 pub struct TransferTokens<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.authority == signer.key() @ QuizError::Unauthorized
+    )]
+    pub config: Account<'info, RewardsConfig>,
+    /// CHECK: only pass the user's public key
+    pub user: UncheckedAccount<'info>,
     #[account(mut)]
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
-    pub sender_token_account: Account<'info, TokenAccount>,
+    pub sender_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    pub recipient_token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"quiz_receipt", user.key().as_ref(), &quiz_id.to_le_bytes()],
+        bump
+    )]
+    pub quiz_reward_receipt: Account<'info, QuizRewardReceipt>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// Program settings, a single PDA
+#[account]
+pub struct RewardsConfig {
+    pub authority: Pubkey, // signer allowed to apply quiz rewards
 }
 
+impl RewardsConfig {
+    pub const SIZE: usize = 32; // account byte size
+}
+
+// Current QuizUserData layout version, shared with the worker through the IDL
+#[constant]
+pub const QUIZ_USER_DATA_VERSION: u8 = 2;
+
+// Byte size of unversioned QuizUserData accounts, shared with the worker through the IDL
+#[constant]
+pub const QUIZ_USER_DATA_V1_SIZE: u64 = 32 + 8 + 1 + 8 + 8 + 8 + 8;
+
 // On-chain account storing user quiz stats
 #[account]
 pub struct QuizUserData {
+    pub version: u8,
     pub user_wallet: Pubkey,
     pub last_quiz_day: u64,
     pub streak: u8,
     pub total_quizzes: u64,
     pub total_questions: u64,
     pub correct_answers: u64,
-    pub earned_tokens: u64,
+    pub earned_tokens: u64, // base units of the reward mint
+    pub reserved: [u8; 64], // space for future fields without realloc
 }
 
 impl QuizUserData {
-    pub const SIZE: usize = 32 + 8 + 1 + 8 + 8 + 8 + 8; // account byte size
+    pub const VERSION: u8 = QUIZ_USER_DATA_VERSION; // current layout version
+    pub const SIZE: usize = 1 + 32 + 8 + 1 + 8 + 8 + 8 + 8 + 64; // account byte size
+}
+
+impl QuizUserData {
+    // Snapshot of the quiz stats for events
+    pub fn stats(&self) -> QuizStats {
+        QuizStats {
+            last_quiz_day: self.last_quiz_day,
+            streak: self.streak,
+            total_quizzes: self.total_quizzes,
+            total_questions: self.total_questions,
+            correct_answers: self.correct_answers,
+            earned_tokens: self.earned_tokens,
+        }
+    }
+}
+
+impl QuizUserData {
+    // Current layout of an account's data, or None if it is already on it
+    pub fn migrate(data: &[u8]) -> Result<Option<Self>> {
+        if data.len() < 8 || data[..8] != Self::DISCRIMINATOR[..] {
+            return Err(error!(QuizError::InvalidUserDataAccount));
+        }
+
+        match data.len() - 8 {
+            QuizUserDataV1::SIZE => {
+                let legacy = QuizUserDataV1::deserialize(&mut &data[8..])?;
+                Ok(Some(Self::from(legacy)))
+            }
+            Self::SIZE if data[8] == Self::VERSION => Ok(None),
+            _ => Err(error!(QuizError::UnsupportedUserDataVersion)),
+        }
+    }
+}
+
+impl From<QuizUserDataV1> for QuizUserData {
+    fn from(legacy: QuizUserDataV1) -> Self {
+        Self {
+            version: Self::VERSION,
+            user_wallet: legacy.user_wallet,
+            last_quiz_day: legacy.last_quiz_day,
+            streak: legacy.streak,
+            total_quizzes: legacy.total_quizzes,
+            total_questions: legacy.total_questions,
+            correct_answers: legacy.correct_answers,
+            earned_tokens: legacy.earned_tokens,
+            reserved: [0; 64],
+        }
+    }
+}
+
+// Unversioned layout of QuizUserData accounts created before versioning
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct QuizUserDataV1 {
+    pub user_wallet: Pubkey,
+    pub last_quiz_day: u64,
+    pub streak: u8,
+    pub total_quizzes: u64,
+    pub total_questions: u64,
+    pub correct_answers: u64,
+    pub earned_tokens: u64,
+}
+
+impl QuizUserDataV1 {
+    pub const SIZE: usize = QUIZ_USER_DATA_V1_SIZE as usize; // account byte size
+}
+
+// On-chain receipt of a single applied quiz reward
+#[account]
+pub struct QuizRewardReceipt {
+    pub user_wallet: Pubkey,
+    pub quiz_id: u64,
+    pub total_questions: u64,
+    pub correct_answers: u64,
+    pub earned_tokens: u64, // base units of the reward mint
+    pub transfer_amount: u64,
+    pub created_at: i64,
+}
+
+impl QuizRewardReceipt {
+    pub const SIZE: usize = 32 + 8 + 8 + 8 + 8 + 8 + 8; // account byte size
 }
 
 #[error_code]
 pub enum QuizError {
-    #[msg("User already completed quiz today")]
-    AlreadyPlayedToday,
+    #[msg("Reward for this quiz was already applied")]
+    RewardAlreadyApplied,
+    #[msg("Account is not a QuizUserData account")]
+    InvalidUserDataAccount,
+    #[msg("Unsupported QuizUserData layout version")]
+    UnsupportedUserDataVersion,
+    #[msg("Transfer amount must be greater than zero")]
+    InvalidTransferAmount,
+    #[msg("Signer is not the reward authority")]
+    Unauthorized,
 }
 
 // Event emitted when streak goal is reached
 #[event]
 pub struct StreakAchieved {
     pub user: Pubkey,
+    pub quiz_id: u64,
+    pub streak: u8,
+}
+
+// Event emitted on every quiz results update
+#[event]
+pub struct QuizResultsUpdated {
+    pub user: Pubkey,
+    pub quiz_id: u64,
+    pub total_questions: u64,
+    pub correct_answers: u64,
+    pub earned_tokens: u64, // base units of the reward mint
+    pub before: QuizStats,
+    pub after: QuizStats,
+}
+
+// User quiz stats carried by events
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct QuizStats {
+    pub last_quiz_day: u64,
     pub streak: u8,
+    pub total_quizzes: u64,
+    pub total_questions: u64,
+    pub correct_answers: u64,
+    pub earned_tokens: u64, // base units of the reward mint
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_data() -> QuizUserData {
+        QuizUserData {
+            version: QuizUserData::VERSION,
+            user_wallet: Pubkey::new_unique(),
+            last_quiz_day: 20_000,
+            streak: 3,
+            total_quizzes: 5,
+            total_questions: 50,
+            correct_answers: 40,
+            earned_tokens: 120,
+            reserved: [0; 64],
+        }
+    }
+
+    #[test]
+    fn migrate_is_a_no_op_on_the_current_version() {
+        let mut data = vec![];
+        user_data().try_serialize(&mut data).unwrap();
+
+        assert_eq!(data.len(), 8 + QuizUserData::SIZE);
+        assert!(QuizUserData::migrate(&data).unwrap().is_none());
+    }
+
+    #[test]
+    fn migrate_upgrades_unversioned_accounts() {
+        let current = user_data();
+        let legacy = QuizUserDataV1 {
+            user_wallet: current.user_wallet,
+            last_quiz_day: current.last_quiz_day,
+            streak: current.streak,
+            total_quizzes: current.total_quizzes,
+            total_questions: current.total_questions,
+            correct_answers: current.correct_answers,
+            earned_tokens: current.earned_tokens,
+        };
+        let mut data = QuizUserData::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        assert_eq!(data.len(), 8 + QuizUserDataV1::SIZE);
+
+        let migrated = QuizUserData::migrate(&data).unwrap().unwrap();
+
+        assert_eq!(migrated.version, QUIZ_USER_DATA_VERSION);
+        assert_eq!(migrated.user_wallet, current.user_wallet);
+        assert_eq!(migrated.streak, current.streak);
+        assert_eq!(migrated.earned_tokens, current.earned_tokens);
+    }
+
+    #[test]
+    fn migrate_rejects_other_accounts() {
+        let mut data = vec![];
+        user_data().try_serialize(&mut data).unwrap();
+        data[0] ^= 1;
+
+        assert!(QuizUserData::migrate(&data).is_err());
+    }
 }
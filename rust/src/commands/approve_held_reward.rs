@@ -0,0 +1,93 @@
+use crate::kafka::consumer_handlers::solana_quiz_reward::SolanaQuizRewardHandler;
+use crate::kafka::producer::KafkaProducer;
+use crate::models::reward::ApprovalOutcome;
+use crate::models::wallet::AuditAction;
+use crate::services::audit_api::AuditApi;
+use crate::services::reward_hold_api::RewardHoldApi;
+use anyhow::{Result, anyhow};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Approves a reward held for manual review and pays it out,
+/// skipping the validation and anti-sybil checks, then records it in the audit log.
+///
+/// The reward stays held only if nothing was sent: once paid out, it is never held again,
+/// even if confirming the payout fails. A reward exceeding an emission budget is held again,
+/// and a reward rejected by the payout checks is recorded as rejected.
+///
+/// # Arguments
+/// * `wallet` - User wallet address.
+/// * `quiz_id` - Quiz of the held reward.
+/// * `actor` - Who approves the reward, recorded in the audit log.
+pub async fn run(wallet: String, quiz_id: u64, actor: String) -> Result<()> {
+    let hold_api = RewardHoldApi::new();
+    let key = RewardHoldApi::key(&wallet, quiz_id);
+
+    let Some(held) = hold_api.take(&key).await? else {
+        info!("No reward of quiz {} is held for {}", quiz_id, wallet);
+        return Ok(());
+    };
+
+    let handler = SolanaQuizRewardHandler::new(Arc::new(KafkaProducer::new()));
+    let outcome = match handler.approve(&held.reward).await {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            hold_api.restore(&key, held).await?;
+            return Err(err);
+        }
+    };
+
+    let audit_api = AuditApi::new();
+    match outcome {
+        ApprovalOutcome::Paid => {
+            audit_api
+                .record(AuditAction::ApproveHeldReward, &wallet, &actor, None, None)
+                .await?;
+
+            info!("Reward of quiz {} for {} approved", quiz_id, wallet);
+        }
+        ApprovalOutcome::PaidUnconfirmed(err) => {
+            let reason = format!("Paid out, but the confirmation failed: {}", err);
+            audit_api
+                .record(
+                    AuditAction::ApproveHeldReward,
+                    &wallet,
+                    &actor,
+                    Some(reason),
+                    None,
+                )
+                .await?;
+
+            return Err(anyhow!(
+                "Reward of quiz {} for {} paid out, but the confirmation failed: {}",
+                quiz_id,
+                wallet,
+                err
+            ));
+        }
+        ApprovalOutcome::Held => {
+            warn!(
+                "Reward of quiz {} for {} exceeds an emission budget and is held again",
+                quiz_id, wallet
+            );
+        }
+        ApprovalOutcome::Rejected => {
+            audit_api
+                .record(
+                    AuditAction::RejectHeldReward,
+                    &wallet,
+                    &actor,
+                    Some("Rejected by the payout checks on approval".to_string()),
+                    None,
+                )
+                .await?;
+
+            warn!(
+                "Reward of quiz {} for {} rejected by the payout checks",
+                quiz_id, wallet
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use crate::services::wallet_blocklist_api::WalletBlocklistApi;
+use anyhow::Result;
+use tracing::info;
+
+/// Blocks a wallet from receiving rewards and records it in the audit log.
+///
+/// # Arguments
+/// * `wallet` - User wallet address.
+/// * `reason` - Why the wallet is blocked.
+/// * `actor` - Who blocks the wallet, recorded in the audit log.
+pub async fn run(wallet: String, reason: String, actor: String) -> Result<()> {
+    WalletBlocklistApi::new()
+        .block(&wallet, &reason, &actor)
+        .await?;
+
+    info!("Wallet {} blocked", wallet);
+
+    Ok(())
+}
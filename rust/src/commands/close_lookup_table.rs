@@ -0,0 +1,21 @@
+use crate::services::lookup_table_api::LookupTableApi;
+use anyhow::Result;
+use solana_program::pubkey::Pubkey;
+use tracing::{error, info};
+
+/// Closes a deactivated address lookup table and returns its rent to the authority.
+///
+/// # Arguments
+/// * `table` - Address of the lookup table.
+///
+/// Prints the transaction signature on success, or an error message otherwise.
+pub async fn run(table: String) -> Result<()> {
+    let table_pubkey = table.parse::<Pubkey>()?;
+
+    match LookupTableApi::new().close(&table_pubkey).await {
+        Ok(signature) => info!("Transaction Signature: {}", signature),
+        Err(err) => error!("Error sending transaction: {}", err),
+    }
+
+    Ok(())
+}
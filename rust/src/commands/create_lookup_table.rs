@@ -0,0 +1,20 @@
+use crate::services::lookup_table_api::LookupTableApi;
+use anyhow::Result;
+use tracing::{error, info};
+
+/// Creates an address lookup table with the accounts shared by every reward transaction
+/// (program, mint, token programs, treasury token account) and makes it the active table.
+///
+/// Prints the table address and the transaction signature on success,
+/// or an error message otherwise.
+pub async fn run() -> Result<()> {
+    match LookupTableApi::new().create().await {
+        Ok((table, signature)) => {
+            info!("Lookup table: {}", table);
+            info!("Transaction Signature: {}", signature);
+        }
+        Err(err) => error!("Error sending transaction: {}", err),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use crate::services::nft_api::NftApi;
+use anyhow::Result;
+use tracing::{error, info};
+
+/// Creates the collection NFT that groups all streak NFTs.
+///
+/// On success, prints the collection mint address (to be set in
+/// `SOLANA_NFT_COLLECTION_MINT`) and the transaction signatures.
+/// On failure, prints the error message.
+pub async fn run() -> Result<()> {
+    match NftApi::new().create_collection().await {
+        Ok((collection_mint, signatures)) => {
+            info!("Collection Mint: {}", collection_mint);
+            for signature in signatures {
+                info!("Transaction Signature: {}", signature);
+            }
+        }
+        Err(err) => error!("Error sending transaction: {}", err),
+    }
+
+    Ok(())
+}
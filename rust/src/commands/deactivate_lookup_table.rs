@@ -0,0 +1,22 @@
+use crate::services::lookup_table_api::LookupTableApi;
+use anyhow::Result;
+use solana_program::pubkey::Pubkey;
+use tracing::{error, info};
+
+/// Deactivates an address lookup table, so reward transactions no longer use it.
+/// It can be closed with `close-lookup-table` once the deactivation is no longer recent.
+///
+/// # Arguments
+/// * `table` - Address of the lookup table.
+///
+/// Prints the transaction signature on success, or an error message otherwise.
+pub async fn run(table: String) -> Result<()> {
+    let table_pubkey = table.parse::<Pubkey>()?;
+
+    match LookupTableApi::new().deactivate(&table_pubkey).await {
+        Ok(signature) => info!("Transaction Signature: {}", signature),
+        Err(err) => error!("Error sending transaction: {}", err),
+    }
+
+    Ok(())
+}
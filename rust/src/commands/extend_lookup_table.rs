@@ -0,0 +1,47 @@
+use crate::services::lookup_table_api::LookupTableApi;
+use anyhow::{Result, bail};
+use solana_program::pubkey::Pubkey;
+use tracing::{error, info};
+
+/// Adds addresses and/or the token accounts of the most rewarded users
+/// to an address lookup table.
+///
+/// # Arguments
+/// * `table` - Address of the lookup table (defaults to the last active table).
+/// * `addresses` - Addresses to add.
+/// * `hot` - Number of the most rewarded users whose token accounts are added.
+///
+/// Prints the transaction signatures on success, or an error message otherwise.
+pub async fn run(table: Option<String>, addresses: Vec<String>, hot: Option<usize>) -> Result<()> {
+    let lookup_table_api = LookupTableApi::new();
+
+    let table_pubkey = match table {
+        Some(table) => table.parse::<Pubkey>()?,
+        None => match lookup_table_api.list().await?.last() {
+            Some(table_pubkey) => *table_pubkey,
+            None => bail!("There is no active lookup table, create one with create-lookup-table"),
+        },
+    };
+
+    let mut new_addresses = addresses
+        .iter()
+        .map(|address| address.parse::<Pubkey>())
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(hot) = hot {
+        new_addresses.extend(lookup_table_api.hot_token_accounts(hot).await?);
+    }
+
+    match lookup_table_api.extend(&table_pubkey, &new_addresses).await {
+        Ok(signatures) if signatures.is_empty() => {
+            info!("Lookup table {} already holds the addresses", table_pubkey)
+        }
+        Ok(signatures) => {
+            for signature in signatures {
+                info!("Transaction Signature: {}", signature);
+            }
+        }
+        Err(err) => error!("Error sending transaction: {}", err),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use crate::services::payer_pool_api::PayerPoolApi;
+use crate::utils::{solana_util, token_util};
+use anyhow::{Result, bail};
+use tracing::{error, info};
+
+/// Tops every payer of the pool (`SOLANA_PAYER_POOL_KEYPAIR_PATHS`) up to a balance,
+/// with transfers from the authority wallet.
+///
+/// # Arguments
+/// * `sol_amount` - Balance every payer is topped up to, in SOL as a decimal (e.g. `0.5`).
+///
+/// Prints the transaction signatures and the balances of the payers on success,
+/// or an error message otherwise.
+pub async fn run(sol_amount: String) -> Result<()> {
+    let payer_pool_api = PayerPoolApi::new();
+
+    if payer_pool_api.payers().is_empty() {
+        bail!("The payer pool is empty, set SOLANA_PAYER_POOL_KEYPAIR_PATHS");
+    }
+
+    let target_lamports = token_util::parse_token_amount(&sol_amount, token_util::SOL_DECIMALS)?;
+    let authority_keypair = solana_util::get_authority_keypair();
+
+    match payer_pool_api
+        .fund(&authority_keypair, target_lamports)
+        .await
+    {
+        Ok(signatures) if signatures.is_empty() => {
+            info!("Every payer already holds {} SOL", sol_amount)
+        }
+        Ok(signatures) => {
+            for signature in signatures {
+                info!("Transaction Signature: {}", signature);
+            }
+        }
+        Err(err) => {
+            error!("Error sending transaction: {}", err);
+            return Ok(());
+        }
+    }
+
+    for (pubkey, lamports) in payer_pool_api.balances().await? {
+        info!(
+            "Payer {}: {} SOL",
+            pubkey,
+            token_util::format_token_amount(lamports, token_util::SOL_DECIMALS)
+        );
+    }
+
+    Ok(())
+}
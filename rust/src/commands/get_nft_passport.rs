@@ -0,0 +1,20 @@
+use crate::models::nft::NFT_PASSPORTS_STORE;
+use crate::storage::json_store::JsonStore;
+use anyhow::Result;
+use std::collections::HashMap;
+use tracing::info;
+
+/// Looks up the streak passport NFT mint held by a wallet.
+///
+/// # Arguments
+/// * `wallet` - User wallet address.
+pub async fn run(wallet: String) -> Result<()> {
+    let passports: HashMap<String, String> = JsonStore::new(NFT_PASSPORTS_STORE).load().await?;
+
+    match passports.get(&wallet) {
+        Some(mint) => info!("Passport Mint: {}", mint),
+        None => info!("No passport found for {}", wallet),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use crate::services::wallet_blocklist_api::WalletBlocklistApi;
+use anyhow::Result;
+use tracing::info;
+
+/// Lists the wallets blocked by `block-wallet` or the control topic
+/// (wallets in the `REWARD_BLOCKLIST_PATH` file are not included).
+pub async fn run() -> Result<()> {
+    let blocklist = WalletBlocklistApi::new().list().await?;
+
+    if blocklist.is_empty() {
+        info!("No blocked wallets");
+    }
+
+    for (wallet, blocked) in blocklist {
+        info!(
+            "{}: {} (by {} at {})",
+            wallet, blocked.reason, blocked.actor, blocked.blocked_at
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use crate::services::reward_hold_api::RewardHoldApi;
+use anyhow::Result;
+use tracing::info;
+
+/// Lists the rewards held for manual review, with the reason they were held.
+pub async fn run() -> Result<()> {
+    let holds = RewardHoldApi::new().list().await?;
+
+    if holds.is_empty() {
+        info!("No held rewards");
+    }
+
+    for (key, held) in holds {
+        info!(
+            "{}: user {}, {}/{} correct, {} day streak - {:?}: {} (held at {})",
+            key,
+            held.reward.user_id,
+            held.reward.correct_answers,
+            held.reward.total_questions,
+            held.reward.streak_days,
+            held.reason,
+            held.message,
+            held.held_at
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use crate::services::blockchain_api::BlockchainApi;
+use anyhow::Result;
+use tracing::{error, info};
+
+/// Migrates all unversioned on-chain quiz user data accounts to the current layout version.
+///
+/// # Arguments
+/// * `batch_size` - Number of accounts to migrate per transaction.
+///
+/// Prints the transaction signatures on success, or an error message otherwise.
+pub async fn run(batch_size: usize) -> Result<()> {
+    match BlockchainApi::new()
+        .migrate_user_data_accounts(batch_size)
+        .await
+    {
+        Ok(signatures) => {
+            for signature in signatures {
+                info!("Transaction Signature: {}", signature);
+            }
+        }
+        Err(err) => error!("Error sending transaction: {}", err),
+    }
+
+    Ok(())
+}
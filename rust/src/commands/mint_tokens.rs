@@ -1,15 +1,27 @@
 use crate::services::solana_api::SolanaApi;
-use anyhow::Result;
+use crate::utils::token_util;
+use anyhow::{Result, bail};
 use tracing::{error, info};
 
 /// Mints tokens to the authority account.
 ///
 /// # Arguments
-/// * `amount` - Number of tokens to mint (before applying decimals).
+/// * `amount` - Number of tokens to mint, as a decimal (e.g. `1000000` or `0.5`).
+/// * `base_units` - Amount to mint in base units, instead of `amount`.
 ///
 /// Prints the transaction signature on success, or an error message otherwise.
-pub async fn run(amount: u64) -> Result<()> {
-    match SolanaApi::new().mint_tokens(amount).await {
+pub async fn run(amount: Option<String>, base_units: Option<u64>) -> Result<()> {
+    let solana_api = SolanaApi::new();
+
+    let base_units = match (amount, base_units) {
+        (None, Some(base_units)) => base_units,
+        (Some(amount), None) => {
+            token_util::parse_token_amount(&amount, solana_api.get_mint_decimals().await?)?
+        }
+        _ => bail!("Either --amount or --base-units must be set"),
+    };
+
+    match solana_api.mint_tokens(base_units).await {
         Ok(signature) => info!("Transaction Signature: {}", signature),
         Err(err) => error!("Error sending transaction: {}", err),
     }
@@ -1,12 +1,31 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
+mod approve_held_reward;
+mod block_wallet;
+mod close_lookup_table;
+mod create_lookup_table;
 mod create_metadata_accounts;
 mod create_mint;
+mod create_nft_collection;
 mod create_token_account;
+mod deactivate_lookup_table;
+mod extend_lookup_table;
+mod freeze_token_account;
+mod fund_payer_pool;
+mod get_nft_passport;
+mod list_blocked_wallets;
+mod list_held_rewards;
+mod migrate_user_data;
 mod mint_tokens;
+mod reject_held_reward;
+mod render_nft_badge;
 mod request_airdrop;
+mod revoke_nft;
 mod send_tokens;
+mod set_reward_authority;
+mod thaw_token_account;
+mod unblock_wallet;
 
 #[derive(Parser)]
 #[command(name = "solana-quiz")]
@@ -27,16 +46,124 @@ pub enum Commands {
     CreateMint {},
     CreateTokenAccount {},
     MintTokens {
-        #[arg(short, long)]
-        amount: u64,
+        #[arg(short, long, required_unless_present = "base_units")]
+        amount: Option<String>,
+        #[arg(short, long, conflicts_with = "amount")]
+        base_units: Option<u64>,
     },
     SendTokens {
         #[arg(short, long)]
         recipient: String,
-        #[arg(short, long)]
-        amount: u64,
+        #[arg(short, long, required_unless_present = "base_units")]
+        amount: Option<String>,
+        #[arg(short, long, conflicts_with = "amount")]
+        base_units: Option<u64>,
     },
     CreateMetadataAccounts {},
+    MigrateUserData {
+        #[arg(short, long, default_value_t = 10)]
+        batch_size: usize,
+    },
+    SetRewardAuthority {
+        #[arg(short, long)]
+        authority: Option<String>,
+    },
+    CreateNftCollection {},
+    RenderNftBadge {
+        #[arg(short, long)]
+        mint: String,
+        #[arg(short, long)]
+        wallet: String,
+        #[arg(short, long)]
+        quiz_id: u64,
+        #[arg(short, long)]
+        streak_days: u8,
+        #[arg(short, long)]
+        achieved_at: i64,
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    RevokeNft {
+        #[arg(short, long)]
+        mint: String,
+    },
+    GetNftPassport {
+        #[arg(short, long)]
+        wallet: String,
+    },
+    BlockWallet {
+        #[arg(short, long)]
+        wallet: String,
+        #[arg(short, long)]
+        reason: String,
+        #[arg(short, long, default_value = "cli")]
+        actor: String,
+    },
+    UnblockWallet {
+        #[arg(short, long)]
+        wallet: String,
+        #[arg(short, long)]
+        reason: Option<String>,
+        #[arg(short, long, default_value = "cli")]
+        actor: String,
+    },
+    ListBlockedWallets {},
+    FreezeTokenAccount {
+        #[arg(short, long)]
+        wallet: String,
+        #[arg(short, long)]
+        reason: Option<String>,
+        #[arg(short, long, default_value = "cli")]
+        actor: String,
+    },
+    ThawTokenAccount {
+        #[arg(short, long)]
+        wallet: String,
+        #[arg(short, long)]
+        reason: Option<String>,
+        #[arg(short, long, default_value = "cli")]
+        actor: String,
+    },
+    ListHeldRewards {},
+    ApproveHeldReward {
+        #[arg(short, long)]
+        wallet: String,
+        #[arg(short, long)]
+        quiz_id: u64,
+        #[arg(short, long, default_value = "cli")]
+        actor: String,
+    },
+    RejectHeldReward {
+        #[arg(short, long)]
+        wallet: String,
+        #[arg(short, long)]
+        quiz_id: u64,
+        #[arg(short, long)]
+        reason: Option<String>,
+        #[arg(short, long, default_value = "cli")]
+        actor: String,
+    },
+    CreateLookupTable {},
+    ExtendLookupTable {
+        #[arg(short, long)]
+        table: Option<String>,
+        #[arg(short, long, value_delimiter = ',')]
+        addresses: Vec<String>,
+        #[arg(long)]
+        hot: Option<usize>,
+    },
+    DeactivateLookupTable {
+        #[arg(short, long)]
+        table: String,
+    },
+    CloseLookupTable {
+        #[arg(short, long)]
+        table: String,
+    },
+    FundPayerPool {
+        #[arg(short, long)]
+        sol_amount: String,
+    },
 }
 
 /// Runs the CLI application by parsing the user command and executing
@@ -54,15 +181,112 @@ pub async fn run() -> Result<()> {
         Commands::CreateTokenAccount {} => {
             create_token_account::run().await?;
         }
-        Commands::MintTokens { amount } => {
-            mint_tokens::run(amount).await?;
+        Commands::MintTokens { amount, base_units } => {
+            mint_tokens::run(amount, base_units).await?;
         }
-        Commands::SendTokens { recipient, amount } => {
-            send_tokens::run(recipient, amount).await?;
+        Commands::SendTokens {
+            recipient,
+            amount,
+            base_units,
+        } => {
+            send_tokens::run(recipient, amount, base_units).await?;
         }
         Commands::CreateMetadataAccounts {} => {
             create_metadata_accounts::run().await?;
         }
+        Commands::MigrateUserData { batch_size } => {
+            migrate_user_data::run(batch_size).await?;
+        }
+        Commands::SetRewardAuthority { authority } => {
+            set_reward_authority::run(authority).await?;
+        }
+        Commands::CreateNftCollection {} => {
+            create_nft_collection::run().await?;
+        }
+        Commands::RenderNftBadge {
+            mint,
+            wallet,
+            quiz_id,
+            streak_days,
+            achieved_at,
+            output,
+        } => {
+            render_nft_badge::run(mint, wallet, quiz_id, streak_days, achieved_at, output).await?;
+        }
+        Commands::RevokeNft { mint } => {
+            revoke_nft::run(mint).await?;
+        }
+        Commands::GetNftPassport { wallet } => {
+            get_nft_passport::run(wallet).await?;
+        }
+        Commands::BlockWallet {
+            wallet,
+            reason,
+            actor,
+        } => {
+            block_wallet::run(wallet, reason, actor).await?;
+        }
+        Commands::UnblockWallet {
+            wallet,
+            reason,
+            actor,
+        } => {
+            unblock_wallet::run(wallet, reason, actor).await?;
+        }
+        Commands::ListBlockedWallets {} => {
+            list_blocked_wallets::run().await?;
+        }
+        Commands::FreezeTokenAccount {
+            wallet,
+            reason,
+            actor,
+        } => {
+            freeze_token_account::run(wallet, reason, actor).await?;
+        }
+        Commands::ThawTokenAccount {
+            wallet,
+            reason,
+            actor,
+        } => {
+            thaw_token_account::run(wallet, reason, actor).await?;
+        }
+        Commands::ListHeldRewards {} => {
+            list_held_rewards::run().await?;
+        }
+        Commands::ApproveHeldReward {
+            wallet,
+            quiz_id,
+            actor,
+        } => {
+            approve_held_reward::run(wallet, quiz_id, actor).await?;
+        }
+        Commands::RejectHeldReward {
+            wallet,
+            quiz_id,
+            reason,
+            actor,
+        } => {
+            reject_held_reward::run(wallet, quiz_id, reason, actor).await?;
+        }
+        Commands::CreateLookupTable {} => {
+            create_lookup_table::run().await?;
+        }
+        Commands::ExtendLookupTable {
+            table,
+            addresses,
+            hot,
+        } => {
+            extend_lookup_table::run(table, addresses, hot).await?;
+        }
+        Commands::DeactivateLookupTable { table } => {
+            deactivate_lookup_table::run(table).await?;
+        }
+        Commands::CloseLookupTable { table } => {
+            close_lookup_table::run(table).await?;
+        }
+        Commands::FundPayerPool { sol_amount } => {
+            fund_payer_pool::run(sol_amount).await?;
+        }
     }
 
     Ok(())
@@ -0,0 +1,45 @@
+use crate::kafka::consumer_handlers::solana_quiz_reward::SolanaQuizRewardHandler;
+use crate::kafka::producer::KafkaProducer;
+use crate::models::wallet::AuditAction;
+use crate::services::audit_api::AuditApi;
+use crate::services::reward_hold_api::RewardHoldApi;
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::info;
+
+/// Rejects a reward held for manual review, publishes `solana-quiz-reward-failed`
+/// with the `review_rejected` reason, and records it in the audit log.
+///
+/// # Arguments
+/// * `wallet` - User wallet address.
+/// * `quiz_id` - Quiz of the held reward.
+/// * `reason` - Why the reward is rejected.
+/// * `actor` - Who rejects the reward, recorded in the audit log.
+pub async fn run(
+    wallet: String,
+    quiz_id: u64,
+    reason: Option<String>,
+    actor: String,
+) -> Result<()> {
+    let hold_api = RewardHoldApi::new();
+    let key = RewardHoldApi::key(&wallet, quiz_id);
+
+    let Some(held) = hold_api.take(&key).await? else {
+        info!("No reward of quiz {} is held for {}", quiz_id, wallet);
+        return Ok(());
+    };
+
+    let handler = SolanaQuizRewardHandler::new(Arc::new(KafkaProducer::new()));
+    if let Err(err) = handler.reject_held(&held.reward).await {
+        hold_api.restore(&key, held).await?;
+        return Err(err);
+    }
+
+    AuditApi::new()
+        .record(AuditAction::RejectHeldReward, &wallet, &actor, reason, None)
+        .await?;
+
+    info!("Reward of quiz {} for {} rejected", quiz_id, wallet);
+
+    Ok(())
+}
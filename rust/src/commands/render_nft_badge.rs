@@ -0,0 +1,51 @@
+use crate::models::kafka::SolanaQuizNftRequested;
+use crate::services::nft_metadata_api::NftMetadataApi;
+use anyhow::{Result, bail};
+use sha2::{Digest, Sha256};
+use solana_program::pubkey::Pubkey;
+use tracing::info;
+
+/// Regenerates the badge artwork of a streak NFT, so it can be compared
+/// with the hosted one.
+///
+/// # Arguments
+/// * `mint` - Address of the NFT mint.
+/// * `wallet` - Wallet the NFT was minted for.
+/// * `quiz_id` - Quiz that completed the streak.
+/// * `streak_days` - Streak length.
+/// * `achieved_at` - Achievement time (unix seconds).
+/// * `output` - Output file path (defaults to `<mint>.svg` or `<mint>.png`).
+///
+/// Writes the badge and prints its SHA-256 hash.
+pub async fn run(
+    mint: String,
+    wallet: String,
+    quiz_id: u64,
+    streak_days: u8,
+    achieved_at: i64,
+    output: Option<String>,
+) -> Result<()> {
+    let mint_pubkey = mint.parse::<Pubkey>()?;
+
+    let request = SolanaQuizNftRequested {
+        user_wallet: wallet,
+        quiz_id,
+        streak_days,
+        total_quizzes: None,
+        total_questions: None,
+        correct_answers: None,
+        achieved_at: Some(achieved_at),
+    };
+
+    let Some(badge) = NftMetadataApi::new().render_badge(&mint_pubkey, &request)? else {
+        bail!("SOLANA_NFT_BADGE_TEMPLATE_PATH is not set");
+    };
+
+    let output = output.unwrap_or_else(|| format!("{}.{}", mint_pubkey, badge.extension));
+    tokio::fs::write(&output, &badge.bytes).await?;
+
+    info!("Badge: {}", output);
+    info!("SHA-256: {:x}", Sha256::digest(&badge.bytes));
+
+    Ok(())
+}
@@ -8,7 +8,7 @@ use tracing::{error, info};
 /// # Arguments
 /// * `sol_amount` - Amount of SOL to request in the airdrop (in SOL, not lamports).
 /// * `pubkey` - Optional public key (as a string) to receive the airdrop.
-///              If not provided, the authority keypair from the config is used.
+///   If not provided, the fee payer keypair from the config is used.
 ///
 /// # Returns
 /// * `Result<()>` - Returns `Ok(())` if the airdrop request was successful,
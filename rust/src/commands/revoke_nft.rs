@@ -0,0 +1,22 @@
+use crate::services::nft_api::NftApi;
+use anyhow::Result;
+use solana_program::pubkey::Pubkey;
+use tracing::{error, info};
+
+/// Revokes a streak NFT achievement: unverifies it from the collection
+/// and points its metadata at the revoked document.
+///
+/// # Arguments
+/// * `mint` - Address of the NFT mint.
+///
+/// Prints the transaction signature on success, or an error message otherwise.
+pub async fn run(mint: String) -> Result<()> {
+    let mint_pubkey = mint.parse::<Pubkey>()?;
+
+    match NftApi::new().revoke_nft(&mint_pubkey).await {
+        Ok(signature) => info!("Transaction Signature: {}", signature),
+        Err(err) => error!("Error sending transaction: {}", err),
+    }
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use crate::services::solana_api::SolanaApi;
-use anyhow::Result;
+use crate::utils::token_util;
+use anyhow::{Result, bail};
 use solana_program::pubkey::Pubkey;
 use tracing::{error, info};
 
@@ -7,12 +8,23 @@ use tracing::{error, info};
 ///
 /// # Arguments
 /// * `recipient` — recipient's public key as a string.
-/// * `amount` — number of tokens to transfer (without decimals).
+/// * `amount` — number of tokens to transfer, as a decimal (e.g. `5` or `0.5`).
+/// * `base_units` — amount to transfer in base units, instead of `amount`.
 ///
 /// Prints the transaction signature on success, or an error message otherwise.
-pub async fn run(recipient: String, amount: u64) -> Result<()> {
-    match SolanaApi::new()
-        .send_tokens(&recipient.parse::<Pubkey>()?, amount)
+pub async fn run(recipient: String, amount: Option<String>, base_units: Option<u64>) -> Result<()> {
+    let solana_api = SolanaApi::new();
+
+    let base_units = match (amount, base_units) {
+        (None, Some(base_units)) => base_units,
+        (Some(amount), None) => {
+            token_util::parse_token_amount(&amount, solana_api.get_mint_decimals().await?)?
+        }
+        _ => bail!("Either --amount or --base-units must be set"),
+    };
+
+    match solana_api
+        .send_tokens(&recipient.parse::<Pubkey>()?, base_units)
         .await
     {
         Ok(signature) => info!("Transaction Signature: {}", signature),
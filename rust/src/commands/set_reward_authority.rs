@@ -0,0 +1,29 @@
+use crate::services::blockchain_api::BlockchainApi;
+use crate::utils::solana_util;
+use anyhow::Result;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use tracing::{error, info};
+
+/// Sets the authority allowed to apply quiz rewards on-chain.
+///
+/// # Arguments
+/// * `authority` - Optional public key (as a string) of the reward authority.
+///   If not provided, the authority keypair from the config is used.
+///
+/// The transaction is signed by the authority keypair, which must be the upgrade
+/// authority of the program. Prints the transaction signature on success, or an
+/// error message otherwise.
+pub async fn run(authority: Option<String>) -> Result<()> {
+    let authority = match authority {
+        Some(addr) => addr.parse::<Pubkey>()?,
+        None => solana_util::get_authority_keypair().pubkey(),
+    };
+
+    match BlockchainApi::new().set_reward_authority(&authority).await {
+        Ok(signature) => info!("Transaction Signature: {}", signature),
+        Err(err) => error!("Error sending transaction: {}", err),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use crate::models::wallet::AuditAction;
+use crate::services::audit_api::AuditApi;
+use crate::services::solana_api::SolanaApi;
+use anyhow::Result;
+use solana_program::pubkey::Pubkey;
+use tracing::{error, info};
+
+/// Thaws the wallet's token account of the reward token frozen by `freeze-token-account`,
+/// and records it in the audit log.
+///
+/// # Arguments
+/// * `wallet` - User wallet address.
+/// * `reason` - Why the token account is thawed.
+/// * `actor` - Who thaws the token account, recorded in the audit log.
+///
+/// Prints the transaction signature on success, or an error message otherwise.
+pub async fn run(wallet: String, reason: Option<String>, actor: String) -> Result<()> {
+    let wallet_pubkey = wallet.parse::<Pubkey>()?;
+
+    match SolanaApi::new().thaw_token_account(&wallet_pubkey).await {
+        Ok(signature) => {
+            info!("Transaction Signature: {}", signature);
+
+            AuditApi::new()
+                .record(
+                    AuditAction::ThawTokenAccount,
+                    &wallet,
+                    &actor,
+                    reason,
+                    Some(signature.to_string()),
+                )
+                .await?;
+        }
+        Err(err) => error!("Error sending transaction: {}", err),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use crate::services::wallet_blocklist_api::WalletBlocklistApi;
+use anyhow::Result;
+use tracing::info;
+
+/// Unblocks a wallet blocked by `block-wallet` or the control topic,
+/// and records it in the audit log.
+///
+/// # Arguments
+/// * `wallet` - User wallet address.
+/// * `reason` - Why the wallet is unblocked.
+/// * `actor` - Who unblocks the wallet, recorded in the audit log.
+pub async fn run(wallet: String, reason: Option<String>, actor: String) -> Result<()> {
+    match WalletBlocklistApi::new()
+        .unblock(&wallet, reason, &actor)
+        .await?
+    {
+        true => info!("Wallet {} unblocked", wallet),
+        false => info!("Wallet {} is not blocked", wallet),
+    }
+
+    Ok(())
+}
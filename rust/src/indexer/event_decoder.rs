@@ -0,0 +1,203 @@
+use crate::services::blockchain_api::solana_quiz_rewards::events::{
+    QuizResultsUpdated, StreakAchieved,
+};
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use solana_program::pubkey::Pubkey;
+
+/// Anchor events emitted by the quiz rewards program.
+#[derive(Debug)]
+pub enum ProgramEvent {
+    QuizResultsUpdated(QuizResultsUpdated),
+    StreakAchieved(StreakAchieved),
+}
+
+/// Decodes all Anchor events emitted by `program_id` from transaction log messages.
+///
+/// Events are logged as `Program data: <base64>` lines. Only lines logged while
+/// `program_id` is the currently executing program are decoded, so events from
+/// other programs invoked in the same transaction are ignored.
+pub fn decode_program_events(logs: &[String], program_id: &Pubkey) -> Vec<ProgramEvent> {
+    let program_id = program_id.to_string();
+    let mut call_stack: Vec<&str> = vec![];
+    let mut events = vec![];
+
+    for log in logs {
+        if let Some(data) = log.strip_prefix("Program data: ") {
+            if call_stack.last() == Some(&program_id.as_str())
+                && let Some(event) = decode_event(data)
+            {
+                events.push(event);
+            }
+        } else if let Some(rest) = log.strip_prefix("Program ") {
+            let mut parts = rest.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some(id), Some("invoke")) => call_stack.push(id),
+                (Some(_), Some("success")) | (Some(_), Some("failed:")) => {
+                    call_stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    events
+}
+
+/// Decodes a single base64 encoded event, returning `None` for unknown events.
+fn decode_event(data: &str) -> Option<ProgramEvent> {
+    let bytes = STANDARD.decode(data).ok()?;
+    if bytes.len() < 8 {
+        return None;
+    }
+
+    let (discriminator, mut payload) = bytes.split_at(8);
+
+    if discriminator == QuizResultsUpdated::DISCRIMINATOR {
+        QuizResultsUpdated::deserialize(&mut payload)
+            .ok()
+            .map(ProgramEvent::QuizResultsUpdated)
+    } else if discriminator == StreakAchieved::DISCRIMINATOR {
+        StreakAchieved::deserialize(&mut payload)
+            .ok()
+            .map(ProgramEvent::StreakAchieved)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AnchorSerialize;
+
+    const OTHER_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+    fn program_id() -> String {
+        crate::services::blockchain_api::solana_quiz_rewards::ID.to_string()
+    }
+
+    fn streak_data(quiz_id: u64, streak: u8) -> String {
+        let event = StreakAchieved {
+            user: Pubkey::new_unique(),
+            quiz_id,
+            streak,
+        };
+        let mut bytes = StreakAchieved::DISCRIMINATOR.to_vec();
+        event.serialize(&mut bytes).unwrap();
+
+        format!("Program data: {}", STANDARD.encode(bytes))
+    }
+
+    fn invoke(program: &str, depth: u8) -> String {
+        format!("Program {} invoke [{}]", program, depth)
+    }
+
+    fn success(program: &str) -> String {
+        format!("Program {} success", program)
+    }
+
+    fn decode(logs: &[String]) -> Vec<u64> {
+        decode_program_events(logs, &program_id().parse().unwrap())
+            .into_iter()
+            .map(|event| match event {
+                ProgramEvent::StreakAchieved(event) => event.quiz_id,
+                ProgramEvent::QuizResultsUpdated(event) => event.quiz_id,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn decodes_events_of_the_program() {
+        let program = program_id();
+        let logs = vec![
+            invoke(&program, 1),
+            "Program log: Instruction: UpdateQuizResults".to_string(),
+            streak_data(1, 3),
+            format!("Program {} consumed 5000 of 200000 compute units", program),
+            success(&program),
+        ];
+
+        let events = decode_program_events(&logs, &program.parse().unwrap());
+
+        assert_eq!(events.len(), 1);
+        let ProgramEvent::StreakAchieved(event) = &events[0] else {
+            panic!("expected a StreakAchieved event");
+        };
+        assert_eq!((event.quiz_id, event.streak), (1, 3));
+    }
+
+    #[test]
+    fn follows_the_executing_program_through_nested_invokes() {
+        let program = program_id();
+        let logs = vec![
+            invoke(&program, 1),
+            streak_data(1, 1),
+            // CPI into another program, its data is not ours
+            invoke(OTHER_PROGRAM, 2),
+            streak_data(2, 1),
+            success(OTHER_PROGRAM),
+            // Back in the program after the CPI
+            streak_data(3, 1),
+            success(&program),
+        ];
+
+        assert_eq!(decode(&logs), vec![1, 3]);
+    }
+
+    #[test]
+    fn decodes_the_program_invoked_through_cpi() {
+        let program = program_id();
+        let logs = vec![
+            invoke(OTHER_PROGRAM, 1),
+            streak_data(1, 1),
+            invoke(&program, 2),
+            streak_data(2, 1),
+            success(&program),
+            streak_data(3, 1),
+            format!(
+                "Program {} failed: custom program error: 0x1",
+                OTHER_PROGRAM
+            ),
+            // Data logged outside of any invocation
+            streak_data(4, 1),
+        ];
+
+        assert_eq!(decode(&logs), vec![2]);
+    }
+
+    #[test]
+    fn ignores_logs_of_other_programs() {
+        let logs = vec![
+            invoke(OTHER_PROGRAM, 1),
+            streak_data(1, 1),
+            success(OTHER_PROGRAM),
+        ];
+
+        assert!(decode(&logs).is_empty());
+    }
+
+    #[test]
+    fn skips_unknown_and_invalid_data() {
+        let program = program_id();
+        let mut truncated = StreakAchieved::DISCRIMINATOR.to_vec();
+        truncated.push(1);
+
+        let logs = vec![
+            invoke(&program, 1),
+            // Unknown discriminator
+            format!("Program data: {}", STANDARD.encode([7u8; 48])),
+            // Too short for a discriminator
+            format!("Program data: {}", STANDARD.encode([1u8; 4])),
+            // Known discriminator with a truncated payload
+            format!("Program data: {}", STANDARD.encode(truncated)),
+            // Not base64
+            "Program data: not base64!".to_string(),
+            streak_data(5, 1),
+            success(&program),
+        ];
+
+        assert_eq!(decode(&logs), vec![5]);
+    }
+}
@@ -0,0 +1,15 @@
+use crate::indexer::program_indexer::ProgramIndexer;
+use crate::kafka::producer::KafkaProducer;
+use anyhow::Result;
+use std::sync::Arc;
+
+pub mod event_decoder;
+pub mod program_indexer;
+
+/// Starts the on-chain program indexer and propagates any errors.
+/// This function typically runs for the lifetime of the application.
+pub async fn run() -> Result<()> {
+    let kafka_producer = Arc::new(KafkaProducer::new());
+
+    ProgramIndexer::new(kafka_producer).run().await
+}
@@ -0,0 +1,313 @@
+use crate::indexer::event_decoder::{ProgramEvent, decode_program_events};
+use crate::kafka::producer::KafkaProducer;
+use crate::models::kafka::{
+    SolanaQuizNftRequested, SolanaQuizResultsUpdated, SolanaQuizStats, SolanaQuizStreakAchieved,
+};
+use crate::services::blockchain_api::solana_quiz_rewards;
+use crate::services::blockchain_api::solana_quiz_rewards::types::QuizStats;
+use crate::storage::json_store::JsonStore;
+use crate::utils::solana_util;
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::UiTransactionEncoding;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// Maximum number of signatures requested per RPC page.
+const SIGNATURES_PAGE_LIMIT: usize = 1000;
+
+/// Last transaction of the program that was fully indexed.
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct IndexerCheckpoint {
+    pub signature: Option<String>,
+    pub slot: u64,
+}
+
+/// Polls the quiz rewards program for new transactions, decodes the emitted
+/// Anchor events and republishes them to Kafka.
+///
+/// Progress is checkpointed by signature and slot after every transaction,
+/// so a restarted indexer continues where it stopped.
+pub struct ProgramIndexer {
+    rpc_client: RpcClient,
+    producer: Arc<KafkaProducer>,
+    checkpoint_store: JsonStore<IndexerCheckpoint>,
+    poll_interval: Duration,
+    start_signature: Option<Signature>,
+}
+
+impl ProgramIndexer {
+    /// Creates a new indexer using environment variables.
+    pub fn new(producer: Arc<KafkaProducer>) -> Self {
+        Self {
+            rpc_client: solana_util::create_rpc_client(),
+            producer,
+            checkpoint_store: JsonStore::new("indexer_checkpoint"),
+            poll_interval: solana_util::get_solana_indexer_poll_interval(),
+            start_signature: solana_util::get_solana_indexer_start_signature(),
+        }
+    }
+
+    /// Polls for new program transactions until the task is aborted.
+    pub async fn run(&self) -> Result<()> {
+        let mut checkpoint = self.checkpoint_store.load().await?;
+
+        if checkpoint.signature.is_none() {
+            checkpoint = self.start_checkpoint().await?;
+            self.checkpoint_store.save(&checkpoint).await?;
+        }
+
+        info!("Starting program indexer from {:?}", checkpoint);
+
+        loop {
+            if let Err(e) = self.poll(&mut checkpoint).await {
+                error!("Program indexer error: {:?}", e);
+            }
+
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    /// Indexes all transactions newer than the checkpoint, oldest first.
+    async fn poll(&self, checkpoint: &mut IndexerCheckpoint) -> Result<()> {
+        for status in self.fetch_new_signatures(checkpoint).await? {
+            // Failed transactions are rolled back and emit no events
+            if status.err.is_none() {
+                let logs = self.fetch_logs(&status.signature.parse()?).await?;
+
+                let events = decode_program_events(&logs, &solana_quiz_rewards::ID);
+
+                for event in &events {
+                    self.publish(event, &status.signature, status.slot).await?;
+                }
+
+                self.request_nfts(&events, status.block_time).await?;
+            }
+
+            checkpoint.signature = Some(status.signature);
+            checkpoint.slot = status.slot;
+            self.checkpoint_store.save(checkpoint).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the checkpoint of a first start: the configured start signature, or else
+    /// the latest program transaction, so past events are not published again.
+    async fn start_checkpoint(&self) -> Result<IndexerCheckpoint> {
+        let status = match self.start_signature {
+            Some(signature) => self
+                .rpc_client
+                .get_signature_statuses_with_history(&[signature])
+                .await?
+                .value
+                .into_iter()
+                .next()
+                .flatten()
+                .map(|status| (signature, status.slot)),
+            None => {
+                let latest = self
+                    .rpc_client
+                    .get_signatures_for_address_with_config(
+                        &solana_quiz_rewards::ID,
+                        GetConfirmedSignaturesForAddress2Config {
+                            before: None,
+                            until: None,
+                            limit: Some(1),
+                            commitment: Some(CommitmentConfig::confirmed()),
+                        },
+                    )
+                    .await?;
+
+                match latest.into_iter().next() {
+                    Some(status) => Some((status.signature.parse()?, status.slot)),
+                    None => None,
+                }
+            }
+        };
+
+        Ok(match status {
+            Some((signature, slot)) => IndexerCheckpoint {
+                signature: Some(signature.to_string()),
+                slot,
+            },
+            None => match self.start_signature {
+                Some(signature) => bail!("Start signature {} was not found", signature),
+                // The program has no transactions yet, so there is no history to skip
+                None => IndexerCheckpoint::default(),
+            },
+        })
+    }
+
+    /// Fetches the signatures of program transactions newer than the checkpoint.
+    async fn fetch_new_signatures(
+        &self,
+        checkpoint: &IndexerCheckpoint,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let until = match &checkpoint.signature {
+            Some(signature) => Some(signature.parse::<Signature>()?),
+            None => None,
+        };
+
+        let mut signatures = vec![];
+        let mut before = None;
+
+        // Signatures are returned newest first, page backwards until the checkpoint
+        loop {
+            let page = self
+                .rpc_client
+                .get_signatures_for_address_with_config(
+                    &solana_quiz_rewards::ID,
+                    GetConfirmedSignaturesForAddress2Config {
+                        before,
+                        until,
+                        limit: Some(SIGNATURES_PAGE_LIMIT),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                    },
+                )
+                .await?;
+
+            let page_len = page.len();
+            before = match page.last() {
+                Some(status) => Some(status.signature.parse::<Signature>()?),
+                None => None,
+            };
+            signatures.extend(page);
+
+            if page_len < SIGNATURES_PAGE_LIMIT {
+                break;
+            }
+        }
+
+        signatures.reverse();
+
+        Ok(signatures)
+    }
+
+    /// Fetches the log messages of a confirmed transaction.
+    async fn fetch_logs(&self, signature: &Signature) -> Result<Vec<String>> {
+        let transaction = self
+            .rpc_client
+            .get_transaction_with_config(
+                signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Json),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await?;
+
+        let logs = transaction
+            .transaction
+            .meta
+            .and_then(|meta| Option::<Vec<String>>::from(meta.log_messages))
+            .unwrap_or_default();
+
+        Ok(logs)
+    }
+
+    /// Republishes a decoded program event to its Kafka topic.
+    async fn publish(&self, event: &ProgramEvent, signature: &str, slot: u64) -> Result<()> {
+        match event {
+            ProgramEvent::QuizResultsUpdated(event) => {
+                let message = SolanaQuizResultsUpdated {
+                    user_wallet: event.user.to_string(),
+                    quiz_id: event.quiz_id,
+                    total_questions: event.total_questions,
+                    correct_answers: event.correct_answers,
+                    earned_tokens: event.earned_tokens,
+                    before: quiz_stats_message(&event.before),
+                    after: quiz_stats_message(&event.after),
+                    signature: signature.to_string(),
+                    slot,
+                };
+
+                self.producer
+                    .send(
+                        "solana-quiz-results-updated",
+                        &format!("wallet_{}", message.user_wallet),
+                        &serde_json::to_string(&message)?,
+                    )
+                    .await
+            }
+            ProgramEvent::StreakAchieved(event) => {
+                let message = SolanaQuizStreakAchieved {
+                    user_wallet: event.user.to_string(),
+                    quiz_id: event.quiz_id,
+                    streak: event.streak,
+                    signature: signature.to_string(),
+                    slot,
+                };
+
+                self.producer
+                    .send(
+                        "solana-quiz-streak-achieved",
+                        &format!("wallet_{}", message.user_wallet),
+                        &serde_json::to_string(&message)?,
+                    )
+                    .await
+            }
+        }
+    }
+
+    /// Requests streak NFTs for all `StreakAchieved` events of a transaction.
+    ///
+    /// Reaching the streak goal on-chain triggers the NFT reward flow. The stats
+    /// for the NFT metadata come from the `QuizResultsUpdated` event of the same quiz.
+    async fn request_nfts(&self, events: &[ProgramEvent], block_time: Option<i64>) -> Result<()> {
+        for event in events {
+            let ProgramEvent::StreakAchieved(streak) = event else {
+                continue;
+            };
+
+            let stats = events.iter().find_map(|event| match event {
+                ProgramEvent::QuizResultsUpdated(results)
+                    if results.user == streak.user && results.quiz_id == streak.quiz_id =>
+                {
+                    Some(&results.after)
+                }
+                _ => None,
+            });
+
+            let request = SolanaQuizNftRequested {
+                user_wallet: streak.user.to_string(),
+                quiz_id: streak.quiz_id,
+                streak_days: streak.streak,
+                total_quizzes: stats.map(|stats| stats.total_quizzes),
+                total_questions: stats.map(|stats| stats.total_questions),
+                correct_answers: stats.map(|stats| stats.correct_answers),
+                achieved_at: block_time,
+            };
+
+            self.producer
+                .send(
+                    "solana-quiz-nft-requested",
+                    &format!("wallet_{}", request.user_wallet),
+                    &serde_json::to_string(&request)?,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts on-chain quiz stats into their Kafka message representation.
+fn quiz_stats_message(stats: &QuizStats) -> SolanaQuizStats {
+    SolanaQuizStats {
+        last_quiz_day: stats.last_quiz_day,
+        streak: stats.streak,
+        total_quizzes: stats.total_quizzes,
+        total_questions: stats.total_questions,
+        correct_answers: stats.correct_answers,
+        earned_tokens: stats.earned_tokens,
+    }
+}
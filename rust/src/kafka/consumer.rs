@@ -1,11 +1,15 @@
 use crate::kafka::config::create_kafka_consumer_config;
-use crate::kafka::consumer_handlers::KafkaConsumerHandler;
+use crate::kafka::consumer_handlers::solana_quiz_nft::SolanaQuizNftHandler;
 use crate::kafka::consumer_handlers::solana_quiz_reward::SolanaQuizRewardHandler;
+use crate::kafka::consumer_handlers::solana_quiz_wallet_control::SolanaQuizWalletControlHandler;
+use crate::kafka::consumer_handlers::{KafkaConsumerHandler, KafkaHeaders};
 use crate::kafka::producer::KafkaProducer;
 use rdkafka::Message;
 use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+use rdkafka::message::{BorrowedMessage, Headers};
 use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::time::{self, Instant};
 use tracing::error;
 
 /// KafkaConsumer is a wrapper around a Kafka StreamConsumer.
@@ -33,11 +37,19 @@ impl KafkaConsumer {
     /// Returns a list of handlers for Kafka topics.
     /// Each handler implements the `KafkaConsumerHandler` trait.
     pub fn get_handlers(&self, producer: Arc<KafkaProducer>) -> Vec<Box<dyn KafkaConsumerHandler>> {
-        vec![Box::new(SolanaQuizRewardHandler::new(producer.clone()))]
+        vec![
+            Box::new(SolanaQuizRewardHandler::new(producer.clone())),
+            Box::new(SolanaQuizNftHandler::new(producer.clone())),
+            Box::new(SolanaQuizWalletControlHandler::new()),
+        ]
     }
 
     /// Subscribes to topics of all handlers and continuously consumes messages.
     /// Dispatches each message to the appropriate handler based on the topic.
+    ///
+    /// Messages of handlers with a `batch_window` are collected until the window ends
+    /// or the batch is full, then dispatched together. Offsets are committed once
+    /// the messages are handled.
     pub async fn consume_events(
         &self,
         consumer: &StreamConsumer,
@@ -57,39 +69,133 @@ impl KafkaConsumer {
             map.insert(handler.topic(), handler);
         }
 
+        // Batches being collected per topic, with the time they are dispatched at
+        let mut batches: HashMap<&str, (Instant, Vec<BorrowedMessage>)> = HashMap::new();
+
         // Infinite loop to consume messages
         loop {
-            match consumer.recv().await {
-                Ok(message) => {
+            let deadline = batches.values().map(|(deadline, _)| *deadline).min();
+            let received = match deadline {
+                Some(deadline) => time::timeout_at(deadline, consumer.recv()).await.ok(),
+                None => Some(consumer.recv().await),
+            };
+
+            match received {
+                // A batch window ended
+                None => {
+                    let now = Instant::now();
+                    let due: Vec<&str> = batches
+                        .iter()
+                        .filter(|(_, (deadline, _))| *deadline <= now)
+                        .map(|(topic, _)| *topic)
+                        .collect();
+
+                    for topic in due {
+                        if let (Some(handler), Some((_, messages))) =
+                            (map.get(topic), batches.remove(topic))
+                        {
+                            Self::dispatch_batch(consumer, handler.as_ref(), messages).await;
+                        }
+                    }
+                }
+                Some(Ok(message)) => {
                     match message.payload_view::<str>() {
                         Some(Ok(payload)) => {
                             let topic = message.topic();
 
                             // Dispatch to the correct handler
-                            if let Some(handler) = map.get(topic) {
-                                if let Err(e) = handler.handle(payload).await {
-                                    error!("Error while handling message: {:?}", e);
+                            match map.get(topic) {
+                                Some(handler) => {
+                                    if let Some(window) = handler.batch_window() {
+                                        let (_, messages) = batches
+                                            .entry(handler.topic())
+                                            .or_insert_with(|| (Instant::now() + window, vec![]));
+                                        messages.push(message);
+
+                                        if messages.len() >= handler.batch_max_size()
+                                            && let Some((_, messages)) =
+                                                batches.remove(handler.topic())
+                                        {
+                                            Self::dispatch_batch(
+                                                consumer,
+                                                handler.as_ref(),
+                                                messages,
+                                            )
+                                            .await;
+                                        }
+                                        continue;
+                                    }
+
+                                    let headers = Self::message_headers(&message);
+                                    if let Err(e) = handler.handle(payload, &headers).await {
+                                        error!("Error while handling message: {:?}", e);
+                                    }
                                 }
-                            } else {
-                                error!("No handler for topic {}", topic);
+                                None => error!("No handler for topic {}", topic),
                             }
 
                             // Commit the message offset
                             if let Err(e) = consumer.commit_message(&message, CommitMode::Async) {
                                 error!("Failed to commit message: {}", e);
                             }
-
-                            payload
                         }
                         Some(Err(e)) => {
                             error!("Error while deserializing message payload: {:?}", e);
-                            ""
                         }
-                        None => "",
+                        None => {}
                     };
                 }
-                Err(e) => error!("Kafka Consumer Error: {}", e),
+                Some(Err(e)) => error!("Kafka Consumer Error: {}", e),
+            }
+        }
+    }
+
+    /// Dispatches a batch of messages to their handler, then commits their offsets.
+    async fn dispatch_batch(
+        consumer: &StreamConsumer,
+        handler: &dyn KafkaConsumerHandler,
+        messages: Vec<BorrowedMessage<'_>>,
+    ) {
+        // Messages are only batched once their payload was read as UTF-8
+        let batch: Vec<(&str, KafkaHeaders)> = messages
+            .iter()
+            .filter_map(|message| {
+                let payload = message.payload_view::<str>()?.ok()?;
+                Some((payload, Self::message_headers(message)))
+            })
+            .collect();
+
+        if let Err(e) = handler.handle_batch(&batch).await {
+            error!("Error while handling messages: {:?}", e);
+        }
+
+        // Commit the message offsets
+        for message in &messages {
+            if let Err(e) = consumer.commit_message(message, CommitMode::Async) {
+                error!("Failed to commit message: {}", e);
             }
         }
     }
+
+    /// Returns the headers of a message.
+    fn message_headers(message: &BorrowedMessage) -> KafkaHeaders {
+        message
+            .headers()
+            .map(|headers| {
+                headers
+                    .iter()
+                    .filter_map(|header| {
+                        let value = String::from_utf8_lossy(header.value?);
+                        Some((header.key.to_string(), value.into_owned()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl Default for KafkaConsumer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -1,16 +1,25 @@
-use crate::models::kafka::SolanaQuizReward;
-use crate::services::blockchain_api::solana_quiz_rewards::accounts::QuizUserData;
+use crate::kafka::producer::KafkaProducer;
+use crate::services::reward_budget_api::RewardBudgetApi;
 use anyhow::Result;
 use async_trait::async_trait;
-use solana_sdk::signature::Signature;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{error, info};
 
+pub mod solana_quiz_nft;
 pub mod solana_quiz_reward;
+pub mod solana_quiz_wallet_control;
+
+/// Headers of a Kafka message (values that are not valid UTF-8 are replaced lossily).
+pub type KafkaHeaders = HashMap<String, String>;
 
 /// A trait that defines a Kafka consumer handler for a specific topic.
 ///
 /// Each handler is responsible for:
 /// - Returning the topic name it listens to (`topic()`).
 /// - Processing incoming messages for that topic asynchronously (`handle()`).
+/// - Optionally, processing messages collected over a short window together
+///   (`batch_window()`, `batch_max_size()` and `handle_batch()`).
 ///
 /// `Send + Sync` ensures that handlers can be safely shared across threads,
 /// which is required when running them inside async executors like Tokio.
@@ -22,15 +31,63 @@ pub trait KafkaConsumerHandler: Send + Sync {
     /// Returns the Kafka topic that this handler is responsible for.
     fn topic(&self) -> &'static str;
 
-    /// Handles an incoming Kafka message payload asynchronously.
-    async fn handle(&self, payload: &str) -> Result<()>;
+    /// Handles an incoming Kafka message payload (and its headers) asynchronously.
+    async fn handle(&self, payload: &str, headers: &KafkaHeaders) -> Result<()>;
+
+    /// Returns how long messages are collected before they are handled together
+    /// by `handle_batch`, or `None` (the default) to handle every message on its own.
+    fn batch_window(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Returns the maximum number of messages handled together (defaults to `1`).
+    fn batch_max_size(&self) -> usize {
+        1
+    }
+
+    /// Handles messages collected over `batch_window` (payloads and headers).
+    /// By default, each message is handled on its own.
+    async fn handle_batch(&self, messages: &[(&str, KafkaHeaders)]) -> Result<()> {
+        for (payload, headers) in messages {
+            if let Err(e) = self.handle(payload, headers).await {
+                error!("Error while handling message: {:?}", e);
+            }
+        }
+
+        Ok(())
+    }
+}
 
-    /// Sends the earned tokens to the user's wallet via on-chain Solana transaction.
-    async fn send_tokens_on_chain(&self, reward: &SolanaQuizReward) -> Result<QuizUserData>;
+/// Republishes the messages deferred by an exhausted budget on a previous day,
+/// so they are handled again against the budgets of the new day.
+pub async fn republish_deferred(
+    producer: &KafkaProducer,
+    budget_api: &RewardBudgetApi,
+) -> Result<()> {
+    for message in budget_api.take_due_deferred().await? {
+        info!("Republishing deferred message to {}", message.topic);
 
-    /// Sends the earned tokens to the user's wallet via an off-chain mechanism.
-    async fn send_tokens_off_chain(&self, reward: &SolanaQuizReward) -> Result<Signature>;
+        // Keep the message for another day rather than losing it
+        if let Err(e) = producer
+            .send_with_headers(
+                &message.topic,
+                &message.key,
+                &message.payload,
+                &message.headers,
+            )
+            .await
+        {
+            error!("Failed to republish deferred message: {:?}", e);
+            budget_api
+                .defer(
+                    &message.topic,
+                    &message.key,
+                    &message.payload,
+                    &message.headers,
+                )
+                .await?;
+        }
+    }
 
-    /// Sends NFT rewards to the user based on their quiz performance and streak.
-    async fn send_nft_rewards(&self, reward: &SolanaQuizReward) -> Result<()>;
+    Ok(())
 }
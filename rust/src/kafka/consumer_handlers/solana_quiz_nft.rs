@@ -0,0 +1,545 @@
+use crate::kafka::consumer_handlers::{KafkaConsumerHandler, KafkaHeaders, republish_deferred};
+use crate::kafka::producer::KafkaProducer;
+use crate::models::kafka::{
+    SolanaQuizNftMinted, SolanaQuizNftRequested, SolanaQuizNftUpdated, SolanaQuizRewardFailed,
+};
+use crate::models::nft::{NFT_PASSPORTS_STORE, NftMintProgress, NftMintStep};
+use crate::models::reward::{BudgetExceededAction, RewardBudget};
+use crate::services::nft_api::NftApi;
+use crate::services::nft_metadata_api::NftMetadataApi;
+use crate::services::reward_budget_api::RewardBudgetApi;
+use crate::storage::json_store::JsonStore;
+use crate::utils::solana_util;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// Number of attempts to complete an NFT mint before giving up on a message.
+const MAX_MINT_ATTEMPTS: u32 = 3;
+
+pub struct SolanaQuizNftHandler {
+    producer: Arc<KafkaProducer>,
+    store: JsonStore<HashMap<String, NftMintProgress>>,
+    passports: JsonStore<HashMap<String, String>>,
+    budget_api: RewardBudgetApi,
+}
+
+impl SolanaQuizNftHandler {
+    pub fn new(producer: Arc<KafkaProducer>) -> Self {
+        Self {
+            producer,
+            store: JsonStore::new("nft_mints"),
+            passports: JsonStore::new(NFT_PASSPORTS_STORE),
+            budget_api: RewardBudgetApi::new(),
+        }
+    }
+
+    /// Mints the requested NFT, resuming from the last step that succeeded.
+    ///
+    /// Progress is persisted after every step, so a failed or interrupted
+    /// flow never creates a second mint for the same achievement.
+    ///
+    /// In passport mode, a user who already holds a passport gets it updated instead.
+    /// A new mint that exceeds the daily NFT budget is deferred or rejected.
+    async fn mint_nft(&self, request: &SolanaQuizNftRequested) -> Result<()> {
+        let key = mint_key(request);
+        let recipient_pubkey = request.user_wallet.parse::<Pubkey>()?;
+
+        let mut progress = self.store.load().await?.remove(&key).unwrap_or_default();
+
+        if progress.step == NftMintStep::Completed {
+            info!("NFT for {} already minted, skipping", key);
+            return Ok(());
+        }
+
+        let passport_mint = match solana_util::get_nft_passport() {
+            true => self.passports.load().await?.remove(&request.user_wallet),
+            false => None,
+        };
+
+        // The user already holds a passport minted for another achievement
+        if let Some(passport_mint) = &passport_mint
+            && progress.mint_keypair.is_none()
+        {
+            return self
+                .update_passport(&key, &mut progress, request, &passport_mint.parse()?)
+                .await;
+        }
+
+        let nft_api = NftApi::new();
+
+        // Generate the mint keypair up front, so every attempt targets the same mint
+        if progress.mint_keypair.is_none() {
+            if let Some(budget) = self.budget_api.reserve_nft().await? {
+                return self.budget_exceeded(request, budget).await;
+            }
+
+            progress.mint_keypair = Some(Keypair::new().to_base58_string());
+            self.save_step(&key, &mut progress, NftMintStep::Requested, None)
+                .await?;
+        }
+
+        let mint_keypair = Keypair::from_base58_string(
+            progress
+                .mint_keypair
+                .as_deref()
+                .context("Mint keypair is missing from NFT mint progress")?,
+        );
+        let mint_pubkey = mint_keypair.pubkey();
+
+        // Render the per-achievement metadata (deterministic, so a resumed mint gets the same)
+        let metadata = NftMetadataApi::new().prepare(&mint_pubkey, request).await?;
+
+        // Fresh mint: create everything in one (or two) transactions
+        if progress.step < NftMintStep::MintCreated && !nft_api.account_exists(&mint_pubkey).await?
+        {
+            let signatures = nft_api
+                .mint_nft_atomic(&mint_keypair, &recipient_pubkey, &metadata)
+                .await?;
+            info!("✅ NFT Mint: {}, Signatures: {:?}", mint_pubkey, signatures);
+
+            progress
+                .signatures
+                .extend(signatures.iter().map(|signature| signature.to_string()));
+            self.save_step(&key, &mut progress, NftMintStep::MetadataCreated, None)
+                .await?;
+        }
+
+        // Otherwise resume an interrupted mint step by step
+        // 1) Mint already exists on-chain
+        if progress.step < NftMintStep::MintCreated {
+            self.save_step(&key, &mut progress, NftMintStep::MintCreated, None)
+                .await?;
+        }
+
+        // 2) Create user's ATA
+        if progress.step < NftMintStep::TokenAccountCreated {
+            let signature = nft_api
+                .create_token_account(&mint_pubkey, &recipient_pubkey)
+                .await?;
+            info!("✅ Token Account, Signature: {}", signature);
+
+            self.save_step(
+                &key,
+                &mut progress,
+                NftMintStep::TokenAccountCreated,
+                Some(signature),
+            )
+            .await?;
+        }
+
+        // 3) Mint 1 token to recipient (unless a previous attempt already landed)
+        if progress.step < NftMintStep::TokenMinted {
+            let signature = if nft_api
+                .get_token_amount(&mint_pubkey, &recipient_pubkey)
+                .await?
+                == 0
+            {
+                let signature = nft_api.mint_token(&mint_keypair, &recipient_pubkey).await?;
+                info!("✅ Token (NFT), Signature: {}", signature);
+                Some(signature)
+            } else {
+                None
+            };
+
+            self.save_step(&key, &mut progress, NftMintStep::TokenMinted, signature)
+                .await?;
+        }
+
+        // 4) Create metadata + master edition (unless a previous attempt already landed)
+        if progress.step < NftMintStep::MetadataCreated {
+            let signature = if !nft_api.metadata_exists(&mint_pubkey).await? {
+                let signature = nft_api.create_metadata(&mint_keypair, &metadata).await?;
+                info!("✅ Metadata, Signature: {}", signature);
+                Some(signature)
+            } else {
+                None
+            };
+
+            self.save_step(&key, &mut progress, NftMintStep::MetadataCreated, signature)
+                .await?;
+        }
+
+        // In passport mode, the first minted NFT becomes the user's passport once it is confirmed
+        if solana_util::get_nft_passport() && passport_mint.is_none() {
+            let mut passports = self.passports.load().await?;
+            passports
+                .entry(request.user_wallet.clone())
+                .or_insert_with(|| mint_pubkey.to_string());
+            self.passports.save(&passports).await?;
+        }
+
+        // 5) Notify other services about the minted NFT
+        let minted = SolanaQuizNftMinted {
+            user_wallet: request.user_wallet.clone(),
+            quiz_id: request.quiz_id,
+            mint_address: mint_pubkey.to_string(),
+        };
+
+        self.producer
+            .send(
+                "solana-quiz-nft-minted",
+                &format!("wallet_{}", request.user_wallet),
+                &serde_json::to_string(&minted)?,
+            )
+            .await?;
+
+        self.save_step(&key, &mut progress, NftMintStep::Completed, None)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Defers or rejects an NFT request that exceeds an emission budget.
+    async fn budget_exceeded(
+        &self,
+        request: &SolanaQuizNftRequested,
+        budget: RewardBudget,
+    ) -> Result<()> {
+        let key = format!("wallet_{}", request.user_wallet);
+
+        match self.budget_api.action() {
+            // NFT requests are not held for review, so they are deferred instead
+            BudgetExceededAction::Defer | BudgetExceededAction::Hold => {
+                warn!(
+                    "NFT for quiz {} of {} exceeds the {} budget, deferring",
+                    request.quiz_id,
+                    request.user_wallet,
+                    budget.name()
+                );
+
+                self.budget_api
+                    .defer(
+                        self.topic(),
+                        &key,
+                        &serde_json::to_string(request)?,
+                        &KafkaHeaders::new(),
+                    )
+                    .await
+            }
+            BudgetExceededAction::Reject => {
+                warn!(
+                    "NFT for quiz {} of {} exceeds the {} budget, rejecting",
+                    request.quiz_id,
+                    request.user_wallet,
+                    budget.name()
+                );
+
+                let failed = SolanaQuizRewardFailed {
+                    user_id: None,
+                    user_wallet: request.user_wallet.clone(),
+                    quiz_id: request.quiz_id,
+                    reason: format!("{}_budget_exceeded", budget.name()),
+                };
+
+                self.producer
+                    .send(
+                        "solana-quiz-reward-failed",
+                        &key,
+                        &serde_json::to_string(&failed)?,
+                    )
+                    .await
+            }
+        }
+    }
+
+    /// Updates the user's passport NFT metadata for a new milestone.
+    async fn update_passport(
+        &self,
+        key: &str,
+        progress: &mut NftMintProgress,
+        request: &SolanaQuizNftRequested,
+        mint_pubkey: &Pubkey,
+    ) -> Result<()> {
+        if progress.step < NftMintStep::MetadataCreated {
+            let metadata = NftMetadataApi::new().prepare(mint_pubkey, request).await?;
+
+            let signature = NftApi::new()
+                .update_metadata(mint_pubkey, &metadata)
+                .await?;
+            info!(
+                "✅ Passport {} updated, Signature: {}",
+                mint_pubkey, signature
+            );
+
+            self.save_step(key, progress, NftMintStep::MetadataCreated, Some(signature))
+                .await?;
+        }
+
+        // Notify other services about the updated passport
+        let updated = SolanaQuizNftUpdated {
+            user_wallet: request.user_wallet.clone(),
+            quiz_id: request.quiz_id,
+            mint_address: mint_pubkey.to_string(),
+        };
+
+        self.producer
+            .send(
+                "solana-quiz-nft-updated",
+                &format!("wallet_{}", request.user_wallet),
+                &serde_json::to_string(&updated)?,
+            )
+            .await?;
+
+        self.save_step(key, progress, NftMintStep::Completed, None)
+            .await
+    }
+
+    /// Keeps a request whose mint failed every attempt until the next budget day,
+    /// see `defer_mint`.
+    async fn retry_later(&self, request: &SolanaQuizNftRequested) -> Result<()> {
+        let mint_keypair = self
+            .store
+            .load()
+            .await?
+            .remove(&mint_key(request))
+            .and_then(|progress| progress.mint_keypair);
+
+        let mint_created = match mint_keypair {
+            Some(mint_keypair) => {
+                let mint_pubkey = Keypair::from_base58_string(&mint_keypair).pubkey();
+                NftApi::new().account_exists(&mint_pubkey).await?
+            }
+            None => false,
+        };
+
+        defer_mint(
+            &self.store,
+            &self.budget_api,
+            self.topic(),
+            request,
+            mint_created,
+        )
+        .await
+    }
+
+    /// Records a completed step and persists the progress.
+    async fn save_step(
+        &self,
+        key: &str,
+        progress: &mut NftMintProgress,
+        step: NftMintStep,
+        signature: Option<Signature>,
+    ) -> Result<()> {
+        progress.step = step;
+        if let Some(signature) = signature {
+            progress.signatures.push(signature.to_string());
+        }
+
+        let mut mints = self.store.load().await?;
+        mints.insert(key.to_string(), progress.clone());
+
+        self.store.save(&mints).await
+    }
+}
+
+/// Returns the key of the mint progress of an NFT request (`wallet:quiz_id`).
+fn mint_key(request: &SolanaQuizNftRequested) -> String {
+    format!("{}:{}", request.user_wallet, request.quiz_id)
+}
+
+/// Defers an NFT request whose mint failed every attempt to the next budget day,
+/// when it is republished and resumes from its saved progress.
+///
+/// If the mint keypair was generated but the mint never created on-chain, its progress is
+/// dropped and its NFT budget released first, so the retry starts over. A partly created
+/// mint keeps its progress.
+async fn defer_mint(
+    mints: &JsonStore<HashMap<String, NftMintProgress>>,
+    budget_api: &RewardBudgetApi,
+    topic: &str,
+    request: &SolanaQuizNftRequested,
+    mint_created: bool,
+) -> Result<()> {
+    let key = mint_key(request);
+
+    let mut progress = mints.load().await?;
+    if !mint_created
+        && progress
+            .get(&key)
+            .is_some_and(|progress| progress.mint_keypair.is_some())
+    {
+        warn!(
+            "NFT mint for {} was never created, releasing its budget",
+            key
+        );
+
+        progress.remove(&key);
+        mints.save(&progress).await?;
+
+        budget_api.release_nft().await?;
+    }
+
+    warn!("NFT mint for {} failed every attempt, deferring", key);
+
+    budget_api
+        .defer(
+            topic,
+            &format!("wallet_{}", request.user_wallet),
+            &serde_json::to_string(request)?,
+            &KafkaHeaders::new(),
+        )
+        .await
+}
+
+/// Handler for the "solana-quiz-nft-requested" Kafka topic.
+///
+/// Deserializes incoming `SolanaQuizNftRequested` messages, mints the
+/// streak NFT to the user's wallet and publishes `solana-quiz-nft-minted`.
+/// New mints exceeding the daily NFT budget are deferred or rejected, and so are
+/// requests whose mint failed every attempt (deferred until the next budget day).
+#[async_trait]
+impl KafkaConsumerHandler for SolanaQuizNftHandler {
+    fn topic(&self) -> &'static str {
+        "solana-quiz-nft-requested"
+    }
+
+    async fn handle(&self, payload: &str, _headers: &KafkaHeaders) -> Result<()> {
+        match serde_json::from_str::<SolanaQuizNftRequested>(payload) {
+            Ok(request) => {
+                info!("Received NFT Request: {:?}", request);
+
+                if let Err(e) = republish_deferred(&self.producer, &self.budget_api).await {
+                    error!("Failed to republish deferred messages: {:?}", e);
+                }
+
+                let mut attempt = 1;
+
+                loop {
+                    match self.mint_nft(&request).await {
+                        Ok(()) => break,
+                        Err(e) if attempt < MAX_MINT_ATTEMPTS => {
+                            warn!("NFT mint attempt {} failed: {:?}", attempt, e);
+                            tokio::time::sleep(Duration::from_secs(2 * attempt as u64)).await;
+                            attempt += 1;
+                        }
+                        Err(e) => {
+                            self.retry_later(&request).await?;
+                            return Err(e);
+                        }
+                    }
+                }
+            }
+            Err(e) => error!("Failed to deserialize NFT Request: {}", e),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::reward::RewardBudgetState;
+    use std::path::PathBuf;
+
+    const TOPIC: &str = "solana-quiz-nft-requested";
+
+    fn store_path(test: &str, name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "solana_quiz_nft_{}_{}_{}.json",
+            test,
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        path
+    }
+
+    fn request() -> SolanaQuizNftRequested {
+        SolanaQuizNftRequested {
+            user_wallet: Pubkey::new_unique().to_string(),
+            quiz_id: 42,
+            streak_days: 3,
+            total_quizzes: None,
+            total_questions: None,
+            correct_answers: None,
+            achieved_at: None,
+        }
+    }
+
+    /// Moves the deferred messages one day back, as if the next budget day started.
+    async fn next_day(budgets: &JsonStore<RewardBudgetState>) {
+        let mut state = budgets.load().await.unwrap();
+        for message in &mut state.deferred {
+            message.day -= 1;
+        }
+        budgets.save(&state).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn partial_mint_is_picked_up_on_the_next_delivery() {
+        let mints = JsonStore::with_path(store_path("partial", "mints"));
+        let budgets_path = store_path("partial", "budgets");
+        let budget_api = RewardBudgetApi::with_store(JsonStore::with_path(budgets_path.clone()));
+
+        let request = request();
+        let mint_keypair = Keypair::new().to_base58_string();
+        let progress = NftMintProgress {
+            step: NftMintStep::TokenMinted,
+            mint_keypair: Some(mint_keypair.clone()),
+            signatures: vec![],
+        };
+        mints
+            .save(&HashMap::from([(mint_key(&request), progress)]))
+            .await
+            .unwrap();
+
+        defer_mint(&mints, &budget_api, TOPIC, &request, true)
+            .await
+            .unwrap();
+        assert!(budget_api.take_due_deferred().await.unwrap().is_empty());
+
+        next_day(&JsonStore::with_path(budgets_path)).await;
+        let due = budget_api.take_due_deferred().await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].topic, TOPIC);
+
+        let redelivered: SolanaQuizNftRequested = serde_json::from_str(&due[0].payload).unwrap();
+        let progress = mints
+            .load()
+            .await
+            .unwrap()
+            .remove(&mint_key(&redelivered))
+            .unwrap();
+        assert_eq!(progress.step, NftMintStep::TokenMinted);
+        assert_eq!(progress.mint_keypair, Some(mint_keypair));
+    }
+
+    #[tokio::test]
+    async fn mint_never_created_starts_over() {
+        let mints = JsonStore::with_path(store_path("never_created", "mints"));
+        let budgets_path = store_path("never_created", "budgets");
+        let budget_api = RewardBudgetApi::with_store(JsonStore::with_path(budgets_path.clone()));
+
+        let request = request();
+        budget_api.reserve_nft().await.unwrap();
+        let progress = NftMintProgress {
+            step: NftMintStep::Requested,
+            mint_keypair: Some(Keypair::new().to_base58_string()),
+            signatures: vec![],
+        };
+        mints
+            .save(&HashMap::from([(mint_key(&request), progress)]))
+            .await
+            .unwrap();
+
+        defer_mint(&mints, &budget_api, TOPIC, &request, false)
+            .await
+            .unwrap();
+
+        assert!(mints.load().await.unwrap().is_empty());
+        let state = JsonStore::<RewardBudgetState>::with_path(budgets_path)
+            .load()
+            .await
+            .unwrap();
+        assert_eq!(state.nfts, 0);
+        assert_eq!(state.deferred.len(), 1);
+    }
+}
@@ -1,85 +1,427 @@
-use crate::kafka::consumer_handlers::KafkaConsumerHandler;
+use crate::kafka::consumer_handlers::{KafkaConsumerHandler, KafkaHeaders, republish_deferred};
 use crate::kafka::producer::KafkaProducer;
-use crate::models::kafka::{SolanaQuizReward, SolanaQuizRewardApplied};
+use crate::models::kafka::{
+    SolanaQuizNftRequested, SolanaQuizReward, SolanaQuizRewardApplied, SolanaQuizRewardFailed,
+    SolanaQuizRewardFlagged, SolanaQuizRewardHeld, SolanaQuizRewardRejected,
+};
+use crate::models::reward::{
+    ApprovalOutcome, BudgetExceededAction, RewardBudget, RewardRejection, RewardRejectionReason,
+    RewardTransfer, ValidationFailedAction,
+};
+use crate::models::sybil::SybilAction;
 use crate::services::blockchain_api::BlockchainApi;
 use crate::services::blockchain_api::solana_quiz_rewards::accounts::QuizUserData;
-use crate::services::nft_api::NftApi;
+use crate::services::lookup_table_api::LookupTableApi;
+use crate::services::message_signature_api::MessageSignatureApi;
+use crate::services::reward_budget_api::RewardBudgetApi;
+use crate::services::reward_hold_api::RewardHoldApi;
+use crate::services::reward_policy_api::RewardPolicyApi;
+use crate::services::reward_sybil_api::RewardSybilApi;
+use crate::services::reward_validation_api::RewardValidationApi;
 use crate::services::solana_api::SolanaApi;
-use crate::utils::solana_util::{get_solana_on_chain, get_solana_streak_days};
-use anyhow::Result;
+use crate::services::wallet_blocklist_api::WalletBlocklistApi;
+use crate::utils::solana_util::{
+    get_solana_indexer_enabled, get_solana_on_chain, get_solana_streak_days,
+};
+use crate::utils::{reward_util, token_util};
+use anyhow::{Result, anyhow};
 use async_trait::async_trait;
+use chrono::Utc;
 use solana_program::pubkey::Pubkey;
 use solana_sdk::signature::Signature;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::runtime::Handle;
 use tokio::task;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 pub struct SolanaQuizRewardHandler {
     producer: Arc<KafkaProducer>,
+    reward_policy_api: RewardPolicyApi,
+    budget_api: RewardBudgetApi,
+    validation_api: RewardValidationApi,
+    signature_api: MessageSignatureApi,
+    blocklist_api: WalletBlocklistApi,
+    sybil_api: RewardSybilApi,
+    hold_api: RewardHoldApi,
+    lookup_table_api: LookupTableApi,
+    batch_window: Option<Duration>,
+    batch_max_size: usize,
+}
+
+/// A reward that passed all checks, with its amount reserved against the budgets.
+struct PayableReward {
+    reward: SolanaQuizReward,
+    amount: u64,
+    decimals: u8,
+    budget_key: String,
 }
 
 impl SolanaQuizRewardHandler {
     pub fn new(producer: Arc<KafkaProducer>) -> Self {
-        Self { producer }
+        Self {
+            producer,
+            reward_policy_api: RewardPolicyApi::new(),
+            budget_api: RewardBudgetApi::new(),
+            validation_api: RewardValidationApi::new(),
+            signature_api: MessageSignatureApi::new(),
+            blocklist_api: WalletBlocklistApi::new(),
+            sybil_api: RewardSybilApi::new(),
+            hold_api: RewardHoldApi::new(),
+            lookup_table_api: LookupTableApi::new(),
+            batch_window: reward_util::get_reward_batch_window(),
+            batch_max_size: reward_util::get_reward_batch_max_size(),
+        }
     }
-}
 
-/// Handler for the "solana-quiz-rewards" Kafka topic.
-///
-/// Deserializes incoming `SolanaQuizReward` messages and sends
-/// the earned tokens to the user's wallet via Solana API.
-#[async_trait]
-impl KafkaConsumerHandler for SolanaQuizRewardHandler {
-    fn topic(&self) -> &'static str {
-        "solana-quiz-rewards"
-    }
+    /// Checks that the reward can be paid out, i.e. it is signed by a trusted producer,
+    /// does not go to a blocked wallet, is valid, passes the anti-sybil checks
+    /// and fits in the emission budgets.
+    ///
+    /// A reward `approved` in manual review skips the signature, validation and
+    /// anti-sybil checks, and is held again if it exceeds an emission budget.
+    ///
+    /// Returns the reward with its amount reserved against the budgets,
+    /// or `None` if the reward was rejected, held for review, or deferred by a budget.
+    async fn prepare(
+        &self,
+        reward: &SolanaQuizReward,
+        payload: &str,
+        headers: &KafkaHeaders,
+        approved: bool,
+    ) -> Result<Option<PayableReward>> {
+        if !approved && let Some(rejection) = self.signature_api.verify(payload, headers).await? {
+            self.reject(reward, rejection).await?;
+            return Ok(None);
+        }
 
-    async fn handle(&self, payload: &str) -> Result<()> {
-        match serde_json::from_str::<SolanaQuizReward>(payload) {
-            Ok(reward) => {
-                info!("Received Quiz Reward: {:?}", reward);
+        if let Some(reason) = self
+            .blocklist_api
+            .blocked_reason(&reward.user_wallet)
+            .await?
+        {
+            let rejection = RewardRejection::new(
+                RewardRejectionReason::WalletBlocked,
+                format!("The wallet is blocked: {}", reason),
+            );
+            self.reject(reward, rejection).await?;
+            return Ok(None);
+        }
 
-                let streak_days: u8;
+        if !approved && let Some(rejection) = self.validation_api.validate(reward).await? {
+            self.invalid(reward, rejection).await?;
+            return Ok(None);
+        }
 
-                if get_solana_on_chain() {
-                    let quiz_user_data_account = self.send_tokens_on_chain(&reward).await?;
-                    streak_days = quiz_user_data_account.streak;
-                } else {
-                    self.send_tokens_off_chain(&reward).await?;
-                    streak_days = reward.streak_days;
+        if !approved && let Some(finding) = self.sybil_api.check(reward).await? {
+            match finding.action {
+                SybilAction::Reject => {
+                    self.reject(reward, finding.rejection).await?;
+                    return Ok(None);
+                }
+                SybilAction::Hold => {
+                    self.hold(reward, finding.rejection).await?;
+                    return Ok(None);
                 }
+                SybilAction::Flag => self.flag(reward, finding.rejection).await?,
+            }
+        }
+
+        let decimals = SolanaApi::new().get_mint_decimals().await?;
+        let amount = match self.reward_policy_api.reward_base_units(reward, decimals) {
+            Ok(amount) => amount,
+            Err(e) => {
+                let rejection =
+                    RewardRejection::new(RewardRejectionReason::InvalidAmount, e.to_string());
+                self.invalid(reward, rejection).await?;
+                return Ok(None);
+            }
+        };
+
+        if !approved
+            && let Some(rejection) = self.validation_api.validate_amount(amount, decimals)?
+        {
+            self.invalid(reward, rejection).await?;
+            return Ok(None);
+        }
+
+        let budget_key = format!("{}:{}", reward.user_wallet, reward.quiz_id);
+        if let Some(budget) = self
+            .budget_api
+            .reserve_tokens(&budget_key, &reward.user_wallet, amount, decimals)
+            .await?
+        {
+            let action = match approved {
+                true => BudgetExceededAction::Hold,
+                false => self.budget_api.action(),
+            };
+            self.budget_exceeded(reward, payload, headers, budget, action)
+                .await?;
+            return Ok(None);
+        }
+
+        Ok(Some(PayableReward {
+            reward: reward.clone(),
+            amount,
+            decimals,
+            budget_key,
+        }))
+    }
+
+    /// Pays a reward out on its own.
+    ///
+    /// Returns the user's streak and on-chain data after the payout.
+    async fn send(&self, payable: &PayableReward) -> Result<(u8, Option<QuizUserData>)> {
+        let reward = &payable.reward;
+
+        if get_solana_on_chain() {
+            self.send_tokens_on_chain(reward, payable.amount)
+                .await
+                .map(|quiz_user_data| (quiz_user_data.streak, Some(quiz_user_data)))
+        } else {
+            self.send_tokens_off_chain(reward, payable.amount, payable.decimals)
+                .await
+                .map(|_| (reward.streak_days, None))
+        }
+    }
+
+    /// Pays rewards out together, in as few transactions as possible.
+    ///
+    /// Returns the user's streak and on-chain data after every payout, or its error, in order.
+    async fn send_batch(
+        &self,
+        payables: &[PayableReward],
+    ) -> Result<Vec<Result<(u8, Option<QuizUserData>)>>> {
+        if get_solana_on_chain() {
+            Ok(self
+                .send_tokens_on_chain_batch(payables)
+                .await?
+                .into_iter()
+                .map(|result| {
+                    result.map(|quiz_user_data| (quiz_user_data.streak, Some(quiz_user_data)))
+                })
+                .collect())
+        } else {
+            Ok(self
+                .send_tokens_off_chain_batch(payables)
+                .await?
+                .into_iter()
+                .zip(payables)
+                .map(|(result, payable)| result.map(|_| (payable.reward.streak_days, None)))
+                .collect())
+        }
+    }
+
+    /// Completes the payout of a reward: a failed payout releases its budget, a successful one
+    /// is confirmed, requests the streak NFT if the streak is reached, and counts towards
+    /// the hot token accounts of the lookup tables.
+    async fn settle(
+        &self,
+        payable: &PayableReward,
+        result: Result<(u8, Option<QuizUserData>)>,
+    ) -> Result<()> {
+        let reward = &payable.reward;
+
+        let (streak_days, quiz_user_data) = match result {
+            Ok(paid) => paid,
+            // A failed payout does not use up the budget
+            Err(e) => {
+                self.budget_api
+                    .release_tokens(&payable.budget_key, &reward.user_wallet)
+                    .await?;
+                return Err(e);
+            }
+        };
+
+        if !get_solana_on_chain() {
+            self.validation_api.record_streak(reward).await?;
+        }
+
+        let confirmation = SolanaQuizRewardApplied {
+            user_id: reward.user_id,
+            quiz_id: reward.quiz_id,
+        };
+        let payload = serde_json::to_string(&confirmation)?;
+
+        self.producer
+            .send(
+                "solana-quiz-reward-applied",
+                &format!("user_{}", reward.user_id),
+                &payload,
+            )
+            .await?;
+
+        if streak_days == get_solana_streak_days()
+            && self
+                .validation_api
+                .record_milestone(&reward.user_wallet)
+                .await?
+        {
+            self.request_nft_rewards(reward, quiz_user_data.as_ref())
+                .await?;
+        }
+
+        self.lookup_table_api
+            .record_recipient(&reward.user_wallet.parse::<Pubkey>()?)
+            .await
+    }
 
-                let confirmation = SolanaQuizRewardApplied {
-                    user_id: reward.user_id,
+    /// Rejects or holds an invalid reward, as configured.
+    async fn invalid(&self, reward: &SolanaQuizReward, rejection: RewardRejection) -> Result<()> {
+        match self.validation_api.action() {
+            ValidationFailedAction::Reject => self.reject(reward, rejection).await,
+            ValidationFailedAction::Hold => self.hold(reward, rejection).await,
+        }
+    }
+
+    /// Publishes the rejection of an invalid reward, instead of paying it out.
+    async fn reject(&self, reward: &SolanaQuizReward, rejection: RewardRejection) -> Result<()> {
+        warn!(
+            "Reward for quiz {} of {} rejected: {}",
+            reward.quiz_id, reward.user_wallet, rejection.message
+        );
+
+        let rejected = SolanaQuizRewardRejected {
+            user_id: reward.user_id,
+            user_wallet: reward.user_wallet.clone(),
+            quiz_id: reward.quiz_id,
+            reason: rejection.reason,
+            message: rejection.message,
+        };
+
+        self.producer
+            .send(
+                "solana-quiz-reward-rejected",
+                &format!("user_{}", reward.user_id),
+                &serde_json::to_string(&rejected)?,
+            )
+            .await
+    }
+
+    /// Keeps a reward for manual review, instead of paying it out.
+    async fn hold(&self, reward: &SolanaQuizReward, rejection: RewardRejection) -> Result<()> {
+        warn!(
+            "Reward for quiz {} of {} held for review: {}",
+            reward.quiz_id, reward.user_wallet, rejection.message
+        );
+
+        self.hold_api.hold(reward, &rejection).await?;
+
+        let held = SolanaQuizRewardHeld {
+            user_id: reward.user_id,
+            user_wallet: reward.user_wallet.clone(),
+            quiz_id: reward.quiz_id,
+            reason: rejection.reason,
+            message: rejection.message,
+        };
+
+        self.producer
+            .send(
+                "solana-quiz-reward-held",
+                &format!("user_{}", reward.user_id),
+                &serde_json::to_string(&held)?,
+            )
+            .await
+    }
+
+    /// Publishes a suspicious reward for later review, it is still paid out.
+    async fn flag(&self, reward: &SolanaQuizReward, rejection: RewardRejection) -> Result<()> {
+        warn!(
+            "Reward for quiz {} of {} flagged: {}",
+            reward.quiz_id, reward.user_wallet, rejection.message
+        );
+
+        let flagged = SolanaQuizRewardFlagged {
+            user_id: reward.user_id,
+            user_wallet: reward.user_wallet.clone(),
+            quiz_id: reward.quiz_id,
+            reason: rejection.reason,
+            message: rejection.message,
+        };
+
+        self.producer
+            .send(
+                "solana-quiz-reward-flagged",
+                &format!("user_{}", reward.user_id),
+                &serde_json::to_string(&flagged)?,
+            )
+            .await
+    }
+
+    /// Defers, rejects or holds a reward that exceeds an emission budget.
+    async fn budget_exceeded(
+        &self,
+        reward: &SolanaQuizReward,
+        payload: &str,
+        headers: &KafkaHeaders,
+        budget: RewardBudget,
+        action: BudgetExceededAction,
+    ) -> Result<()> {
+        let key = format!("user_{}", reward.user_id);
+
+        match action {
+            BudgetExceededAction::Defer => {
+                warn!(
+                    "Reward for quiz {} of {} exceeds the {} budget, deferring",
+                    reward.quiz_id,
+                    reward.user_wallet,
+                    budget.name()
+                );
+
+                // The signature will have expired when the message is republished
+                self.signature_api.release(headers).await?;
+
+                self.budget_api
+                    .defer(self.topic(), &key, payload, headers)
+                    .await
+            }
+            BudgetExceededAction::Reject => {
+                warn!(
+                    "Reward for quiz {} of {} exceeds the {} budget, rejecting",
+                    reward.quiz_id,
+                    reward.user_wallet,
+                    budget.name()
+                );
+
+                let failed = SolanaQuizRewardFailed {
+                    user_id: Some(reward.user_id),
+                    user_wallet: reward.user_wallet.clone(),
                     quiz_id: reward.quiz_id,
+                    reason: format!("{}_budget_exceeded", budget.name()),
                 };
-                let payload = serde_json::to_string(&confirmation)?;
 
                 self.producer
                     .send(
-                        "solana-quiz-reward-applied",
-                        &format!("user_{}", reward.user_id),
-                        &payload,
+                        "solana-quiz-reward-failed",
+                        &key,
+                        &serde_json::to_string(&failed)?,
                     )
-                    .await?;
+                    .await
+            }
+            BudgetExceededAction::Hold => {
+                let rejection = RewardRejection::new(
+                    RewardRejectionReason::BudgetExceeded,
+                    format!("The reward exceeds the {} budget", budget.name()),
+                );
 
-                if streak_days == get_solana_streak_days() {
-                    self.send_nft_rewards(&reward).await?;
-                }
+                self.hold(reward, rejection).await
             }
-            Err(e) => error!("Failed to deserialize Quiz Reward: {}", e),
         }
-
-        Ok(())
     }
 
-    async fn send_tokens_on_chain(&self, reward: &SolanaQuizReward) -> Result<QuizUserData> {
+    /// Sends the earned tokens to the user's wallet via on-chain Solana transaction.
+    ///
+    /// The user's on-chain stats count the earned tokens in base units, like the transfer.
+    async fn send_tokens_on_chain(
+        &self,
+        reward: &SolanaQuizReward,
+        transfer_amount: u64,
+    ) -> Result<QuizUserData> {
         let user_wallet = reward.user_wallet.parse::<Pubkey>()?;
 
+        let quiz_id = reward.quiz_id;
         let total_questions = reward.total_questions;
         let correct_answers = reward.correct_answers;
-        let earned_tokens = reward.earned_tokens;
 
         info!("Starting on-chain reward transaction for {}", user_wallet);
         let start = std::time::Instant::now();
@@ -92,9 +434,10 @@ impl KafkaConsumerHandler for SolanaQuizRewardHandler {
                 blockchain_api
                     .call_program_rewards(
                         &user_wallet,
+                        quiz_id,
                         total_questions,
                         correct_answers,
-                        earned_tokens,
+                        transfer_amount,
                     )
                     .await
             })
@@ -106,21 +449,388 @@ impl KafkaConsumerHandler for SolanaQuizRewardHandler {
         Ok(quiz_user_data_account)
     }
 
-    async fn send_tokens_off_chain(&self, reward: &SolanaQuizReward) -> Result<Signature> {
-        let signature = SolanaApi::new()
-            .send_tokens(&reward.user_wallet.parse::<Pubkey>()?, reward.earned_tokens)
+    /// Sends the earned tokens to the user's wallet via an off-chain mechanism.
+    ///
+    /// Returns `None` for a quiz that earned nothing, no transaction is sent then.
+    async fn send_tokens_off_chain(
+        &self,
+        reward: &SolanaQuizReward,
+        amount: u64,
+        decimals: u8,
+    ) -> Result<Option<Signature>> {
+        if amount == 0 {
+            info!(
+                "Quiz {} of {} earned no tokens",
+                reward.quiz_id, reward.user_wallet
+            );
+            return Ok(None);
+        }
+
+        let solana_api = SolanaApi::new();
+
+        info!(
+            "Sending {} tokens to {}",
+            token_util::format_token_amount(amount, decimals),
+            reward.user_wallet
+        );
+        let signature = solana_api
+            .send_tokens(&reward.user_wallet.parse::<Pubkey>()?, amount)
             .await?;
 
         info!("Transaction Signature: {}", signature);
 
-        Ok(signature)
+        Ok(Some(signature))
     }
 
-    async fn send_nft_rewards(&self, reward: &SolanaQuizReward) -> Result<()> {
-        NftApi::new()
-            .mint_nft_to_recipient(&reward.user_wallet.parse::<Pubkey>()?)
-            .await?;
+    /// Sends the earned tokens of several rewards via on-chain Solana transactions,
+    /// batching the program instructions of up to `REWARD_BATCH_MAX_SIZE` rewards per transaction.
+    async fn send_tokens_on_chain_batch(
+        &self,
+        payables: &[PayableReward],
+    ) -> Result<Vec<Result<QuizUserData>>> {
+        let mut transfers = vec![];
+        for payable in payables {
+            transfers.push(reward_transfer(payable)?);
+        }
+        let max_rewards = self.batch_max_size;
+
+        info!(
+            "Starting on-chain reward transactions for {} rewards",
+            transfers.len()
+        );
+
+        let handle = Handle::current();
+
+        task::spawn_blocking(move || {
+            handle.block_on(async {
+                let blockchain_api = BlockchainApi::new();
+                blockchain_api
+                    .call_program_rewards_batch(&transfers, max_rewards)
+                    .await
+            })
+        })
+        .await?
+    }
+
+    /// Sends the earned tokens of several rewards via an off-chain mechanism,
+    /// batching up to `REWARD_BATCH_MAX_SIZE` transfers per transaction.
+    ///
+    /// Quizzes that earned nothing get `None`, no transfer is sent for them.
+    async fn send_tokens_off_chain_batch(
+        &self,
+        payables: &[PayableReward],
+    ) -> Result<Vec<Result<Option<Signature>>>> {
+        let mut transfers = vec![];
+        for payable in payables.iter().filter(|payable| payable.amount > 0) {
+            transfers.push((
+                payable.reward.user_wallet.parse::<Pubkey>()?,
+                payable.amount,
+            ));
+        }
+
+        let mut sent = match transfers.is_empty() {
+            true => vec![],
+            false => {
+                info!("Sending tokens to {} wallets", transfers.len());
+                SolanaApi::new()
+                    .send_tokens_batch(&transfers, self.batch_max_size)
+                    .await?
+            }
+        }
+        .into_iter();
+
+        let mut results = vec![];
+        for payable in payables {
+            if payable.amount == 0 {
+                info!(
+                    "Quiz {} of {} earned no tokens",
+                    payable.reward.quiz_id, payable.reward.user_wallet
+                );
+                results.push(Ok(None));
+                continue;
+            }
+
+            let result = sent
+                .next()
+                .ok_or_else(|| anyhow!("Missing transfer result"))?;
+            if let Ok(signature) = &result {
+                info!(
+                    "Sent {} tokens to {}, Transaction Signature: {}",
+                    token_util::format_token_amount(payable.amount, payable.decimals),
+                    payable.reward.user_wallet,
+                    signature
+                );
+            }
+            results.push(result.map(Some));
+        }
+
+        Ok(results)
+    }
+
+    /// Pays a reward out, then confirms it and requests the streak NFT if the streak is reached.
+    async fn process(
+        &self,
+        reward: &SolanaQuizReward,
+        payload: &str,
+        headers: &KafkaHeaders,
+    ) -> Result<()> {
+        let Some(payable) = self.prepare(reward, payload, headers, false).await? else {
+            return Ok(());
+        };
+
+        let result = self.send(&payable).await;
+        self.settle(&payable, result).await
+    }
+
+    /// Pays rewards out together, then confirms each of them and requests the streak NFTs.
+    ///
+    /// Every reward is checked and settled on its own, so a failure only affects its reward:
+    /// it is logged and, if the reward was not paid, published to `solana-quiz-reward-failed`.
+    /// The transfers of a failed transaction are retried one by one only once it definitely
+    /// did not land; if the batch cannot be sent at all, its rewards fail.
+    async fn process_batch(
+        &self,
+        rewards: &[(SolanaQuizReward, &str, &KafkaHeaders)],
+    ) -> Result<()> {
+        let mut payables = vec![];
+        for (reward, payload, headers) in rewards {
+            match self.prepare(reward, payload, headers, false).await {
+                Ok(Some(payable)) => payables.push(payable),
+                Ok(None) => {}
+                Err(e) => {
+                    error!(
+                        "Error while handling reward for quiz {} of {}: {:?}",
+                        reward.quiz_id, reward.user_wallet, e
+                    );
+                    self.failed(reward, "processing_failed").await;
+                }
+            }
+        }
+
+        if payables.is_empty() {
+            return Ok(());
+        }
+
+        info!("Paying out {} rewards in a batch", payables.len());
+        let start = std::time::Instant::now();
+
+        // Sending the rewards again could pay them twice, the batch may have been sent in part
+        let results = match self.send_batch(&payables).await {
+            Ok(results) => results,
+            Err(e) => {
+                warn!("Batch of {} rewards failed: {:?}", payables.len(), e);
+
+                payables
+                    .iter()
+                    .map(|_| Err(anyhow!("Batch of rewards failed: {:?}", e)))
+                    .collect()
+            }
+        };
+
+        info!("Batch completed in {:.2?}", start.elapsed());
+
+        for (payable, result) in payables.iter().zip(results) {
+            let paid = result.is_ok();
+
+            if let Err(e) = self.settle(payable, result).await {
+                error!(
+                    "Error while paying out reward for quiz {} of {}: {:?}",
+                    payable.reward.quiz_id, payable.reward.user_wallet, e
+                );
+
+                // A paid reward only failed to be confirmed
+                if !paid {
+                    self.failed(&payable.reward, "payout_failed").await;
+                }
+            }
+        }
 
         Ok(())
     }
+
+    /// Publishes a reward that could not be paid out to `solana-quiz-reward-failed`.
+    /// Publishing errors are logged, so they do not affect the other rewards of a batch.
+    async fn failed(&self, reward: &SolanaQuizReward, reason: &str) {
+        let failed = SolanaQuizRewardFailed {
+            user_id: Some(reward.user_id),
+            user_wallet: reward.user_wallet.clone(),
+            quiz_id: reward.quiz_id,
+            reason: reason.to_string(),
+        };
+
+        let result = match serde_json::to_string(&failed) {
+            Ok(payload) => {
+                self.producer
+                    .send(
+                        "solana-quiz-reward-failed",
+                        &format!("user_{}", reward.user_id),
+                        &payload,
+                    )
+                    .await
+            }
+            Err(e) => Err(e.into()),
+        };
+
+        if let Err(e) = result {
+            error!(
+                "Failed to publish the failure of reward for quiz {} of {}: {:?}",
+                reward.quiz_id, reward.user_wallet, e
+            );
+        }
+    }
+
+    /// Pays out a reward approved in manual review through the normal payout path,
+    /// skipping the signature, validation and anti-sybil checks.
+    ///
+    /// The blocklist and the emission budgets still apply: a reward exceeding
+    /// a budget is held again.
+    ///
+    /// Returns what happened to the reward, or an error if it was not paid out.
+    pub async fn approve(&self, reward: &SolanaQuizReward) -> Result<ApprovalOutcome> {
+        let payload = serde_json::to_string(reward)?;
+
+        let Some(payable) = self
+            .prepare(reward, &payload, &KafkaHeaders::new(), true)
+            .await?
+        else {
+            let key = RewardHoldApi::key(&reward.user_wallet, reward.quiz_id);
+            return match self.hold_api.list().await?.contains_key(&key) {
+                true => Ok(ApprovalOutcome::Held),
+                false => Ok(ApprovalOutcome::Rejected),
+            };
+        };
+
+        let result = self.send(&payable).await;
+        let paid = result.is_ok();
+
+        match self.settle(&payable, result).await {
+            Ok(()) => Ok(ApprovalOutcome::Paid),
+            Err(e) if paid => Ok(ApprovalOutcome::PaidUnconfirmed(format!("{:?}", e))),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Rejects a reward in manual review and publishes `solana-quiz-reward-failed`.
+    pub async fn reject_held(&self, reward: &SolanaQuizReward) -> Result<()> {
+        let failed = SolanaQuizRewardFailed {
+            user_id: Some(reward.user_id),
+            user_wallet: reward.user_wallet.clone(),
+            quiz_id: reward.quiz_id,
+            reason: "review_rejected".to_string(),
+        };
+
+        self.producer
+            .send(
+                "solana-quiz-reward-failed",
+                &format!("user_{}", reward.user_id),
+                &serde_json::to_string(&failed)?,
+            )
+            .await
+    }
+
+    /// Requests a streak NFT for the user via the "solana-quiz-nft-requested" topic.
+    ///
+    /// In on-chain mode with the indexer enabled, the request is published by the
+    /// indexer from the `StreakAchieved` program event instead.
+    /// The on-chain user data, when available, provides the stats for the NFT metadata.
+    async fn request_nft_rewards(
+        &self,
+        reward: &SolanaQuizReward,
+        quiz_user_data: Option<&QuizUserData>,
+    ) -> Result<()> {
+        if get_solana_on_chain() && get_solana_indexer_enabled() {
+            return Ok(());
+        }
+
+        let request = SolanaQuizNftRequested {
+            user_wallet: reward.user_wallet.clone(),
+            quiz_id: reward.quiz_id,
+            streak_days: get_solana_streak_days(),
+            total_quizzes: quiz_user_data.map(|data| data.total_quizzes),
+            total_questions: quiz_user_data.map(|data| data.total_questions),
+            correct_answers: quiz_user_data.map(|data| data.correct_answers),
+            achieved_at: Some(Utc::now().timestamp()),
+        };
+
+        self.producer
+            .send(
+                "solana-quiz-nft-requested",
+                &format!("wallet_{}", reward.user_wallet),
+                &serde_json::to_string(&request)?,
+            )
+            .await
+    }
+}
+
+/// Handler for the "solana-quiz-rewards" Kafka topic.
+///
+/// Deserializes incoming `SolanaQuizReward` messages, sends the earned
+/// tokens to the user's wallet via Solana API and requests streak NFTs.
+/// Unsigned (in strict mode), blocked and invalid rewards are rejected, rewards failing
+/// an anti-sybil check are rejected, held or flagged, and rewards exceeding an emission
+/// budget are deferred or rejected.
+/// With `REWARD_BATCH_WINDOW_MS`, rewards received within the window are paid out together.
+#[async_trait]
+impl KafkaConsumerHandler for SolanaQuizRewardHandler {
+    fn topic(&self) -> &'static str {
+        "solana-quiz-rewards"
+    }
+
+    async fn handle(&self, payload: &str, headers: &KafkaHeaders) -> Result<()> {
+        match serde_json::from_str::<SolanaQuizReward>(payload) {
+            Ok(reward) => {
+                info!("Received Quiz Reward: {:?}", reward);
+
+                if let Err(e) = republish_deferred(&self.producer, &self.budget_api).await {
+                    error!("Failed to republish deferred messages: {:?}", e);
+                }
+
+                self.process(&reward, payload, headers).await?;
+            }
+            Err(e) => error!("Failed to deserialize Quiz Reward: {}", e),
+        }
+
+        Ok(())
+    }
+
+    fn batch_window(&self) -> Option<Duration> {
+        self.batch_window
+    }
+
+    fn batch_max_size(&self) -> usize {
+        self.batch_max_size
+    }
+
+    async fn handle_batch(&self, messages: &[(&str, KafkaHeaders)]) -> Result<()> {
+        // The rewards of the batch are handled even if republishing fails
+        if let Err(e) = republish_deferred(&self.producer, &self.budget_api).await {
+            error!("Failed to republish deferred messages: {:?}", e);
+        }
+
+        let mut rewards = vec![];
+        for (payload, headers) in messages {
+            match serde_json::from_str::<SolanaQuizReward>(payload) {
+                Ok(reward) => {
+                    info!("Received Quiz Reward: {:?}", reward);
+                    rewards.push((reward, *payload, headers));
+                }
+                Err(e) => error!("Failed to deserialize Quiz Reward: {}", e),
+            }
+        }
+
+        self.process_batch(&rewards).await
+    }
+}
+
+/// Builds the token transfer of a reward.
+fn reward_transfer(payable: &PayableReward) -> Result<RewardTransfer> {
+    let reward = &payable.reward;
+
+    Ok(RewardTransfer {
+        user_wallet: reward.user_wallet.parse::<Pubkey>()?,
+        quiz_id: reward.quiz_id,
+        total_questions: reward.total_questions,
+        correct_answers: reward.correct_answers,
+        amount: payable.amount,
+    })
 }
@@ -0,0 +1,69 @@
+use crate::kafka::consumer_handlers::{KafkaConsumerHandler, KafkaHeaders};
+use crate::models::kafka::{SolanaQuizWalletControl, WalletControlAction};
+use crate::services::wallet_blocklist_api::WalletBlocklistApi;
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::{error, info, warn};
+
+/// Actor recorded in the audit log for control messages that do not name one.
+const DEFAULT_ACTOR: &str = "kafka";
+
+pub struct SolanaQuizWalletControlHandler {
+    blocklist_api: WalletBlocklistApi,
+}
+
+impl SolanaQuizWalletControlHandler {
+    pub fn new() -> Self {
+        Self {
+            blocklist_api: WalletBlocklistApi::new(),
+        }
+    }
+}
+
+impl Default for SolanaQuizWalletControlHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Handler for the "solana-quiz-wallet-control" Kafka topic.
+///
+/// Deserializes incoming `SolanaQuizWalletControl` messages and blocks or unblocks
+/// the wallet from receiving rewards. Every change is recorded in the audit log.
+#[async_trait]
+impl KafkaConsumerHandler for SolanaQuizWalletControlHandler {
+    fn topic(&self) -> &'static str {
+        "solana-quiz-wallet-control"
+    }
+
+    async fn handle(&self, payload: &str, _headers: &KafkaHeaders) -> Result<()> {
+        match serde_json::from_str::<SolanaQuizWalletControl>(payload) {
+            Ok(control) => {
+                info!("Received Wallet Control: {:?}", control);
+
+                let actor = control.actor.as_deref().unwrap_or(DEFAULT_ACTOR);
+
+                match control.action {
+                    WalletControlAction::Block => {
+                        let reason = control.reason.as_deref().unwrap_or_default();
+                        self.blocklist_api
+                            .block(&control.wallet, reason, actor)
+                            .await?;
+                    }
+                    WalletControlAction::Unblock => {
+                        if !self
+                            .blocklist_api
+                            .unblock(&control.wallet, control.reason.clone(), actor)
+                            .await?
+                        {
+                            warn!("Wallet {} is not blocked", control.wallet);
+                        }
+                    }
+                }
+            }
+            Err(e) => error!("Failed to deserialize Wallet Control: {}", e),
+        }
+
+        Ok(())
+    }
+}
@@ -17,7 +17,7 @@ pub async fn run() -> Result<()> {
     let consumer = kafka_consumer.get_consumer();
     let handlers = kafka_consumer.get_handlers(Arc::clone(&kafka_producer));
 
-    kafka_consumer.consume_events(&consumer, &handlers).await;
+    kafka_consumer.consume_events(consumer, &handlers).await;
 
     Ok(())
 }
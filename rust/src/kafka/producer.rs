@@ -1,5 +1,7 @@
 use crate::kafka::config::create_kafka_producer_config;
+use crate::kafka::consumer_handlers::KafkaHeaders;
 use anyhow::Result;
+use rdkafka::message::{Header, OwnedHeaders};
 use rdkafka::producer::{FutureProducer, FutureRecord};
 use rdkafka::util::Timeout;
 use std::sync::Arc;
@@ -29,7 +31,30 @@ impl KafkaProducer {
 
     /// Sends a message asynchronously to the given Kafka topic.
     pub async fn send(&self, topic: &str, key: &str, payload: &str) -> Result<()> {
-        let record: FutureRecord<str, str> = FutureRecord::to(topic).key(key).payload(payload);
+        self.send_with_headers(topic, key, payload, &KafkaHeaders::new())
+            .await
+    }
+
+    /// Sends a message with headers asynchronously to the given Kafka topic.
+    pub async fn send_with_headers(
+        &self,
+        topic: &str,
+        key: &str,
+        payload: &str,
+        headers: &KafkaHeaders,
+    ) -> Result<()> {
+        let mut record: FutureRecord<str, str> = FutureRecord::to(topic).key(key).payload(payload);
+        if !headers.is_empty() {
+            let headers = headers
+                .iter()
+                .fold(OwnedHeaders::new(), |owned, (key, value)| {
+                    owned.insert(Header {
+                        key,
+                        value: Some(value),
+                    })
+                });
+            record = record.headers(headers);
+        }
         let producer = self.get_producer();
 
         match producer.send(record, Timeout::Never).await {
@@ -44,3 +69,9 @@ impl KafkaProducer {
         }
     }
 }
+
+impl Default for KafkaProducer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,5 +1,10 @@
 pub mod commands;
+pub mod indexer;
 pub mod kafka;
+pub mod metadata_server;
+pub mod metrics;
 pub mod models;
 pub mod services;
+pub mod storage;
+pub mod treasury;
 pub mod utils;
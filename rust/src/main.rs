@@ -1,6 +1,10 @@
 use anyhow::Result;
 use dotenv::dotenv;
-use solana_quiz::kafka;
+use solana_quiz::utils::server_util::{get_metadata_server_enabled, get_metrics_server_enabled};
+use solana_quiz::utils::solana_util::{
+    get_solana_indexer_enabled, get_solana_treasury_monitor_enabled,
+};
+use solana_quiz::{indexer, kafka, metadata_server, metrics, treasury};
 use tracing::{error, info};
 
 #[tokio::main]
@@ -18,10 +22,54 @@ async fn main() -> Result<()> {
         }
     });
 
+    let indexer_task = get_solana_indexer_enabled().then(|| {
+        tokio::spawn(async move {
+            if let Err(e) = indexer::run().await {
+                error!("Program indexer error: {:?}", e);
+            }
+        })
+    });
+
+    let treasury_monitor_task = get_solana_treasury_monitor_enabled().then(|| {
+        tokio::spawn(async move {
+            if let Err(e) = treasury::run().await {
+                error!("Treasury monitor error: {:?}", e);
+            }
+        })
+    });
+
+    let metadata_server_task = get_metadata_server_enabled().then(|| {
+        tokio::spawn(async move {
+            if let Err(e) = metadata_server::run().await {
+                error!("Metadata server error: {:?}", e);
+            }
+        })
+    });
+
+    let metrics_server_task = get_metrics_server_enabled().then(|| {
+        tokio::spawn(async move {
+            if let Err(e) = metrics::run().await {
+                error!("Metrics server error: {:?}", e);
+            }
+        })
+    });
+
     tokio::signal::ctrl_c().await?;
     info!("Received Ctrl+C, shutting down...");
 
     kafka_task.abort();
+    if let Some(indexer_task) = indexer_task {
+        indexer_task.abort();
+    }
+    if let Some(treasury_monitor_task) = treasury_monitor_task {
+        treasury_monitor_task.abort();
+    }
+    if let Some(metadata_server_task) = metadata_server_task {
+        metadata_server_task.abort();
+    }
+    if let Some(metrics_server_task) = metrics_server_task {
+        metrics_server_task.abort();
+    }
 
     info!("Application stopped");
 
@@ -0,0 +1,189 @@
+use crate::utils::solana_util;
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use serde_json::{Value, json};
+use solana_program::pubkey::Pubkey;
+use std::path::{self, PathBuf};
+use std::sync::Arc;
+use tokio::fs;
+use tracing::error;
+
+/// Shared state of the metadata server handlers.
+pub struct MetadataServerState {
+    pub assets_dir: PathBuf,
+    pub nft_metadata_dir: PathBuf,
+    pub public_url: String,
+}
+
+impl MetadataServerState {
+    /// Returns the public URL of an image asset.
+    fn asset_url(&self, file: &str) -> String {
+        format!("{}/assets/{}", self.public_url, file)
+    }
+}
+
+/// Serves the fungible reward token metadata.
+///
+/// Uses `<assets dir>/token.json` if present, otherwise generates it
+/// from `SOLANA_TOKEN_NAME`, `SOLANA_TOKEN_SYMBOL` and `<assets dir>/token.png`.
+pub async fn token_metadata(
+    State(state): State<Arc<MetadataServerState>>,
+) -> Result<Json<Value>, StatusCode> {
+    if let Some(document) = read_json(&state.assets_dir.join("token.json")).await? {
+        return Ok(Json(document));
+    }
+
+    Ok(Json(metadata_document(
+        solana_util::get_token_name(),
+        solana_util::get_token_symbol(),
+        "Reward token earned for correct answers in Solana Quiz.",
+        state.asset_url("token.png"),
+        vec![],
+    )))
+}
+
+/// Serves the fixed streak NFT metadata.
+///
+/// Uses `<assets dir>/nft.json` if present, otherwise generates it
+/// from `SOLANA_NFT_NAME`, `SOLANA_NFT_SYMBOL` and `<assets dir>/nft.png`.
+pub async fn nft_metadata(
+    State(state): State<Arc<MetadataServerState>>,
+) -> Result<Json<Value>, StatusCode> {
+    if let Some(document) = read_json(&state.assets_dir.join("nft.json")).await? {
+        return Ok(Json(document));
+    }
+
+    Ok(Json(fixed_nft_document(&state, vec![])))
+}
+
+/// Serves the streak NFT collection metadata.
+///
+/// Uses `<assets dir>/collection.json` if present, otherwise generates it
+/// from `SOLANA_NFT_COLLECTION_NAME`, `SOLANA_NFT_SYMBOL` and `<assets dir>/collection.png`.
+pub async fn collection_metadata(
+    State(state): State<Arc<MetadataServerState>>,
+) -> Result<Json<Value>, StatusCode> {
+    if let Some(document) = read_json(&state.assets_dir.join("collection.json")).await? {
+        return Ok(Json(document));
+    }
+
+    Ok(Json(metadata_document(
+        solana_util::get_nft_collection_name(),
+        solana_util::get_nft_symbol(),
+        "Collection of Solana Quiz streak achievements.",
+        state.asset_url("collection.png"),
+        vec![],
+    )))
+}
+
+/// Serves the metadata of revoked streak NFTs.
+///
+/// Uses `<assets dir>/revoked.json` if present, otherwise generates it
+/// from `SOLANA_NFT_SYMBOL` and `<assets dir>/revoked.png`.
+pub async fn revoked_metadata(
+    State(state): State<Arc<MetadataServerState>>,
+) -> Result<Json<Value>, StatusCode> {
+    if let Some(document) = read_json(&state.assets_dir.join("revoked.json")).await? {
+        return Ok(Json(document));
+    }
+
+    Ok(Json(metadata_document(
+        "Revoked Solana Quiz Achievement".to_string(),
+        solana_util::get_nft_symbol(),
+        "This Solana Quiz achievement has been revoked.",
+        state.asset_url("revoked.png"),
+        vec![json!({ "trait_type": "Status", "value": "Revoked" })],
+    )))
+}
+
+/// Serves the files of a single streak NFT (`/metadata/nft/{mint}.json`, `.svg` or `.png`).
+///
+/// For the JSON, uses the document rendered for the mint from the metadata template if present,
+/// otherwise generates the fixed streak NFT metadata for the mint on the fly.
+/// The badge artwork is served only if it was rendered for the mint.
+pub async fn nft_mint_file(
+    State(state): State<Arc<MetadataServerState>>,
+    Path(file): Path<String>,
+) -> Result<Response, StatusCode> {
+    let (mint, extension) = file.rsplit_once('.').ok_or(StatusCode::NOT_FOUND)?;
+    let mint_pubkey = mint.parse::<Pubkey>().map_err(|_| StatusCode::NOT_FOUND)?;
+    let path = state
+        .nft_metadata_dir
+        .join(format!("{}.{}", mint_pubkey, extension));
+
+    let content_type = match extension {
+        "json" => {
+            if let Some(document) = read_json(&path).await? {
+                return Ok(Json(document).into_response());
+            }
+
+            let document = fixed_nft_document(
+                &state,
+                vec![json!({ "trait_type": "Mint", "value": mint_pubkey.to_string() })],
+            );
+
+            return Ok(Json(document).into_response());
+        }
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        _ => return Err(StatusCode::NOT_FOUND),
+    };
+
+    match fs::read(&path).await {
+        Ok(bytes) => Ok(([(header::CONTENT_TYPE, content_type)], bytes).into_response()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            error!("Failed to read NFT badge at {}: {}", path.display(), e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Builds the fixed streak NFT metadata with the given attributes.
+fn fixed_nft_document(state: &MetadataServerState, attributes: Vec<Value>) -> Value {
+    metadata_document(
+        solana_util::get_nft_name(),
+        solana_util::get_nft_symbol(),
+        "Awarded for a streak of correct answers in Solana Quiz.",
+        state.asset_url("nft.png"),
+        attributes,
+    )
+}
+
+/// Builds a Metaplex-compliant metadata JSON document.
+fn metadata_document(
+    name: String,
+    symbol: String,
+    description: &str,
+    image: String,
+    attributes: Vec<Value>,
+) -> Value {
+    json!({
+        "name": name,
+        "symbol": symbol,
+        "description": description,
+        "image": image,
+        "attributes": attributes,
+        "properties": {
+            "files": [{ "uri": image, "type": "image/png" }],
+            "category": "image",
+        },
+    })
+}
+
+/// Reads a JSON document from disk, or returns `None` if the file does not exist.
+async fn read_json(path: &path::Path) -> Result<Option<Value>, StatusCode> {
+    match fs::read(path).await {
+        Ok(bytes) => serde_json::from_slice(&bytes).map(Some).map_err(|e| {
+            error!("Invalid metadata JSON at {}: {}", path.display(), e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => {
+            error!("Failed to read metadata JSON at {}: {}", path.display(), e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
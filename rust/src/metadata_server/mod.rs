@@ -0,0 +1,52 @@
+use crate::metadata_server::handlers::MetadataServerState;
+use crate::utils::{server_util, solana_util};
+use anyhow::Result;
+use axum::Router;
+use axum::routing::get;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tower_http::services::ServeDir;
+use tracing::info;
+
+pub mod handlers;
+
+/// Starts the built-in HTTP server for token/NFT metadata JSON and image assets.
+/// This function typically runs for the lifetime of the application.
+///
+/// Routes:
+/// * `GET /metadata/token.json` - fungible reward token metadata
+/// * `GET /metadata/nft.json` - fixed streak NFT metadata
+/// * `GET /metadata/collection.json` - streak NFT collection metadata
+/// * `GET /metadata/revoked.json` - metadata of revoked streak NFTs
+/// * `GET /metadata/nft/{mint}.json` - metadata of a single streak NFT
+/// * `GET /metadata/nft/{mint}.svg|png` - badge artwork of a single streak NFT
+/// * `GET /assets/*` - files from the assets directory
+pub async fn run() -> Result<()> {
+    let assets_dir = server_util::get_metadata_server_assets_dir();
+
+    let state = Arc::new(MetadataServerState {
+        assets_dir: assets_dir.clone(),
+        nft_metadata_dir: solana_util::get_nft_metadata_dir(),
+        public_url: server_util::get_metadata_server_public_url(),
+    });
+
+    let router = Router::new()
+        .route("/metadata/token.json", get(handlers::token_metadata))
+        .route("/metadata/nft.json", get(handlers::nft_metadata))
+        .route(
+            "/metadata/collection.json",
+            get(handlers::collection_metadata),
+        )
+        .route("/metadata/revoked.json", get(handlers::revoked_metadata))
+        .route("/metadata/nft/:file", get(handlers::nft_mint_file))
+        .nest_service("/assets", ServeDir::new(assets_dir))
+        .with_state(state);
+
+    let addr = server_util::get_metadata_server_addr();
+    let listener = TcpListener::bind(&addr).await?;
+    info!("Metadata server listening on {}", addr);
+
+    axum::serve(listener, router).await?;
+
+    Ok(())
+}
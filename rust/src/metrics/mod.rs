@@ -0,0 +1,125 @@
+use crate::models::reward::RewardBudget;
+use crate::utils::server_util;
+use anyhow::Result;
+use axum::Router;
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use prometheus::{
+    Encoder, Gauge, IntCounter, IntCounterVec, IntGaugeVec, TextEncoder, register_gauge,
+    register_int_counter, register_int_counter_vec, register_int_gauge_vec,
+};
+use std::sync::LazyLock;
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+/// Rewards and NFT requests held back by an exhausted emission budget, per budget.
+pub static BUDGET_EXCEEDED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "solana_quiz_budget_exceeded_total",
+        "Rewards and NFT requests held back by an exhausted emission budget",
+        &["budget"]
+    )
+    .expect("Failed to register solana_quiz_budget_exceeded_total")
+});
+
+/// Balance of every payer of the pool in lamports, as last read from the chain
+/// minus the fees of the transactions sent since.
+pub static PAYER_BALANCE: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "solana_quiz_payer_balance_lamports",
+        "Balance of every payer of the pool in lamports",
+        &["payer"]
+    )
+    .expect("Failed to register solana_quiz_payer_balance_lamports")
+});
+
+/// SOL balance of the authority, fee payer and rent payer wallets in lamports.
+pub static WALLET_SOL_BALANCE: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "solana_quiz_wallet_sol_balance_lamports",
+        "SOL balance of the authority, fee payer and rent payer wallets in lamports",
+        &["wallet"]
+    )
+    .expect("Failed to register solana_quiz_wallet_sol_balance_lamports")
+});
+
+/// Reward token balance of the treasury (the authority's token account), in tokens.
+pub static TREASURY_TOKEN_BALANCE: LazyLock<Gauge> = LazyLock::new(|| {
+    register_gauge!(
+        "solana_quiz_treasury_token_balance",
+        "Reward token balance of the treasury, in tokens"
+    )
+    .expect("Failed to register solana_quiz_treasury_token_balance")
+});
+
+/// Total supply of the reward token, in tokens.
+pub static TOKEN_SUPPLY: LazyLock<Gauge> = LazyLock::new(|| {
+    register_gauge!(
+        "solana_quiz_token_supply",
+        "Total supply of the reward token, in tokens"
+    )
+    .expect("Failed to register solana_quiz_token_supply")
+});
+
+/// 1 while a monitored balance is below its alert threshold, 0 otherwise.
+pub static TREASURY_LOW: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "solana_quiz_treasury_low",
+        "1 while a monitored balance is below its alert threshold",
+        &["balance"]
+    )
+    .expect("Failed to register solana_quiz_treasury_low")
+});
+
+/// Automatic mints to the treasury, made when it dropped below the watermark.
+pub static TREASURY_AUTO_MINTS: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "solana_quiz_treasury_auto_mints_total",
+        "Automatic mints to the treasury"
+    )
+    .expect("Failed to register solana_quiz_treasury_auto_mints_total")
+});
+
+/// Starts the HTTP server exposing the worker metrics to Prometheus.
+/// This function typically runs for the lifetime of the application.
+///
+/// Routes:
+/// * `GET /metrics` - metrics in the Prometheus text format
+pub async fn run() -> Result<()> {
+    // Export the counters from zero, so the first increase is visible to Prometheus
+    for budget in [
+        RewardBudget::UserDailyTokens,
+        RewardBudget::DailyTokens,
+        RewardBudget::DailyNfts,
+    ] {
+        BUDGET_EXCEEDED.with_label_values(&[budget.name()]);
+    }
+
+    let router = Router::new().route("/metrics", get(metrics));
+
+    let addr = server_util::get_metrics_server_addr();
+    let listener = TcpListener::bind(&addr).await?;
+    info!("Metrics server listening on {}", addr);
+
+    axum::serve(listener, router).await?;
+
+    Ok(())
+}
+
+/// Serves all registered metrics.
+async fn metrics() -> Response {
+    let encoder = TextEncoder::new();
+    let mut buffer = vec![];
+
+    if let Err(e) = encoder.encode(&prometheus::gather(), &mut buffer) {
+        error!("Failed to encode metrics: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    (
+        [(header::CONTENT_TYPE, encoder.format_type().to_string())],
+        buffer,
+    )
+        .into_response()
+}
@@ -1,17 +1,56 @@
+use crate::models::reward::RewardRejectionReason;
+use crate::utils::token_util;
+use anyhow::{Result, bail};
 use serde::{Deserialize, Serialize};
 
+/// Number of reward tokens, either whole (`5`) or as a decimal string (`"0.5"`).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum TokenAmount {
+    Whole(u64),
+    Decimal(String),
+}
+
+impl TokenAmount {
+    /// Converts the amount into base units of a mint with the given decimals.
+    pub fn to_base_units(&self, decimals: u8) -> Result<u64> {
+        match self {
+            TokenAmount::Whole(tokens) => token_util::tokens_to_base_units(*tokens, decimals),
+            TokenAmount::Decimal(amount) => token_util::parse_token_amount(amount, decimals),
+        }
+    }
+}
+
 /// Represents a reward earned by a user for completing a Solana quiz.
-#[derive(Serialize, Deserialize, Debug)]
+///
+/// The reward is given either in tokens (`earned_tokens`) or in base units
+/// of the mint (`earned_base_units`), exactly one of them must be set.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SolanaQuizReward {
     pub user_id: u64,
     pub user_wallet: String,
     pub quiz_id: u64,
     pub total_questions: u64,
     pub correct_answers: u64,
-    pub earned_tokens: u64,
+    #[serde(default)]
+    pub earned_tokens: Option<TokenAmount>,
+    #[serde(default)]
+    pub earned_base_units: Option<u64>,
     pub streak_days: u8,
 }
 
+impl SolanaQuizReward {
+    /// Returns the reward in base units of a mint with the given decimals.
+    pub fn reward_base_units(&self, decimals: u8) -> Result<u64> {
+        match (&self.earned_tokens, self.earned_base_units) {
+            (Some(earned_tokens), None) => earned_tokens.to_base_units(decimals),
+            (None, Some(earned_base_units)) => Ok(earned_base_units),
+            (Some(_), Some(_)) => bail!("Reward sets both earned_tokens and earned_base_units"),
+            (None, None) => bail!("Reward sets neither earned_tokens nor earned_base_units"),
+        }
+    }
+}
+
 /// Represents a successfully applied Solana quiz reward.
 ///
 /// This struct is sent to other services (e.g., Node.js) to indicate
@@ -21,3 +60,140 @@ pub struct SolanaQuizRewardApplied {
     pub user_id: u64,
     pub quiz_id: u64,
 }
+
+/// Represents a reward (or streak NFT) the worker refused to pay out.
+///
+/// `user_id` is known for token rewards only, NFT requests carry just the wallet.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SolanaQuizRewardFailed {
+    pub user_id: Option<u64>,
+    pub user_wallet: String,
+    pub quiz_id: u64,
+    pub reason: String,
+}
+
+/// Represents a reward rejected by validation, before any transaction was sent.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SolanaQuizRewardRejected {
+    pub user_id: u64,
+    pub user_wallet: String,
+    pub quiz_id: u64,
+    pub reason: RewardRejectionReason,
+    pub message: String,
+}
+
+/// Represents a reward paid out despite a failed anti-sybil check, for later review.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SolanaQuizRewardFlagged {
+    pub user_id: u64,
+    pub user_wallet: String,
+    pub quiz_id: u64,
+    pub reason: RewardRejectionReason,
+    pub message: String,
+}
+
+/// Represents a reward held for manual review instead of being paid out.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SolanaQuizRewardHeld {
+    pub user_id: u64,
+    pub user_wallet: String,
+    pub quiz_id: u64,
+    pub reason: RewardRejectionReason,
+    pub message: String,
+}
+
+/// Snapshot of a user's on-chain quiz stats, `earned_tokens` in base units of the reward mint.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SolanaQuizStats {
+    pub last_quiz_day: u64,
+    pub streak: u8,
+    pub total_quizzes: u64,
+    pub total_questions: u64,
+    pub correct_answers: u64,
+    pub earned_tokens: u64,
+}
+
+/// Represents a `QuizResultsUpdated` event emitted by the on-chain program.
+///
+/// Published by the program indexer together with the transaction
+/// signature and slot, so consumers can deduplicate replays.
+/// `earned_tokens` is in base units of the reward mint.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SolanaQuizResultsUpdated {
+    pub user_wallet: String,
+    pub quiz_id: u64,
+    pub total_questions: u64,
+    pub correct_answers: u64,
+    pub earned_tokens: u64,
+    pub before: SolanaQuizStats,
+    pub after: SolanaQuizStats,
+    pub signature: String,
+    pub slot: u64,
+}
+
+/// Represents a `StreakAchieved` event emitted by the on-chain program.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SolanaQuizStreakAchieved {
+    pub user_wallet: String,
+    pub quiz_id: u64,
+    pub streak: u8,
+    pub signature: String,
+    pub slot: u64,
+}
+
+/// Represents a request to mint a streak NFT for a user.
+///
+/// The user wallet and quiz id identify the achievement,
+/// so duplicate requests result in a single NFT.
+/// The optional stats are used to render the NFT metadata.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SolanaQuizNftRequested {
+    pub user_wallet: String,
+    pub quiz_id: u64,
+    pub streak_days: u8,
+    #[serde(default)]
+    pub total_quizzes: Option<u64>,
+    #[serde(default)]
+    pub total_questions: Option<u64>,
+    #[serde(default)]
+    pub correct_answers: Option<u64>,
+    #[serde(default)]
+    pub achieved_at: Option<i64>,
+}
+
+/// Represents a successfully minted streak NFT.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SolanaQuizNftMinted {
+    pub user_wallet: String,
+    pub quiz_id: u64,
+    pub mint_address: String,
+}
+
+/// Represents a streak passport NFT updated for a new milestone.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SolanaQuizNftUpdated {
+    pub user_wallet: String,
+    pub quiz_id: u64,
+    pub mint_address: String,
+}
+
+/// Blocklist action requested through the "solana-quiz-wallet-control" topic.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WalletControlAction {
+    Block,
+    Unblock,
+}
+
+/// Represents a request to block or unblock a wallet from receiving rewards.
+///
+/// `actor` identifies who requested the change in the audit log.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SolanaQuizWalletControl {
+    pub action: WalletControlAction,
+    pub wallet: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub actor: Option<String>,
+}
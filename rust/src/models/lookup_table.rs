@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Name of the store with the address lookup tables of reward transactions.
+pub const LOOKUP_TABLES_STORE: &str = "lookup_tables";
+
+/// Address lookup tables used by reward transactions, and how often
+/// every user token account was rewarded (to find the hot ones).
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct LookupTables {
+    /// Tables compiled into reward transactions, oldest first.
+    /// New token accounts are added to the last one.
+    pub active: Vec<String>,
+    /// Number of rewards paid per user token account.
+    pub recipients: HashMap<String, u64>,
+}
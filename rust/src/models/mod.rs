@@ -1 +1,6 @@
-pub mod kafka;
\ No newline at end of file
+pub mod kafka;
+pub mod lookup_table;
+pub mod nft;
+pub mod reward;
+pub mod sybil;
+pub mod wallet;
\ No newline at end of file
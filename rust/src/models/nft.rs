@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// Name of the store mapping user wallets to their streak passport NFT mint.
+pub const NFT_PASSPORTS_STORE: &str = "nft_passports";
+
+/// Steps of the streak NFT minting flow, in execution order.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NftMintStep {
+    #[default]
+    Requested,
+    MintCreated,
+    TokenAccountCreated,
+    TokenMinted,
+    MetadataCreated,
+    Completed,
+}
+
+/// Persisted progress of a single streak NFT mint.
+///
+/// Stores the mint keypair (base58) so an interrupted flow can be resumed
+/// from the last successful step instead of creating a new mint.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct NftMintProgress {
+    pub step: NftMintStep,
+    pub mint_keypair: Option<String>,
+    pub signatures: Vec<String>,
+}
+
+/// On-chain Metaplex metadata fields of an NFT.
+#[derive(Debug, Clone)]
+pub struct NftMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+/// Streak tier, reached once the streak is at least `min_streak` days.
+/// The optional color is used for the tier's badge artwork.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NftTier {
+    pub name: String,
+    pub min_streak: u8,
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+/// Template for the per-achievement NFT metadata.
+///
+/// `metadata` is the off-chain JSON document (name, description, image, attributes, ...).
+/// Its string values may contain placeholders like `{tier}` or `{streak}`,
+/// which are rendered separately for every mint.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NftMetadataTemplate {
+    #[serde(default)]
+    pub tiers: Vec<NftTier>,
+    pub metadata: serde_json::Value,
+}
@@ -0,0 +1,255 @@
+use crate::models::kafka::{SolanaQuizReward, TokenAmount};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+
+/// How the worker applies the reward policy to incoming rewards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RewardPolicyMode {
+    /// Pays the amount computed by Node, after checking it against the policy.
+    Verify,
+    /// Pays the amount computed by the policy, ignoring Node's amount.
+    Authoritative,
+}
+
+/// Multiplier applied once the streak is at least `min_streak` days.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StreakMultiplier {
+    pub min_streak: u8,
+    pub multiplier: f64,
+}
+
+/// Multiplier applied to rewards paid from `starts_at` (inclusive) to `ends_at` (exclusive),
+/// e.g. a "double token weekend".
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PromoMultiplier {
+    pub name: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub multiplier: f64,
+}
+
+/// Declarative reward policy of the worker.
+///
+/// The reward is `tokens_per_correct_answer * correct_answers`, plus `perfect_score_bonus`
+/// if all answers are correct, multiplied by the highest reached streak multiplier
+/// and all active promo multipliers, and capped at `max_tokens_per_reward`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RewardPolicy {
+    pub tokens_per_correct_answer: TokenAmount,
+    #[serde(default)]
+    pub perfect_score_bonus: Option<TokenAmount>,
+    #[serde(default)]
+    pub streak_multipliers: Vec<StreakMultiplier>,
+    #[serde(default)]
+    pub promo_multipliers: Vec<PromoMultiplier>,
+    #[serde(default)]
+    pub max_tokens_per_reward: Option<TokenAmount>,
+}
+
+/// Name of the store with the daily emission budget state.
+pub const REWARD_BUDGETS_STORE: &str = "reward_budgets";
+
+/// What the worker does with a reward or NFT request that exceeds an emission budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetExceededAction {
+    /// Keeps the message and republishes it once the next budget day starts.
+    Defer,
+    /// Drops the message and publishes `solana-quiz-reward-failed`.
+    Reject,
+    /// Keeps the reward for manual review (NFT requests are deferred instead).
+    Hold,
+}
+
+/// Daily emission budget of the worker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RewardBudget {
+    UserDailyTokens,
+    DailyTokens,
+    DailyNfts,
+}
+
+impl RewardBudget {
+    /// Returns the budget name used in metrics and failure reasons.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RewardBudget::UserDailyTokens => "user_daily_tokens",
+            RewardBudget::DailyTokens => "daily_tokens",
+            RewardBudget::DailyNfts => "daily_nfts",
+        }
+    }
+}
+
+/// Kafka message deferred by an exhausted budget, republished on a later day.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeferredMessage {
+    pub day: i64,
+    pub topic: String,
+    pub key: String,
+    pub payload: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+/// Emission budget usage of the current day (UTC), in base units of the reward token.
+///
+/// `rewards` holds the amount reserved per `wallet:quiz_id`, so a replayed reward
+/// is not counted twice.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct RewardBudgetState {
+    pub day: i64,
+    #[serde(default)]
+    pub rewards: HashMap<String, u64>,
+    #[serde(default)]
+    pub user_tokens: HashMap<String, u64>,
+    #[serde(default)]
+    pub tokens: u64,
+    #[serde(default)]
+    pub nfts: u64,
+    #[serde(default)]
+    pub deferred: Vec<DeferredMessage>,
+}
+
+/// What the worker does with a reward that fails validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationFailedAction {
+    /// Drops the reward and publishes `solana-quiz-reward-rejected`.
+    Reject,
+    /// Keeps the reward for manual review and publishes `solana-quiz-reward-held`.
+    Hold,
+}
+
+/// Name of the store with the streaks of wallets seen by the worker (off-chain mode).
+pub const REWARD_STREAKS_STORE: &str = "reward_streaks";
+
+/// Last known streak of a wallet and the day (UTC) it was reached.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct WalletStreak {
+    pub day: i64,
+    pub streak: u8,
+}
+
+/// Name of the store with the day (UTC) every wallet last reached the streak goal.
+pub const REWARD_MILESTONES_STORE: &str = "reward_milestones";
+
+/// Why an incoming reward was rejected by validation.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RewardRejectionReason {
+    /// The quiz has no questions, too many, or more correct answers than questions.
+    InvalidAnswers,
+    /// The amount is missing, malformed or does not match the reward policy.
+    InvalidAmount,
+    /// The amount is above the maximum reward.
+    AmountTooLarge,
+    /// The streak grew faster than one day per day.
+    InvalidStreak,
+    /// The wallet is not a valid on-curve public key.
+    InvalidWallet,
+    /// The wallet is owned by a program other than the System Program.
+    WalletNotSystemOwned,
+    /// The message is unsigned (in strict mode), signed with an unknown key,
+    /// or its signature does not match the payload.
+    InvalidSignature,
+    /// The wallet is on the blocklist.
+    WalletBlocked,
+    /// The wallet's first transaction is more recent than the minimum wallet age.
+    WalletTooNew,
+    /// The wallet holds less SOL than the minimum balance.
+    BalanceTooLow,
+    /// Too many users received rewards to the wallet within the window.
+    WalletSharedByUsers,
+    /// Too many users received rewards to wallets funded by the same source within the window.
+    FunderSharedByUsers,
+    /// The reward exceeds an emission budget (held rewards only).
+    BudgetExceeded,
+}
+
+/// Rejection of an incoming reward, with a human-readable explanation.
+#[derive(Debug, Clone)]
+pub struct RewardRejection {
+    pub reason: RewardRejectionReason,
+    pub message: String,
+}
+
+impl RewardRejection {
+    pub fn new(reason: RewardRejectionReason, message: impl Into<String>) -> Self {
+        Self {
+            reason,
+            message: message.into(),
+        }
+    }
+}
+
+/// Name of the store with the rewards held for manual review.
+pub const REWARD_HOLDS_STORE: &str = "reward_holds";
+
+/// A reward held for manual review, keyed by `wallet:quiz_id` in the holds store.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HeldReward {
+    pub reward: SolanaQuizReward,
+    pub reason: RewardRejectionReason,
+    pub message: String,
+    pub held_at: DateTime<Utc>,
+}
+
+/// What happened to a held reward approved in manual review.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApprovalOutcome {
+    /// The reward was paid out and confirmed.
+    Paid,
+    /// The reward was paid out, but confirming it failed with this error.
+    /// It must not be paid out again.
+    PaidUnconfirmed(String),
+    /// The reward exceeds an emission budget and is held again.
+    Held,
+    /// The reward was rejected, e.g. its wallet is blocked.
+    Rejected,
+}
+
+/// The token transfer of a reward, sent on its own or batched with other rewards.
+#[derive(Debug, Clone)]
+pub struct RewardTransfer {
+    pub user_wallet: Pubkey,
+    pub quiz_id: u64,
+    pub total_questions: u64,
+    pub correct_answers: u64,
+    /// Base units transferred to the user and recorded in the user's on-chain stats.
+    pub amount: u64,
+}
+
+/// Kafka header with the base64 signature of a reward message.
+pub const SIGNATURE_HEADER: &str = "x-signature";
+
+/// Kafka header with the id of the key a reward message is signed with.
+pub const SIGNATURE_KEY_ID_HEADER: &str = "x-signature-key-id";
+
+/// Kafka header with the time (Unix seconds) a reward message was signed at.
+pub const SIGNATURE_TIMESTAMP_HEADER: &str = "x-signature-timestamp";
+
+/// Kafka header with the unique id of a signed reward message.
+pub const SIGNATURE_NONCE_HEADER: &str = "x-signature-nonce";
+
+/// Name of the store with the nonces of the signed reward messages seen by the worker.
+pub const SIGNATURE_NONCES_STORE: &str = "signature_nonces";
+
+/// Nonces of signed reward messages, so a replayed message is rejected.
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct SignatureNonces {
+    /// Signing time (Unix seconds) of every nonce seen within the freshness window.
+    #[serde(default)]
+    pub seen: HashMap<String, i64>,
+    /// Nonces of messages deferred by the worker, accepted once more when republished.
+    #[serde(default)]
+    pub released: HashSet<String>,
+}
+
+/// Key the signatures of reward messages are verified with.
+#[derive(Debug, Clone)]
+pub enum SignatureKey {
+    /// Secret shared with the producer, for HMAC-SHA256 signatures.
+    HmacSha256(Vec<u8>),
+    /// Public key of the producer, for ed25519 signatures.
+    Ed25519([u8; 32]),
+}
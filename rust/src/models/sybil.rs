@@ -0,0 +1,51 @@
+use crate::models::reward::RewardRejection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Name of the store with the cached on-chain profiles of rewarded wallets.
+pub const SYBIL_PROFILES_STORE: &str = "sybil_profiles";
+
+/// Name of the store with the user ids seen per wallet and funding source.
+pub const SYBIL_LINKS_STORE: &str = "sybil_links";
+
+/// What the worker does with a reward that fails an anti-sybil check,
+/// ordered from the least to the most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SybilAction {
+    /// Pays the reward out and publishes `solana-quiz-reward-flagged`.
+    Flag,
+    /// Keeps the reward for manual review and publishes `solana-quiz-reward-held`.
+    Hold,
+    /// Drops the reward and publishes `solana-quiz-reward-rejected`.
+    Reject,
+}
+
+/// Cached on-chain profile of a wallet, as of `checked_at` (Unix timestamp).
+///
+/// The first signature and the funder never change once known, so only the balance
+/// is refreshed when the cache expires. `first_signature_at` is a lower bound
+/// (and `funder` is unknown) for wallets with too many signatures to page through.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct WalletProfile {
+    pub first_signature_at: Option<i64>,
+    pub funder: Option<String>,
+    pub balance: u64,
+    pub checked_at: i64,
+}
+
+/// User ids seen per wallet and per funding source, with the time (Unix timestamp)
+/// each user was last seen.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SybilLinks {
+    #[serde(default)]
+    pub wallets: HashMap<String, HashMap<u64, i64>>,
+    #[serde(default)]
+    pub funders: HashMap<String, HashMap<u64, i64>>,
+}
+
+/// Failed anti-sybil check of a reward, with the configured action.
+#[derive(Debug, Clone)]
+pub struct SybilFinding {
+    pub rejection: RewardRejection,
+    pub action: SybilAction,
+}
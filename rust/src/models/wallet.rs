@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Name of the store with the wallets blocked at runtime (through the control topic or CLI).
+pub const WALLET_BLOCKLIST_STORE: &str = "wallet_blocklist";
+
+/// Name of the append-only log of block, freeze, review and mint actions.
+pub const AUDIT_LOG: &str = "audit";
+
+/// A wallet blocked from receiving rewards.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockedWallet {
+    pub reason: String,
+    pub actor: String,
+    pub blocked_at: DateTime<Utc>,
+}
+
+/// Action recorded in the audit log.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    BlockWallet,
+    UnblockWallet,
+    FreezeTokenAccount,
+    ThawTokenAccount,
+    ApproveHeldReward,
+    RejectHeldReward,
+    MintTokens,
+}
+
+/// Entry of the audit log: who did what to which wallet, and when.
+///
+/// `signature` is set for actions sent as a Solana transaction.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AuditEntry {
+    pub at: DateTime<Utc>,
+    pub action: AuditAction,
+    pub wallet: String,
+    pub actor: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
@@ -0,0 +1,54 @@
+use crate::models::wallet::{AUDIT_LOG, AuditAction, AuditEntry};
+use crate::storage::json_log::JsonLog;
+use anyhow::Result;
+use chrono::Utc;
+use tracing::info;
+
+pub struct AuditApi {
+    log: JsonLog<AuditEntry>,
+}
+
+impl AuditApi {
+    /// Creates new API wrapper for the audit log in `<STORAGE_DIR>/audit.jsonl`
+    pub fn new() -> Self {
+        Self {
+            log: JsonLog::new(AUDIT_LOG),
+        }
+    }
+
+    /// Records a block, freeze, review or mint action in the audit log.
+    ///
+    /// # Arguments
+    /// * `action` - What was done.
+    /// * `wallet` - The wallet it was done to.
+    /// * `actor` - Who did it.
+    /// * `reason` - Why it was done, if known.
+    /// * `signature` - Signature of the transaction, for on-chain actions.
+    pub async fn record(
+        &self,
+        action: AuditAction,
+        wallet: &str,
+        actor: &str,
+        reason: Option<String>,
+        signature: Option<String>,
+    ) -> Result<()> {
+        let entry = AuditEntry {
+            at: Utc::now(),
+            action,
+            wallet: wallet.to_string(),
+            actor: actor.to_string(),
+            reason,
+            signature,
+        };
+
+        info!("Audit: {}", serde_json::to_string(&entry)?);
+
+        self.log.append(&entry).await
+    }
+}
+
+impl Default for AuditApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
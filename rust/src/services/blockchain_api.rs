@@ -1,22 +1,51 @@
+use crate::models::reward::RewardTransfer;
+use crate::services::lookup_table_api::LookupTableApi;
+use crate::services::payer_pool_api::PayerPoolApi;
 use crate::utils::solana_util;
-use anchor_client::Client;
+use anchor_client::{Client, ClientError, Program};
 use anchor_lang::declare_program;
-use anchor_spl::associated_token::get_associated_token_address;
-use anyhow::Result;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anyhow::{Result, anyhow};
+use futures::future::join_all;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_program::instruction::Instruction;
 use solana_program::pubkey::Pubkey;
 use solana_program::system_program;
+use solana_sdk::account::Account;
+use solana_sdk::address_lookup_table_account::AddressLookupTableAccount;
+use solana_sdk::bpf_loader_upgradeable;
 use solana_sdk::commitment_config::CommitmentConfig;
-use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use std::ops::Deref;
 use std::rc::Rc;
+use tracing::{debug, info, warn};
 
 declare_program!(solana_quiz_rewards);
 use crate::utils::solana_util::get_solana_streak_days;
-use solana_quiz_rewards::{accounts::QuizUserData, client::accounts, client::args};
+use anchor_lang::Discriminator;
+use solana_account_decoder::UiAccountEncoding;
+use solana_quiz_rewards::{
+    accounts::{QuizRewardReceipt, QuizUserData},
+    client::accounts,
+    client::args,
+    constants::QUIZ_USER_DATA_V1_SIZE,
+};
+
+/// Byte size of unversioned `QuizUserData` accounts, discriminator included
+const QUIZ_USER_DATA_V1_LEN: usize = 8 + QUIZ_USER_DATA_V1_SIZE as usize;
+
+/// Estimated compute units of the migrate/initialize/update/transfer instructions of a reward
+const PROGRAM_REWARD_COMPUTE_UNITS: u64 = 150_000;
 
 pub struct BlockchainApi {
-    rpc_endpoint: String,       // RPC endpoint URL
-    authority_keypair: Keypair, // Keypair of the payer/authority
-    mint_account: Keypair,      // Mint account keypair for token transfers
+    rpc_endpoint: String,        // RPC endpoint URL
+    authority_keypair: Keypair,  // Keypair of the token authority
+    fee_payer_keypair: Keypair,  // Keypair paying transaction fees
+    rent_payer_keypair: Keypair, // Keypair paying the rent of new accounts
+    payer_pool: PayerPoolApi,    // Pool of payers of the reward transactions
+    mint_account: Keypair,       // Mint account keypair for token transfers
 }
 
 impl BlockchainApi {
@@ -29,33 +58,99 @@ impl BlockchainApi {
         Self {
             rpc_endpoint,
             authority_keypair,
+            fee_payer_keypair: solana_util::get_fee_payer_keypair(),
+            rent_payer_keypair: solana_util::get_rent_payer_keypair(),
+            payer_pool: PayerPoolApi::new(),
             mint_account,
         }
     }
 
-    /// Creates a new instance with custom parameters (useful for testing)
+    /// Creates a new instance with custom parameters (useful for testing),
+    /// the authority also pays fees and rent, without a payer pool
     pub fn _with_params(
         rpc_endpoint: String,
         authority_keypair: Keypair,
         mint_account: Keypair,
     ) -> Self {
         Self {
+            payer_pool: PayerPoolApi::_with_params(RpcClient::new(rpc_endpoint.clone()), vec![]),
             rpc_endpoint,
+            fee_payer_keypair: authority_keypair.insecure_clone(),
+            rent_payer_keypair: authority_keypair.insecure_clone(),
             authority_keypair,
             mint_account,
         }
     }
 
-    /// Calls the quiz rewards program for a specific user
+    /// Derives the PDA of the quiz data account for a specific user
+    pub fn find_quiz_user_data_pda(user_pubkey: &Pubkey) -> Pubkey {
+        let user_seeds = &[b"user_data_v2", user_pubkey.as_ref()];
+        let (quiz_user_pda, _bump) =
+            Pubkey::find_program_address(user_seeds, &solana_quiz_rewards::ID);
+
+        quiz_user_pda
+    }
+
+    /// Derives the PDA of the program config, holding the reward authority
+    pub fn find_rewards_config_pda() -> Pubkey {
+        let (config_pda, _bump) =
+            Pubkey::find_program_address(&[b"config"], &solana_quiz_rewards::ID);
+
+        config_pda
+    }
+
+    /// Derives the PDA of the reward receipt for a specific user and quiz
+    pub fn find_quiz_reward_receipt_pda(user_pubkey: &Pubkey, quiz_id: u64) -> Pubkey {
+        let receipt_seeds = &[
+            b"quiz_receipt".as_ref(),
+            user_pubkey.as_ref(),
+            &quiz_id.to_le_bytes(),
+        ];
+        let (receipt_pda, _bump) =
+            Pubkey::find_program_address(receipt_seeds, &solana_quiz_rewards::ID);
+
+        receipt_pda
+    }
+
+    /// Fetches the reward receipt for a specific user and quiz,
+    /// returning `None` if the reward has not been applied yet
+    pub async fn get_quiz_reward_receipt(
+        &self,
+        user_pubkey: &Pubkey,
+        quiz_id: u64,
+    ) -> Result<Option<QuizRewardReceipt>> {
+        let payer_rc = Rc::new(&self.fee_payer_keypair);
+
+        let provider = Client::new_with_options(
+            self.rpc_endpoint.parse()?,
+            payer_rc.clone(),
+            CommitmentConfig::confirmed(),
+        );
+        let program = provider.program(solana_quiz_rewards::ID)?;
+
+        let receipt_pda = Self::find_quiz_reward_receipt_pda(user_pubkey, quiz_id);
+
+        match program.account::<QuizRewardReceipt>(receipt_pda).await {
+            Ok(receipt) => Ok(Some(receipt)),
+            Err(ClientError::AccountNotFound) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Calls the quiz rewards program for a specific user.
+    ///
+    /// `transfer_amount` (base units) is transferred to the user and recorded
+    /// in the user's stats.
     pub async fn call_program_rewards(
         &self,
         user_pubkey: &Pubkey,
+        quiz_id: u64,
         total_questions: u64,
         correct_answers: u64,
-        earned_tokens: u64,
+        transfer_amount: u64,
     ) -> Result<QuizUserData> {
-        // Wrap authority keypair in Rc to share ownership
-        let payer_rc = Rc::new(&self.authority_keypair);
+        // Wrap fee payer keypair in Rc to share ownership
+        let payer_rc = Rc::new(&self.fee_payer_keypair);
 
         // Create Anchor client provider
         let provider = Client::new_with_options(
@@ -68,22 +163,317 @@ impl BlockchainApi {
         let program = provider.program(program_id)?;
 
         // Derive the user's PDA for quiz data
-        let user_seeds = &[b"user_data_v2", user_pubkey.as_ref()];
-        let (quiz_user_pda, _bump) = Pubkey::find_program_address(user_seeds, &program_id);
+        let quiz_user_pda = Self::find_quiz_user_data_pda(user_pubkey);
+
+        // Skip the transaction if the reward for this quiz was already applied
+        if let Some(receipt) = self.get_quiz_reward_receipt(user_pubkey, quiz_id).await? {
+            info!("Reward already applied: {:?}", receipt);
+            let quiz_user_data_account: QuizUserData = program.account(quiz_user_pda).await?;
+            return Ok(quiz_user_data_account);
+        }
+
+        // Log start of transaction
+        info!("Sending transaction with migrate, initialize, update, transfer instructions");
+
+        let transfer = RewardTransfer {
+            user_wallet: *user_pubkey,
+            quiz_id,
+            total_questions,
+            correct_answers,
+            amount: transfer_amount,
+        };
+        let instructions = self.reward_instructions(&program, &transfer)?;
+
+        // Send the instructions in a single v0 transaction
+        let lookup_tables = LookupTableApi::new().load_active().await?;
+        let signature = self
+            .send_v0_transaction(&program, &instructions, &lookup_tables)
+            .await?;
+        info!("Transaction confirmed: {}", signature);
+
+        // Fetch and display user quiz account data
+        let quiz_user_data_account: QuizUserData = program.account(quiz_user_pda).await?;
+        debug!("Quiz user account data: {:?}", quiz_user_data_account);
+
+        Ok(quiz_user_data_account)
+    }
+
+    /// Calls the quiz rewards program for several users, packing as many
+    /// migrate/initialize/update/transfer instruction sets per v0 transaction
+    /// as the size and compute limits allow, with the accounts in the active
+    /// lookup tables resolved through them.
+    ///
+    /// Rewards already applied are skipped. When a transaction fails, each of its
+    /// rewards is retried in its own transaction with `call_program_rewards`, but only
+    /// once it definitely did not land (it failed, or its blockhash expired).
+    ///
+    /// # Arguments
+    /// * `transfers` - the rewards to apply
+    /// * `max_rewards` - maximum number of rewards per transaction
+    ///
+    /// # Returns
+    /// * The user's quiz data after every reward, or its error, in order
+    pub async fn call_program_rewards_batch(
+        &self,
+        transfers: &[RewardTransfer],
+        max_rewards: usize,
+    ) -> Result<Vec<Result<QuizUserData>>> {
+        let payer_rc = Rc::new(&self.fee_payer_keypair);
+
+        let provider = Client::new_with_options(
+            self.rpc_endpoint.parse()?,
+            payer_rc.clone(),
+            CommitmentConfig::confirmed(),
+        );
+        let program = provider.program(solana_quiz_rewards::ID)?;
+
+        let mut results: Vec<Option<Result<QuizUserData>>> =
+            transfers.iter().map(|_| None).collect();
+
+        // Only rewards not applied yet are sent
+        let mut pending = vec![];
+        let mut payouts = vec![];
+        for (index, transfer) in transfers.iter().enumerate() {
+            let user_pubkey = &transfer.user_wallet;
+
+            if let Some(receipt) = self
+                .get_quiz_reward_receipt(user_pubkey, transfer.quiz_id)
+                .await?
+            {
+                info!("Reward already applied: {:?}", receipt);
+                let quiz_user_data_pda = Self::find_quiz_user_data_pda(user_pubkey);
+                results[index] = Some(
+                    program
+                        .account(quiz_user_data_pda)
+                        .await
+                        .map_err(Into::into),
+                );
+                continue;
+            }
+
+            pending.push(index);
+            payouts.push((*user_pubkey, self.reward_instructions(&program, transfer)?));
+        }
+
+        let lookup_tables = LookupTableApi::new().load_active().await?;
+
+        // Every payer of the pool takes as much space in a transaction as the fee payer
+        let payer_pubkey = self
+            .payer_pool
+            .payers()
+            .first()
+            .map_or_else(|| payer_rc.pubkey(), |payer| payer.pubkey());
+        let batches = solana_util::pack_payouts(
+            &payouts,
+            &payer_pubkey,
+            &lookup_tables,
+            PROGRAM_REWARD_COMPUTE_UNITS,
+            max_rewards,
+        );
+
+        // One transaction per payer of the pool is in flight at once
+        let program = &program;
+        let lookup_tables = &lookup_tables;
+        for wave in batches.chunks(self.payer_pool.payers().len().max(1)) {
+            let sent = join_all(wave.iter().map(|batch| {
+                let instructions: Vec<Instruction> = batch
+                    .iter()
+                    .flat_map(|payout| payouts[*payout].1.iter().cloned())
+                    .collect();
+
+                async move {
+                    self.try_send_v0_transaction(program, &instructions, lookup_tables)
+                        .await
+                }
+            }))
+            .await;
+
+            for (batch, result) in wave.iter().zip(sent) {
+                match result {
+                    Ok(Ok(signature)) => {
+                        info!("Applied {} rewards: {}", batch.len(), signature);
+
+                        for payout in batch {
+                            let quiz_user_data_pda =
+                                Self::find_quiz_user_data_pda(&payouts[*payout].0);
+                            results[pending[*payout]] = Some(
+                                program
+                                    .account(quiz_user_data_pda)
+                                    .await
+                                    .map_err(Into::into),
+                            );
+                        }
+                    }
+                    // Resending could apply the rewards twice
+                    Err(e) => {
+                        warn!(
+                            "Batch of {} rewards not resent, its status is unknown: {:?}",
+                            batch.len(),
+                            e
+                        );
+
+                        for payout in batch {
+                            results[pending[*payout]] = Some(Err(anyhow!("{:?}", e)));
+                        }
+                    }
+                    Ok(Err(e)) if batch.len() == 1 => results[pending[batch[0]]] = Some(Err(e)),
+                    Ok(Err(e)) => {
+                        warn!(
+                            "Batch of {} rewards failed, sending them one by one: {:?}",
+                            batch.len(),
+                            e
+                        );
+
+                        for payout in batch {
+                            let transfer = &transfers[pending[*payout]];
+                            results[pending[*payout]] = Some(
+                                self.call_program_rewards(
+                                    &transfer.user_wallet,
+                                    transfer.quiz_id,
+                                    transfer.total_questions,
+                                    transfer.correct_answers,
+                                    transfer.amount,
+                                )
+                                .await,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Sends a v0 transaction paid by the next payer of the pool (or the fee payer without one)
+    /// and signed by the keypairs it requires among the payer, rent payer and authority,
+    /// and waits for confirmation
+    async fn send_v0_transaction<C: Deref<Target = impl Signer> + Clone>(
+        &self,
+        program: &Program<C>,
+        instructions: &[Instruction],
+        lookup_tables: &[AddressLookupTableAccount],
+    ) -> Result<Signature> {
+        self.try_send_v0_transaction(program, instructions, lookup_tables)
+            .await?
+    }
+
+    /// Sends a v0 transaction like `send_v0_transaction`. If the confirmation fails,
+    /// waits until the transaction landed or can no longer land.
+    ///
+    /// # Returns
+    /// * The settled result: the signature, or the error of a transaction that did not land
+    /// * An error if the transaction could not be sent or settled, it must not be resent then
+    async fn try_send_v0_transaction<C: Deref<Target = impl Signer> + Clone>(
+        &self,
+        program: &Program<C>,
+        instructions: &[Instruction],
+        lookup_tables: &[AddressLookupTableAccount],
+    ) -> Result<Result<Signature>> {
+        let rpc_client = program.async_rpc();
+        let payer = self
+            .payer_pool
+            .acquire()
+            .await
+            .unwrap_or(&self.fee_payer_keypair);
+
+        let recent_blockhash = rpc_client.get_latest_blockhash().await?;
+        let transaction = solana_util::create_v0_transaction(
+            instructions,
+            &payer.pubkey(),
+            &[payer, &self.rent_payer_keypair, &self.authority_keypair],
+            lookup_tables,
+            recent_blockhash,
+        )?;
+
+        let result = match rpc_client.send_and_confirm_transaction(&transaction).await {
+            Ok(signature) => Ok(signature),
+            Err(e) => {
+                let signature = transaction.signatures[0];
+                let landed =
+                    solana_util::settle_transaction(&rpc_client, &signature, &recent_blockhash)
+                        .await;
+
+                match landed {
+                    Ok(true) => {
+                        warn!("Transaction {} landed despite: {:?}", signature, e);
+                        Ok(signature)
+                    }
+                    Ok(false) => Err(e.into()),
+                    Err(settle_error) => {
+                        self.payer_pool.invalidate(&payer.pubkey());
+                        return Err(anyhow!(
+                            "Transaction {} may have landed ({:?}), its status is unknown: {:?}",
+                            signature,
+                            e,
+                            settle_error
+                        ));
+                    }
+                }
+            }
+        };
+
+        // Track the balance of the payer
+        match &result {
+            Ok(_) => self
+                .payer_pool
+                .charge(&payer.pubkey(), transaction.signatures.len()),
+            Err(_) => self.payer_pool.invalidate(&payer.pubkey()),
+        }
+
+        Ok(result)
+    }
+
+    /// Builds the migrate, initialize, update and transfer instructions of a reward
+    fn reward_instructions<C: Deref<Target = impl Signer> + Clone>(
+        &self,
+        program: &Program<C>,
+        transfer: &RewardTransfer,
+    ) -> Result<Vec<Instruction>> {
+        let authority_pubkey = self.authority_keypair.pubkey();
+        let rent_payer_pubkey = self.rent_payer_keypair.pubkey();
+        let user_pubkey = &transfer.user_wallet;
+
+        // Derive the user's PDA for quiz data
+        let quiz_user_pda = Self::find_quiz_user_data_pda(user_pubkey);
+
+        // Derive the receipt PDA for this quiz
+        let receipt_pda = Self::find_quiz_reward_receipt_pda(user_pubkey, transfer.quiz_id);
+
+        // Derive the config PDA, only its reward authority may apply rewards
+        let config_pda = Self::find_rewards_config_pda();
 
         // Mint and associated token addresses
         let mint_pubkey: Pubkey = self.mint_account.pubkey();
-        let sender_ata = get_associated_token_address(&payer_rc.pubkey(), &mint_pubkey);
-        let recipient_ata = get_associated_token_address(&user_pubkey, &mint_pubkey);
+        let token_program_id = solana_util::get_token_program_id();
+        let sender_ata = get_associated_token_address_with_program_id(
+            &authority_pubkey,
+            &mint_pubkey,
+            &token_program_id,
+        );
+        let recipient_ata = get_associated_token_address_with_program_id(
+            user_pubkey,
+            &mint_pubkey,
+            &token_program_id,
+        );
 
-        // Log start of transaction
-        println!("\nSend transaction with initialize, update, transfer instructions");
+        // Build migrate instruction (no-op for new or already migrated accounts)
+        let migrate_instructions = program
+            .request()
+            .accounts(accounts::MigrateUserData {
+                payer: rent_payer_pubkey,
+                user: *user_pubkey,
+                quiz_user_data: quiz_user_pda,
+                system_program: system_program::ID,
+            })
+            .args(args::MigrateUserData)
+            .instructions()?;
 
         // Build initialize instruction
         let initialize_instructions = program
             .request()
             .accounts(accounts::Initialize {
-                payer: payer_rc.pubkey(),
+                payer: rent_payer_pubkey,
                 user: *user_pubkey, // Dereference Pubkey reference
                 quiz_user_data: quiz_user_pda,
                 system_program: system_program::ID,
@@ -95,54 +485,248 @@ impl BlockchainApi {
         let update_instructions = program
             .request()
             .accounts(accounts::UpdateQuizResults {
+                payer: rent_payer_pubkey,
+                authority: authority_pubkey,
+                config: config_pda,
                 user: *user_pubkey,
                 quiz_user_data: quiz_user_pda,
+                quiz_reward_receipt: receipt_pda,
+                system_program: system_program::ID,
             })
             .args(args::UpdateQuizResults {
-                total_questions,
-                correct_answers,
-                earned_tokens,
+                quiz_id: transfer.quiz_id,
+                total_questions: transfer.total_questions,
+                correct_answers: transfer.correct_answers,
+                earned_tokens: transfer.amount,
                 streak_days: get_solana_streak_days(),
             })
             .instructions()?;
 
+        let mut instructions = migrate_instructions;
+        instructions.extend(initialize_instructions);
+        instructions.extend(update_instructions);
+
+        // Quizzes earning nothing are only recorded, the program rejects empty transfers
+        if transfer.amount == 0 {
+            return Ok(instructions);
+        }
+
         // Build token transfer instruction
         let transfer_instructions = program
             .request()
             .accounts(accounts::TransferTokens {
-                signer: payer_rc.pubkey().into(),
-                mint: mint_pubkey.into(),
-                sender_token_account: sender_ata.into(),
-                recipient_token_account: recipient_ata.into(),
-                token_program: anchor_spl::token::ID,
+                signer: authority_pubkey,
+                config: config_pda,
+                user: *user_pubkey,
+                mint: mint_pubkey,
+                sender_token_account: sender_ata,
+                recipient_token_account: recipient_ata,
+                quiz_reward_receipt: receipt_pda,
+                token_program: token_program_id,
             })
             .args(args::TransferTokens {
-                amount: earned_tokens,
+                quiz_id: transfer.quiz_id,
+                amount: transfer.amount,
             })
             .instructions()?;
 
-        // Combine instructions into a single transaction
-        let mut request = program.request().signer(payer_rc.as_ref());
+        instructions.extend(transfer_instructions);
+
+        Ok(instructions)
+    }
+
+    /// Sets the authority allowed to apply quiz rewards, signed by the authority keypair,
+    /// which must be the upgrade authority of the program
+    pub async fn set_reward_authority(&self, reward_authority: &Pubkey) -> Result<Signature> {
+        let payer_rc = Rc::new(&self.authority_keypair);
+
+        let provider = Client::new_with_options(
+            self.rpc_endpoint.parse()?,
+            payer_rc.clone(),
+            CommitmentConfig::confirmed(),
+        );
+
+        let program_id = solana_quiz_rewards::ID;
+        let program = provider.program(program_id)?;
 
-        for ix in initialize_instructions {
-            request = request.instruction(ix);
+        let signature = program
+            .request()
+            .accounts(accounts::SetRewardAuthority {
+                payer: self.authority_keypair.pubkey(),
+                config: Self::find_rewards_config_pda(),
+                program: program_id,
+                program_data: Pubkey::find_program_address(
+                    &[program_id.as_ref()],
+                    &bpf_loader_upgradeable::id(),
+                )
+                .0,
+                system_program: system_program::ID,
+            })
+            .args(args::SetRewardAuthority {
+                authority: *reward_authority,
+            })
+            .send()
+            .await?;
+
+        Ok(signature)
+    }
+
+    /// Migrates all unversioned quiz user data accounts to the current layout version,
+    /// sending up to `batch_size` migrate instructions per transaction
+    pub async fn migrate_user_data_accounts(&self, batch_size: usize) -> Result<Vec<Signature>> {
+        let payer_rc = Rc::new(&self.fee_payer_keypair);
+
+        let provider = Client::new_with_options(
+            self.rpc_endpoint.parse()?,
+            payer_rc.clone(),
+            CommitmentConfig::confirmed(),
+        );
+
+        let program_id = solana_quiz_rewards::ID;
+        let program = provider.program(program_id)?;
+
+        // Fetch every unversioned account that carries the QuizUserData discriminator
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    0,
+                    QuizUserData::DISCRIMINATOR.to_vec(),
+                )),
+                RpcFilterType::DataSize(QUIZ_USER_DATA_V1_LEN as u64),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        let program_accounts = program
+            .async_rpc()
+            .get_program_accounts_with_config(&program_id, config)
+            .await?;
+
+        // Collect the wallets of accounts that still need a migration
+        let user_pubkeys: Vec<Pubkey> = program_accounts
+            .iter()
+            .filter_map(|(_, account)| Self::outdated_user_wallet(account))
+            .collect();
+
+        info!(
+            "Found {} of {} quiz user accounts to migrate",
+            user_pubkeys.len(),
+            program_accounts.len()
+        );
+
+        let mut signatures = vec![];
+
+        for batch in user_pubkeys.chunks(batch_size.max(1)) {
+            let mut request = program.request().signer(&self.rent_payer_keypair);
+
+            for user_pubkey in batch {
+                let instructions = program
+                    .request()
+                    .accounts(accounts::MigrateUserData {
+                        payer: self.rent_payer_keypair.pubkey(),
+                        user: *user_pubkey,
+                        quiz_user_data: Self::find_quiz_user_data_pda(user_pubkey),
+                        system_program: system_program::ID,
+                    })
+                    .args(args::MigrateUserData)
+                    .instructions()?;
+
+                for ix in instructions {
+                    request = request.instruction(ix);
+                }
+            }
+
+            let signature = request.send().await?;
+            info!("Migrated {} accounts: {}", batch.len(), signature);
+
+            signatures.push(signature);
         }
-        for ix in update_instructions {
-            request = request.instruction(ix);
+
+        Ok(signatures)
+    }
+
+    /// Returns the user wallet of a quiz user data account if it is on the unversioned layout,
+    /// the only one the program's `migrate_user_data` upgrades.
+    ///
+    /// Versioned accounts are never migrated: the program is a no-op for the current version
+    /// and rejects any other.
+    fn outdated_user_wallet(account: &Account) -> Option<Pubkey> {
+        if account.data.len() != QUIZ_USER_DATA_V1_LEN {
+            return None;
         }
-        for ix in transfer_instructions {
-            request = request.instruction(ix);
+
+        // Unversioned layout starts with the user wallet
+        Pubkey::try_from(&account.data[8..40]).ok()
+    }
+}
+
+impl Default for BlockchainApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AccountSerialize;
+    use solana_quiz_rewards::constants::QUIZ_USER_DATA_VERSION;
+
+    fn account(version: u8, user_wallet: Pubkey) -> Account {
+        let user_data = QuizUserData {
+            version,
+            user_wallet,
+            last_quiz_day: 20_000,
+            streak: 3,
+            total_quizzes: 5,
+            total_questions: 50,
+            correct_answers: 40,
+            earned_tokens: 120,
+            reserved: [0; 64],
+        };
+        let mut data = vec![];
+        user_data.try_serialize(&mut data).unwrap();
+
+        Account {
+            data,
+            ..Account::default()
         }
+    }
 
-        // Send transaction
-        let signature = request.send().await?;
-        println!("   Transaction confirmed: {}", signature);
+    #[test]
+    fn current_version_is_not_migrated() {
+        let account = account(QUIZ_USER_DATA_VERSION, Pubkey::new_unique());
 
-        // Fetch and display user quiz account data
-        println!("\nFetch quiz user account data");
-        let quiz_user_data_account: QuizUserData = program.account(quiz_user_pda).await?;
-        println!("   Value: {:?}", quiz_user_data_account);
+        assert_eq!(BlockchainApi::outdated_user_wallet(&account), None);
+    }
 
-        Ok(quiz_user_data_account)
+    #[test]
+    fn other_versions_are_not_migrated() {
+        // The program rejects versioned accounts it cannot upgrade
+        for version in [QUIZ_USER_DATA_VERSION - 1, QUIZ_USER_DATA_VERSION + 1] {
+            let account = account(version, Pubkey::new_unique());
+
+            assert_eq!(BlockchainApi::outdated_user_wallet(&account), None);
+        }
+    }
+
+    #[test]
+    fn unversioned_layout_is_migrated() {
+        let user_wallet = Pubkey::new_unique();
+        let mut data = QuizUserData::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(user_wallet.as_ref());
+        data.resize(QUIZ_USER_DATA_V1_LEN, 0);
+        let account = Account {
+            data,
+            ..Account::default()
+        };
+
+        assert_eq!(
+            BlockchainApi::outdated_user_wallet(&account),
+            Some(user_wallet)
+        );
     }
 }
@@ -0,0 +1,318 @@
+use crate::models::lookup_table::{LOOKUP_TABLES_STORE, LookupTables};
+use crate::services::blockchain_api::solana_quiz_rewards;
+use crate::storage::json_store::JsonStore;
+use crate::utils::solana_util;
+use anyhow::{Result, bail};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::address_lookup_table::instruction::{
+    close_lookup_table, create_lookup_table, deactivate_lookup_table, extend_lookup_table,
+};
+use solana_program::address_lookup_table::state::{AddressLookupTable, LOOKUP_TABLE_MAX_ADDRESSES};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use solana_program::{clock::Slot, system_program};
+use solana_sdk::address_lookup_table_account::AddressLookupTableAccount;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use tracing::{info, warn};
+
+/// Maximum number of addresses added per extend transaction (32 bytes each).
+const EXTEND_ADDRESSES_PER_TRANSACTION: usize = 20;
+
+pub struct LookupTableApi {
+    rpc_client: RpcClient,
+    authority_keypair: Keypair,
+    fee_payer_keypair: Keypair,
+    rent_payer_keypair: Keypair,
+    mint_pubkey: Pubkey,
+    token_program_id: Pubkey,
+    store: JsonStore<LookupTables>,
+    hot_threshold: Option<u64>,
+}
+
+/// API wrapper for the address lookup tables (ALTs) of reward transactions.
+///
+/// The tables hold the accounts of every reward (program, mint, token programs, treasury
+/// token account) and the token accounts of frequently rewarded users, so a v0 transaction
+/// references each of them with a one-byte index instead of a 32-byte address.
+impl LookupTableApi {
+    /// Creates a new instance using environment variables.
+    pub fn new() -> Self {
+        Self {
+            rpc_client: solana_util::create_rpc_client(),
+            authority_keypair: solana_util::get_authority_keypair(),
+            fee_payer_keypair: solana_util::get_fee_payer_keypair(),
+            rent_payer_keypair: solana_util::get_rent_payer_keypair(),
+            mint_pubkey: solana_util::get_mint_account().pubkey(),
+            token_program_id: solana_util::get_token_program_id(),
+            store: JsonStore::new(LOOKUP_TABLES_STORE),
+            hot_threshold: solana_util::get_solana_lookup_table_hot_threshold(),
+        }
+    }
+
+    /// Returns the accounts shared by every reward transaction.
+    pub fn base_addresses(&self) -> Vec<Pubkey> {
+        vec![
+            solana_quiz_rewards::ID,
+            self.mint_pubkey,
+            self.token_program_id,
+            spl_associated_token_account::ID,
+            system_program::ID,
+            self.token_account(&self.authority_keypair.pubkey()),
+        ]
+    }
+
+    /// Creates a lookup table with the base addresses and makes it the active table
+    /// new token accounts are added to. The authority owns the table, the rent payer pays its rent.
+    ///
+    /// # Returns
+    /// * The address of the table and the `Signature` of the creation transaction
+    pub async fn create(&self) -> Result<(Pubkey, Signature)> {
+        let authority_pubkey = self.authority_keypair.pubkey();
+        let rent_payer_pubkey = self.rent_payer_keypair.pubkey();
+
+        // The table address is derived from a recent slot
+        let recent_slot = self
+            .rpc_client
+            .get_slot_with_commitment(CommitmentConfig::finalized())
+            .await?;
+        let (create_ix, table_pubkey) =
+            create_lookup_table(authority_pubkey, rent_payer_pubkey, recent_slot);
+        let extend_ix = extend_lookup_table(
+            table_pubkey,
+            authority_pubkey,
+            Some(rent_payer_pubkey),
+            self.base_addresses(),
+        );
+
+        let signature = self.send(&[create_ix, extend_ix]).await?;
+
+        let mut tables = self.store.load().await?;
+        tables.active.push(table_pubkey.to_string());
+        self.store.save(&tables).await?;
+
+        Ok((table_pubkey, signature))
+    }
+
+    /// Adds addresses to a lookup table, skipping those it already holds.
+    ///
+    /// # Arguments
+    /// * `table_pubkey` - the lookup table
+    /// * `addresses` - the addresses to add
+    ///
+    /// # Returns
+    /// * The `Signature` of every extend transaction
+    pub async fn extend(
+        &self,
+        table_pubkey: &Pubkey,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<Signature>> {
+        let table = self.fetch(table_pubkey).await?;
+
+        let mut new_addresses: Vec<Pubkey> = vec![];
+        for address in addresses {
+            if !table.addresses.contains(address) && !new_addresses.contains(address) {
+                new_addresses.push(*address);
+            }
+        }
+
+        if table.addresses.len() + new_addresses.len() > LOOKUP_TABLE_MAX_ADDRESSES {
+            bail!(
+                "The lookup table {} holds {} addresses, {} more do not fit (max {})",
+                table_pubkey,
+                table.addresses.len(),
+                new_addresses.len(),
+                LOOKUP_TABLE_MAX_ADDRESSES
+            );
+        }
+
+        let authority_pubkey = self.authority_keypair.pubkey();
+        let rent_payer_pubkey = self.rent_payer_keypair.pubkey();
+        let mut signatures = vec![];
+
+        for chunk in new_addresses.chunks(EXTEND_ADDRESSES_PER_TRANSACTION) {
+            let extend_ix = extend_lookup_table(
+                *table_pubkey,
+                authority_pubkey,
+                Some(rent_payer_pubkey),
+                chunk.to_vec(),
+            );
+            signatures.push(self.send(&[extend_ix]).await?);
+        }
+
+        Ok(signatures)
+    }
+
+    /// Returns the token accounts of the most rewarded users, most rewarded first.
+    ///
+    /// # Arguments
+    /// * `count` - maximum number of token accounts
+    pub async fn hot_token_accounts(&self, count: usize) -> Result<Vec<Pubkey>> {
+        let mut recipients: Vec<(String, u64)> =
+            self.store.load().await?.recipients.into_iter().collect();
+        recipients.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        recipients
+            .into_iter()
+            .take(count)
+            .map(|(token_account, _)| Ok(token_account.parse::<Pubkey>()?))
+            .collect()
+    }
+
+    /// Deactivates a lookup table, so it is no longer used nor extended.
+    /// It can be closed with `close` once the deactivation is no longer recent (~513 slots).
+    ///
+    /// # Returns
+    /// * `Signature` of the deactivation transaction
+    pub async fn deactivate(&self, table_pubkey: &Pubkey) -> Result<Signature> {
+        let deactivate_ix = deactivate_lookup_table(*table_pubkey, self.authority_keypair.pubkey());
+        let signature = self.send(&[deactivate_ix]).await?;
+
+        let mut tables = self.store.load().await?;
+        tables
+            .active
+            .retain(|table| *table != table_pubkey.to_string());
+        self.store.save(&tables).await?;
+
+        Ok(signature)
+    }
+
+    /// Closes a deactivated lookup table, returning its rent to the rent payer.
+    ///
+    /// # Returns
+    /// * `Signature` of the close transaction
+    pub async fn close(&self, table_pubkey: &Pubkey) -> Result<Signature> {
+        let close_ix = close_lookup_table(
+            *table_pubkey,
+            self.authority_keypair.pubkey(),
+            self.rent_payer_keypair.pubkey(),
+        );
+
+        self.send(&[close_ix]).await
+    }
+
+    /// Returns the addresses of the active lookup tables, oldest first.
+    pub async fn list(&self) -> Result<Vec<Pubkey>> {
+        self.store
+            .load()
+            .await?
+            .active
+            .iter()
+            .map(|table| Ok(table.parse::<Pubkey>()?))
+            .collect()
+    }
+
+    /// Returns the active lookup tables that can be compiled into transactions.
+    /// Tables that were deactivated or cannot be read are skipped.
+    pub async fn load_active(&self) -> Result<Vec<AddressLookupTableAccount>> {
+        let tables = self.store.load().await?;
+
+        let mut active = vec![];
+        for table in &tables.active {
+            match self.fetch(&table.parse()?).await {
+                Ok(table) if !table.addresses.is_empty() => active.push(table),
+                Ok(_) => {}
+                Err(e) => warn!("Skipping lookup table {}: {:?}", table, e),
+            }
+        }
+
+        Ok(active)
+    }
+
+    /// Counts a reward paid to a user's token account. With `SOLANA_LOOKUP_TABLE_HOT_THRESHOLD`,
+    /// the token account is added to the last active table once it reaches the threshold.
+    ///
+    /// # Arguments
+    /// * `user_pubkey` - the rewarded wallet
+    pub async fn record_recipient(&self, user_pubkey: &Pubkey) -> Result<()> {
+        let token_account = self.token_account(user_pubkey);
+
+        let mut tables = self.store.load().await?;
+        let count = tables
+            .recipients
+            .entry(token_account.to_string())
+            .or_default();
+        *count += 1;
+        let count = *count;
+        let last_table = tables.active.last().cloned();
+        self.store.save(&tables).await?;
+
+        if self.hot_threshold != Some(count) {
+            return Ok(());
+        }
+
+        let Some(table) = last_table else {
+            warn!(
+                "Token account {} is hot, but there is no lookup table to add it to",
+                token_account
+            );
+            return Ok(());
+        };
+
+        // The payout succeeded, a table that cannot be extended only costs bytes
+        match self.extend(&table.parse()?, &[token_account]).await {
+            Ok(_) => info!(
+                "Added token account {} to lookup table {}",
+                token_account, table
+            ),
+            Err(e) => warn!(
+                "Failed to add token account {} to lookup table {}: {:?}",
+                token_account, table, e
+            ),
+        }
+
+        Ok(())
+    }
+
+    /// Fetches a lookup table, failing if it was deactivated.
+    async fn fetch(&self, table_pubkey: &Pubkey) -> Result<AddressLookupTableAccount> {
+        let account = self.rpc_client.get_account(table_pubkey).await?;
+        let table = AddressLookupTable::deserialize(&account.data)?;
+
+        if table.meta.deactivation_slot != Slot::MAX {
+            bail!("The lookup table {} is deactivated", table_pubkey);
+        }
+
+        Ok(AddressLookupTableAccount {
+            key: *table_pubkey,
+            addresses: table.addresses.to_vec(),
+        })
+    }
+
+    /// Returns the token account of a wallet for the reward mint.
+    fn token_account(&self, wallet_pubkey: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            wallet_pubkey,
+            &self.mint_pubkey,
+            &self.token_program_id,
+        )
+    }
+
+    /// Sends a transaction paid by the fee payer and signed by the keypairs it requires
+    /// among the fee payer, rent payer and authority, and waits for confirmation.
+    async fn send(&self, instructions: &[Instruction]) -> Result<Signature> {
+        let transaction = solana_util::create_v0_transaction(
+            instructions,
+            &self.fee_payer_keypair.pubkey(),
+            &[
+                &self.fee_payer_keypair,
+                &self.rent_payer_keypair,
+                &self.authority_keypair,
+            ],
+            &[],
+            self.rpc_client.get_latest_blockhash().await?,
+        )?;
+
+        Ok(self
+            .rpc_client
+            .send_and_confirm_transaction(&transaction)
+            .await?)
+    }
+}
+
+impl Default for LookupTableApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
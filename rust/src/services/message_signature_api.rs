@@ -0,0 +1,371 @@
+use crate::kafka::consumer_handlers::KafkaHeaders;
+use crate::models::reward::{
+    RewardRejection, RewardRejectionReason, SIGNATURE_HEADER, SIGNATURE_KEY_ID_HEADER,
+    SIGNATURE_NONCE_HEADER, SIGNATURE_NONCES_STORE, SIGNATURE_TIMESTAMP_HEADER, SignatureKey,
+    SignatureNonces,
+};
+use crate::storage::json_store::JsonStore;
+use crate::utils::reward_util;
+use anyhow::Result;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+use solana_sdk::signature::Signature;
+use std::collections::HashMap;
+use tracing::warn;
+
+pub struct MessageSignatureApi {
+    keys: HashMap<String, SignatureKey>,
+    strict: bool,
+    max_age: u64,
+    nonces: JsonStore<SignatureNonces>,
+}
+
+impl MessageSignatureApi {
+    /// Creates new API wrapper with the signature keys from environment
+    pub fn new() -> Self {
+        Self {
+            keys: reward_util::get_reward_signature_keys(),
+            strict: reward_util::get_reward_signature_strict(),
+            max_age: reward_util::get_reward_signature_max_age(),
+            nonces: JsonStore::new(SIGNATURE_NONCES_STORE),
+        }
+    }
+
+    /// Verifies the signature of a reward message, carried in the `x-signature`,
+    /// `x-signature-key-id`, `x-signature-timestamp` and `x-signature-nonce` headers.
+    ///
+    /// The signature covers `<timestamp>.<nonce>.<canonical payload>` (compact JSON with
+    /// sorted keys), so it does not depend on how the producer formats the message.
+    /// Returns the rejection if the message is signed with an unknown key, the signature
+    /// does not match, (in strict mode) the message is unsigned, or the message is a replay:
+    /// signed longer than `REWARD_SIGNATURE_MAX_AGE_SECONDS` ago, or with a nonce already seen.
+    ///
+    /// # Arguments
+    /// * `payload` - The JSON payload of the message.
+    /// * `headers` - The headers of the message.
+    pub async fn verify(
+        &self,
+        payload: &str,
+        headers: &KafkaHeaders,
+    ) -> Result<Option<RewardRejection>> {
+        let reject = |message: String| {
+            Ok(Some(RewardRejection::new(
+                RewardRejectionReason::InvalidSignature,
+                message,
+            )))
+        };
+
+        let (signature, key_id) = match (
+            headers.get(SIGNATURE_HEADER),
+            headers.get(SIGNATURE_KEY_ID_HEADER),
+        ) {
+            (Some(signature), Some(key_id)) => (signature, key_id),
+            (None, None) if self.strict => return reject("The message is unsigned".to_string()),
+            (None, None) => {
+                warn!("Accepting an unsigned reward message, strict mode is off");
+                return Ok(None);
+            }
+            _ => {
+                return reject(format!(
+                    "The message must carry both {} and {} headers",
+                    SIGNATURE_HEADER, SIGNATURE_KEY_ID_HEADER
+                ));
+            }
+        };
+
+        let (Some(timestamp), Some(nonce)) = (
+            headers.get(SIGNATURE_TIMESTAMP_HEADER),
+            headers.get(SIGNATURE_NONCE_HEADER),
+        ) else {
+            return reject(format!(
+                "The message must carry both {} and {} headers",
+                SIGNATURE_TIMESTAMP_HEADER, SIGNATURE_NONCE_HEADER
+            ));
+        };
+        let Ok(signed_at) = timestamp.parse::<i64>() else {
+            return reject(format!("The timestamp {} is not a number", timestamp));
+        };
+
+        let Some(key) = self.keys.get(key_id) else {
+            return reject(format!("Unknown signature key {}", key_id));
+        };
+        let Ok(signature) = STANDARD.decode(signature) else {
+            return reject("The signature is not valid base64".to_string());
+        };
+
+        let message = signed_message(timestamp, nonce, payload)?;
+
+        let valid = match key {
+            SignatureKey::HmacSha256(secret) => Hmac::<Sha256>::new_from_slice(secret)?
+                .chain_update(message.as_bytes())
+                .verify_slice(&signature)
+                .is_ok(),
+            SignatureKey::Ed25519(public_key) => Signature::try_from(signature.as_slice())
+                .is_ok_and(|signature| signature.verify(public_key, message.as_bytes())),
+        };
+
+        if !valid {
+            return reject(format!("The signature does not match key {}", key_id));
+        }
+
+        self.check_replay(nonce, signed_at).await
+    }
+
+    /// Lets a message deferred by the worker through the replay check once more,
+    /// when it is republished after its signature expired.
+    ///
+    /// # Arguments
+    /// * `headers` - The headers of the deferred message.
+    pub async fn release(&self, headers: &KafkaHeaders) -> Result<()> {
+        let Some(nonce) = headers.get(SIGNATURE_NONCE_HEADER) else {
+            return Ok(());
+        };
+
+        let mut nonces = self.nonces.load().await?;
+        nonces.released.insert(nonce.clone());
+
+        self.nonces.save(&nonces).await
+    }
+
+    /// Rejects a stale or already seen message, otherwise records its nonce.
+    /// Nonces are forgotten once their message would be stale anyway.
+    async fn check_replay(&self, nonce: &str, signed_at: i64) -> Result<Option<RewardRejection>> {
+        let now = Utc::now().timestamp();
+        let mut nonces = self.nonces.load().await?;
+
+        // A deferred message is republished by the worker after its signature expired
+        let released = nonces.released.remove(nonce);
+
+        if !released && !is_fresh(signed_at, now, self.max_age) {
+            return Ok(Some(RewardRejection::new(
+                RewardRejectionReason::InvalidSignature,
+                format!(
+                    "The message was signed at {}, more than {} seconds from now",
+                    signed_at, self.max_age
+                ),
+            )));
+        }
+
+        if !released && nonces.seen.contains_key(nonce) {
+            return Ok(Some(RewardRejection::new(
+                RewardRejectionReason::InvalidSignature,
+                format!("The message with nonce {} was already received", nonce),
+            )));
+        }
+
+        nonces
+            .seen
+            .retain(|_, seen_at| is_fresh(*seen_at, now, self.max_age));
+        nonces.seen.insert(nonce.to_string(), signed_at);
+
+        self.nonces.save(&nonces).await?;
+
+        Ok(None)
+    }
+}
+
+impl Default for MessageSignatureApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds the signed message: `<timestamp>.<nonce>.<canonical payload>`.
+fn signed_message(timestamp: &str, nonce: &str, payload: &str) -> Result<String> {
+    Ok(format!(
+        "{}.{}.{}",
+        timestamp,
+        nonce,
+        canonical_payload(payload)?
+    ))
+}
+
+/// Serializes a JSON payload as compact JSON with the object keys sorted
+/// (`serde_json` maps are ordered by key).
+fn canonical_payload(payload: &str) -> Result<String> {
+    let value: Value = serde_json::from_str(payload)?;
+
+    Ok(serde_json::to_string(&value)?)
+}
+
+/// Returns whether a message signed at `signed_at` is within `max_age` seconds of `now`
+/// (either way, to allow for clock skew).
+fn is_fresh(signed_at: i64, now: i64, max_age: u64) -> bool {
+    now.abs_diff(signed_at) <= max_age
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    const HMAC_SECRET: &[u8] = b"test-secret";
+    const PAYLOAD: &str = r#"{"user_id":1,"quiz_id":42,"streak_days":3}"#;
+
+    struct Producer {
+        ed25519: Keypair,
+    }
+
+    impl Producer {
+        fn new() -> Self {
+            Self {
+                ed25519: Keypair::new(),
+            }
+        }
+
+        fn api(&self, name: &str, strict: bool) -> MessageSignatureApi {
+            let path = std::env::temp_dir().join(format!(
+                "message_signature_api_{}_{}.json",
+                name,
+                std::process::id()
+            ));
+            let _ = std::fs::remove_file(&path);
+
+            MessageSignatureApi {
+                keys: HashMap::from([
+                    (
+                        "hmac".to_string(),
+                        SignatureKey::HmacSha256(HMAC_SECRET.to_vec()),
+                    ),
+                    (
+                        "ed25519".to_string(),
+                        SignatureKey::Ed25519(self.ed25519.pubkey().to_bytes()),
+                    ),
+                ]),
+                strict,
+                max_age: 300,
+                nonces: JsonStore::with_path(path),
+            }
+        }
+
+        fn sign(&self, key_id: &str, signed_at: i64, nonce: &str, payload: &str) -> KafkaHeaders {
+            let message = signed_message(&signed_at.to_string(), nonce, payload).unwrap();
+            let signature = match key_id {
+                "ed25519" => self
+                    .ed25519
+                    .sign_message(message.as_bytes())
+                    .as_ref()
+                    .to_vec(),
+                _ => Hmac::<Sha256>::new_from_slice(HMAC_SECRET)
+                    .unwrap()
+                    .chain_update(message.as_bytes())
+                    .finalize()
+                    .into_bytes()
+                    .to_vec(),
+            };
+
+            KafkaHeaders::from([
+                (SIGNATURE_HEADER.to_string(), STANDARD.encode(signature)),
+                (SIGNATURE_KEY_ID_HEADER.to_string(), key_id.to_string()),
+                (
+                    SIGNATURE_TIMESTAMP_HEADER.to_string(),
+                    signed_at.to_string(),
+                ),
+                (SIGNATURE_NONCE_HEADER.to_string(), nonce.to_string()),
+            ])
+        }
+    }
+
+    fn now() -> i64 {
+        Utc::now().timestamp()
+    }
+
+    #[tokio::test]
+    async fn accepts_valid_signatures() {
+        let producer = Producer::new();
+        let api = producer.api("valid", true);
+
+        for key_id in ["hmac", "ed25519"] {
+            let headers = producer.sign(key_id, now(), key_id, PAYLOAD);
+
+            assert!(api.verify(PAYLOAD, &headers).await.unwrap().is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn ignores_the_payload_formatting() {
+        let producer = Producer::new();
+        let api = producer.api("formatting", true);
+        let headers = producer.sign("hmac", now(), "nonce", PAYLOAD);
+        let reformatted = r#"{ "streak_days": 3, "quiz_id": 42, "user_id": 1 }"#;
+
+        assert!(api.verify(reformatted, &headers).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_tampered_messages() {
+        let producer = Producer::new();
+        let api = producer.api("tampered", true);
+        let tampered = r#"{"user_id":1,"quiz_id":42,"streak_days":30}"#;
+
+        for key_id in ["hmac", "ed25519"] {
+            let headers = producer.sign(key_id, now(), key_id, PAYLOAD);
+
+            assert!(api.verify(tampered, &headers).await.unwrap().is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_keys_and_malformed_headers() {
+        let producer = Producer::new();
+        let api = producer.api("malformed", true);
+
+        let unknown_key = producer.sign("other", now(), "nonce-1", PAYLOAD);
+        assert!(api.verify(PAYLOAD, &unknown_key).await.unwrap().is_some());
+
+        let mut no_nonce = producer.sign("hmac", now(), "nonce-2", PAYLOAD);
+        no_nonce.remove(SIGNATURE_NONCE_HEADER);
+        assert!(api.verify(PAYLOAD, &no_nonce).await.unwrap().is_some());
+
+        let mut bad_base64 = producer.sign("hmac", now(), "nonce-3", PAYLOAD);
+        bad_base64.insert(SIGNATURE_HEADER.to_string(), "not base64!".to_string());
+        assert!(api.verify(PAYLOAD, &bad_base64).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn rejects_unsigned_messages_in_strict_mode() {
+        let producer = Producer::new();
+        let unsigned = KafkaHeaders::new();
+
+        let strict = producer.api("unsigned_strict", true);
+        assert!(strict.verify(PAYLOAD, &unsigned).await.unwrap().is_some());
+
+        let lenient = producer.api("unsigned_lenient", false);
+        assert!(lenient.verify(PAYLOAD, &unsigned).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_replayed_messages() {
+        let producer = Producer::new();
+        let api = producer.api("replayed", true);
+        let headers = producer.sign("hmac", now(), "nonce", PAYLOAD);
+
+        assert!(api.verify(PAYLOAD, &headers).await.unwrap().is_none());
+        assert!(api.verify(PAYLOAD, &headers).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn rejects_stale_messages_unless_released() {
+        let producer = Producer::new();
+        let api = producer.api("stale", true);
+        let headers = producer.sign("hmac", now() - 301, "nonce", PAYLOAD);
+
+        assert!(api.verify(PAYLOAD, &headers).await.unwrap().is_some());
+
+        api.release(&headers).await.unwrap();
+        assert!(api.verify(PAYLOAD, &headers).await.unwrap().is_none());
+        assert!(api.verify(PAYLOAD, &headers).await.unwrap().is_some());
+    }
+
+    #[test]
+    fn freshness_allows_clock_skew() {
+        assert!(is_fresh(1_000, 1_300, 300));
+        assert!(is_fresh(1_300, 1_000, 300));
+        assert!(!is_fresh(1_000, 1_301, 300));
+        assert!(!is_fresh(i64::MIN, i64::MAX, 300));
+    }
+}
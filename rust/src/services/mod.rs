@@ -1,3 +1,15 @@
+pub mod audit_api;
 pub mod blockchain_api;
+pub mod lookup_table_api;
+pub mod message_signature_api;
 pub mod nft_api;
+pub mod nft_badge_api;
+pub mod nft_metadata_api;
+pub mod payer_pool_api;
+pub mod reward_budget_api;
+pub mod reward_hold_api;
+pub mod reward_policy_api;
+pub mod reward_sybil_api;
+pub mod reward_validation_api;
 pub mod solana_api;
+pub mod wallet_blocklist_api;
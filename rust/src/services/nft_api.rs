@@ -1,44 +1,367 @@
+use crate::models::nft::NftMetadata;
 use crate::utils::solana_util;
-use anyhow::{Result};
+use anyhow::{Context, Result};
 use mpl_token_metadata::{
     ID as TOKEN_METADATA_PROGRAM_ID,
-    accounts::Metadata,
-    instructions::CreateV1Builder,
-    types::{PrintSupply, TokenStandard},
+    accounts::{MasterEdition, Metadata},
+    instructions::{
+        CreateV1Builder, UnverifyCollectionV1Builder, UpdateV1Builder, VerifyCollectionV1Builder,
+    },
+    types::{Collection, CollectionDetails, Data, PrintSupply, TokenStandard},
 };
 use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::instruction::Instruction;
 use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
 use solana_program::system_instruction::create_account;
 use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    packet::PACKET_DATA_SIZE,
     signature::{Keypair, Signature},
     signer::Signer,
     transaction::Transaction,
 };
 use spl_associated_token_account::get_associated_token_address;
 use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
-use spl_token::instruction::mint_to_checked;
+use spl_token::instruction::{freeze_account, mint_to_checked};
 use spl_token::{ID as TOKEN_PROGRAM_ID, instruction::initialize_mint2, state::Mint};
 
+/// Compute unit limit for the single-transaction NFT mint
+/// (Metaplex `CreateV1` with a master edition is the expensive part).
+const ATOMIC_MINT_COMPUTE_UNITS: u32 = 400_000;
+
 pub struct NftApi {
     rpc_client: RpcClient,
     authority_keypair: Keypair,
+    fee_payer_keypair: Keypair,
+    rent_payer_keypair: Keypair,
 }
 
 impl NftApi {
-    /// Create new API wrapper with RPC client + authority keypair
+    /// Create new API wrapper with RPC client + authority, fee payer and rent payer keypairs
     pub fn new() -> Self {
         Self {
             rpc_client: solana_util::create_rpc_client(),
             authority_keypair: solana_util::get_authority_keypair(),
+            fee_payer_keypair: solana_util::get_fee_payer_keypair(),
+            rent_payer_keypair: solana_util::get_rent_payer_keypair(),
+        }
+    }
+
+    /// Creates an associated token account (ATA) for given mint + user
+    pub async fn create_token_account(
+        &self,
+        mint_pubkey: &Pubkey,
+        recipient_pubkey: &Pubkey,
+    ) -> Result<Signature> {
+        let create_ata_ix = self.create_token_account_instruction(mint_pubkey, recipient_pubkey);
+
+        // Build, sign and send transaction
+        self.send_transaction(&[create_ata_ix], &[&self.authority_keypair])
+            .await
+    }
+
+    /// Mints 1 token (NFT) to the recipient's ATA
+    /// (and freezes the ATA in the same transaction for soulbound NFTs)
+    pub async fn mint_token(
+        &self,
+        mint_keypair: &Keypair,
+        recipient_pubkey: &Pubkey,
+    ) -> Result<Signature> {
+        let mint_to_instructions =
+            self.mint_token_instructions(&mint_keypair.pubkey(), recipient_pubkey)?;
+
+        // Build, sign and send transaction
+        self.send_transaction(
+            &mint_to_instructions,
+            &[&self.authority_keypair, mint_keypair],
+        )
+        .await
+    }
+
+    /// Creates metadata + master edition for the mint (Metaplex NFT)
+    /// and verifies it as a member of the streak collection, if configured
+    pub async fn create_metadata(
+        &self,
+        mint_keypair: &Keypair,
+        metadata: &NftMetadata,
+    ) -> Result<Signature> {
+        let metadata_instructions =
+            self.create_metadata_instructions(&mint_keypair.pubkey(), metadata);
+
+        // Build, sign and send transaction (both must sign)
+        self.send_transaction(
+            &metadata_instructions,
+            &[&self.authority_keypair, mint_keypair],
+        )
+        .await
+    }
+
+    /// Creates the collection NFT that groups all streak NFTs.
+    ///
+    /// The collection token is minted to the authority, which stays the update
+    /// authority and is therefore allowed to verify collection members.
+    ///
+    /// # Returns
+    /// * Address of the collection mint and signatures of the sent transactions
+    pub async fn create_collection(&self) -> Result<(Pubkey, Vec<Signature>)> {
+        let collection_keypair = Keypair::new();
+        let collection_pubkey = collection_keypair.pubkey();
+        let authority_pubkey = self.authority_keypair.pubkey();
+
+        // Token part: create mint + authority's ATA + mint 1 token
+        let mut token_instructions = self.create_mint_instructions(&collection_pubkey).await?;
+        token_instructions
+            .push(self.create_token_account_instruction(&collection_pubkey, &authority_pubkey));
+        token_instructions
+            .push(self.mint_token_instruction(&collection_pubkey, &authority_pubkey)?);
+
+        // Metadata part: collection metadata + master edition
+        let metadata_ix = self
+            .metadata_builder(
+                &collection_pubkey,
+                solana_util::get_nft_collection_name(),
+                solana_util::get_nft_symbol(),
+                solana_util::get_nft_collection_meta_uri(),
+            )
+            .collection_details(CollectionDetails::V1 { size: 0 })
+            .instruction();
+
+        let signatures = self
+            .send_mint_transactions(&collection_keypair, token_instructions, vec![metadata_ix])
+            .await?;
+
+        Ok((collection_pubkey, signatures))
+    }
+
+    /// Returns the amount of `mint_pubkey` tokens held by the recipient's ATA
+    /// (0 if the ATA does not exist yet)
+    pub async fn get_token_amount(
+        &self,
+        mint_pubkey: &Pubkey,
+        recipient_pubkey: &Pubkey,
+    ) -> Result<u64> {
+        let associated_token_account = get_associated_token_address(recipient_pubkey, mint_pubkey);
+
+        match self
+            .rpc_client
+            .get_token_account_balance(&associated_token_account)
+            .await
+        {
+            Ok(balance) => Ok(balance.amount.parse()?),
+            Err(_) => Ok(0),
         }
     }
 
-    /// Creates a new mint account and initializes it as an SPL mint
-    pub async fn create_mint(&self) -> Result<(Keypair, Signature)> {
-        let mint_keypair = Keypair::new();
+    /// Checks whether an account exists at the given address
+    pub async fn account_exists(&self, pubkey: &Pubkey) -> Result<bool> {
+        let account = self
+            .rpc_client
+            .get_account_with_commitment(pubkey, self.rpc_client.commitment())
+            .await?
+            .value;
+
+        Ok(account.is_some())
+    }
+
+    /// Checks whether the metadata account for the mint already exists
+    pub async fn metadata_exists(&self, mint_pubkey: &Pubkey) -> Result<bool> {
+        let (metadata_pubkey, _) = Metadata::find_pda(mint_pubkey);
+
+        self.account_exists(&metadata_pubkey).await
+    }
+
+    /// Mints an NFT with as few transactions as possible.
+    ///
+    /// Sends create mint → create ATA → mint 1 token → create metadata in a single
+    /// transaction. If that transaction exceeds the packet size limit, falls back to
+    /// two transactions: the token part first, then the metadata.
+    ///
+    /// # Returns
+    /// * Signatures of the sent transactions (one or two)
+    pub async fn mint_nft_atomic(
+        &self,
+        mint_keypair: &Keypair,
+        recipient_pubkey: &Pubkey,
+        metadata: &NftMetadata,
+    ) -> Result<Vec<Signature>> {
         let mint_pubkey = mint_keypair.pubkey();
 
+        // Token part: create mint + ATA + mint 1 token
+        let mut token_instructions = self.create_mint_instructions(&mint_pubkey).await?;
+        token_instructions
+            .push(self.create_token_account_instruction(&mint_pubkey, recipient_pubkey));
+        token_instructions.extend(self.mint_token_instructions(&mint_pubkey, recipient_pubkey)?);
+
+        // Metadata part: metadata + master edition (+ collection verification)
+        let metadata_instructions = self.create_metadata_instructions(&mint_pubkey, metadata);
+
+        self.send_mint_transactions(mint_keypair, token_instructions, metadata_instructions)
+            .await
+            .context(format!(
+                "Failed to mint NFT {} to {}",
+                mint_pubkey, recipient_pubkey
+            ))
+    }
+
+    /// Sends the token and metadata parts of a mint in a single transaction,
+    /// or in two transactions if a single one exceeds the packet size limit.
+    async fn send_mint_transactions(
+        &self,
+        mint_keypair: &Keypair,
+        token_instructions: Vec<Instruction>,
+        metadata_instructions: Vec<Instruction>,
+    ) -> Result<Vec<Signature>> {
+        let mint_pubkey = mint_keypair.pubkey();
+        let signers = [&self.authority_keypair, mint_keypair];
+
+        // Metaplex `CreateV1` with a master edition is the expensive part
+        let compute_limit_ix =
+            ComputeBudgetInstruction::set_compute_unit_limit(ATOMIC_MINT_COMPUTE_UNITS);
+
+        // Single transaction when everything fits into one packet
+        let mut instructions = vec![compute_limit_ix.clone()];
+        instructions.extend(token_instructions.iter().cloned());
+        instructions.extend(metadata_instructions.iter().cloned());
+
+        let transaction = self.create_transaction(&instructions, &signers).await?;
+
+        if bincode::serialized_size(&transaction)? as usize <= PACKET_DATA_SIZE {
+            let transaction_signature = self
+                .rpc_client
+                .send_and_confirm_transaction(&transaction)
+                .await?;
+
+            return Ok(vec![transaction_signature]);
+        }
+
+        // Fallback: two transactions
+        let token_signature = self
+            .send_transaction(&token_instructions, &signers)
+            .await
+            .context(format!("Failed to create NFT mint {}", mint_pubkey))?;
+
+        let mut metadata_part = vec![compute_limit_ix];
+        metadata_part.extend(metadata_instructions);
+
+        let metadata_signature = self
+            .send_transaction(&metadata_part, &signers)
+            .await
+            .context(format!(
+                "NFT mint {} was created and minted (signature {}), but creating its metadata failed",
+                mint_pubkey, token_signature
+            ))?;
+
+        Ok(vec![token_signature, metadata_signature])
+    }
+
+    /// Updates the name, symbol and URI of an existing NFT (e.g. a streak passport)
+    /// through Metaplex `UpdateV1`, signed by the update authority.
+    pub async fn update_metadata(
+        &self,
+        mint_pubkey: &Pubkey,
+        metadata: &NftMetadata,
+    ) -> Result<Signature> {
+        let current_metadata = self.fetch_metadata(mint_pubkey).await?;
+
+        let update_ix = self.update_metadata_instruction(
+            mint_pubkey,
+            &current_metadata,
+            metadata.name.clone(),
+            metadata.symbol.clone(),
+            metadata.uri.clone(),
+        );
+
+        self.send_transaction(&[update_ix], &[&self.authority_keypair])
+            .await
+    }
+
+    /// Revokes a streak NFT achievement.
+    ///
+    /// Unverifies the NFT from the collection and points its metadata at the revoked
+    /// document. The holder keeps the token (frozen, if soulbound), but it no longer
+    /// proves the achievement.
+    pub async fn revoke_nft(&self, mint_pubkey: &Pubkey) -> Result<Signature> {
+        let (metadata_pubkey, _) = Metadata::find_pda(mint_pubkey);
+        let metadata = self.fetch_metadata(mint_pubkey).await?;
+
+        let mut instructions = vec![];
+
+        // Remove the NFT from the verified collection
+        if let Some(collection) = metadata
+            .collection
+            .as_ref()
+            .filter(|collection| collection.verified)
+        {
+            let (collection_metadata_pubkey, _) = Metadata::find_pda(&collection.key);
+
+            instructions.push(
+                UnverifyCollectionV1Builder::new()
+                    .authority(self.authority_keypair.pubkey())
+                    .metadata(metadata_pubkey)
+                    .collection_mint(collection.key)
+                    .collection_metadata(Some(collection_metadata_pubkey))
+                    .instruction(),
+            );
+        }
+
+        // Point the metadata at the revoked document
+        instructions.push(self.update_metadata_instruction(
+            mint_pubkey,
+            &metadata,
+            trim_metadata_string(&metadata.name),
+            trim_metadata_string(&metadata.symbol),
+            solana_util::get_nft_revoked_meta_uri(),
+        ));
+
+        self.send_transaction(&instructions, &[&self.authority_keypair])
+            .await
+    }
+
+    /// Fetches and decodes the Metaplex metadata account of the mint
+    async fn fetch_metadata(&self, mint_pubkey: &Pubkey) -> Result<Metadata> {
+        let (metadata_pubkey, _) = Metadata::find_pda(mint_pubkey);
+
+        let metadata_account = self
+            .rpc_client
+            .get_account(&metadata_pubkey)
+            .await
+            .context(format!("Metadata of NFT {} not found", mint_pubkey))?;
+
+        Ok(Metadata::from_bytes(&metadata_account.data)?)
+    }
+
+    /// Builds the Metaplex `UpdateV1` instruction replacing the name, symbol and URI,
+    /// keeping royalties and creators of the current metadata
+    fn update_metadata_instruction(
+        &self,
+        mint_pubkey: &Pubkey,
+        current_metadata: &Metadata,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Instruction {
+        let (metadata_pubkey, _) = Metadata::find_pda(mint_pubkey);
+        let (master_edition_pubkey, _) = MasterEdition::find_pda(mint_pubkey);
+
+        UpdateV1Builder::new()
+            .authority(self.authority_keypair.pubkey()) // update authority
+            .mint(*mint_pubkey) // mint
+            .metadata(metadata_pubkey) // metadata PDA
+            .edition(Some(master_edition_pubkey)) // master edition PDA
+            .payer(self.rent_payer_keypair.pubkey()) // payer (metadata resize rent)
+            .data(Data {
+                name,
+                symbol,
+                uri,
+                seller_fee_basis_points: current_metadata.seller_fee_basis_points,
+                creators: current_metadata.creators.clone(),
+            })
+            .instruction()
+    }
+
+    /// Builds instructions to create the mint account and initialize it as an SPL mint
+    async fn create_mint_instructions(&self, mint_pubkey: &Pubkey) -> Result<Vec<Instruction>> {
         let mint_account_len = Mint::LEN;
         let mint_account_rent = self
             .rpc_client
@@ -47,159 +370,202 @@ impl NftApi {
 
         // Create an account for the mint
         let create_mint_account_ix = create_account(
-            &self.authority_keypair.pubkey(), // payer
-            &mint_pubkey,                     // new mint account
-            mint_account_rent,                // rent-exempt balance
-            mint_account_len as u64,          // mint size
-            &TOKEN_PROGRAM_ID,                // token program
+            &self.rent_payer_keypair.pubkey(), // payer
+            mint_pubkey,                       // new mint account
+            mint_account_rent,                 // rent-exempt balance
+            mint_account_len as u64,           // mint size
+            &TOKEN_PROGRAM_ID,                 // token program
         );
 
         // Initialize mint with decimals = 0
         let initialize_mint_ix = initialize_mint2(
             &TOKEN_PROGRAM_ID,                      // token program
-            &mint_pubkey,                           // mint address
+            mint_pubkey,                            // mint address
             &self.authority_keypair.pubkey(),       // mint authority
             Some(&self.authority_keypair.pubkey()), // freeze authority
             0,                                      // decimals = 0 for NFT
         )?;
 
-        // Build and sign transaction
-        let mut transaction = Transaction::new_with_payer(
-            &[create_mint_account_ix, initialize_mint_ix],
-            Some(&self.authority_keypair.pubkey()),
-        );
-
-        transaction.sign(
-            &[&self.authority_keypair, &mint_keypair],
-            self.rpc_client.get_latest_blockhash().await?,
-        );
-
-        // Send transaction
-        let transaction_signature = self
-            .rpc_client
-            .send_and_confirm_transaction(&transaction)
-            .await?;
-
-        Ok((mint_keypair, transaction_signature))
+        Ok(vec![create_mint_account_ix, initialize_mint_ix])
     }
 
-    /// Creates an associated token account (ATA) for given mint + user
-    pub async fn create_token_account(
+    /// Builds the instruction to create the recipient's ATA if it does not exist
+    fn create_token_account_instruction(
         &self,
         mint_pubkey: &Pubkey,
         recipient_pubkey: &Pubkey,
-    ) -> Result<Signature> {
-
-        // Create ATA if not exist
-        let create_ata_ix = create_associated_token_account_idempotent(
-            &self.authority_keypair.pubkey(), // payer
-            &recipient_pubkey,                // wallet owner
-            &mint_pubkey,                     // mint
-            &TOKEN_PROGRAM_ID,                // token program
-        );
-
-        // Build and sign transaction
-        let mut transaction =
-            Transaction::new_with_payer(&[create_ata_ix], Some(&self.authority_keypair.pubkey()));
-
-        transaction.sign(
-            &[&self.authority_keypair],
-            self.rpc_client.get_latest_blockhash().await?,
-        );
+    ) -> Instruction {
+        create_associated_token_account_idempotent(
+            &self.rent_payer_keypair.pubkey(), // payer
+            recipient_pubkey,                  // wallet owner
+            mint_pubkey,                       // mint
+            &TOKEN_PROGRAM_ID,                 // token program
+        )
+    }
 
-        // Send transaction
-        let transaction_signature = self
-            .rpc_client
-            .send_and_confirm_transaction(&transaction)
-            .await?;
+    /// Builds the instructions to mint 1 token (NFT) to the recipient's ATA.
+    ///
+    /// For soulbound NFTs, the ATA is frozen right after minting (while the authority
+    /// is still the freeze authority), so the token can never be transferred.
+    fn mint_token_instructions(
+        &self,
+        mint_pubkey: &Pubkey,
+        recipient_pubkey: &Pubkey,
+    ) -> Result<Vec<Instruction>> {
+        let mut instructions = vec![self.mint_token_instruction(mint_pubkey, recipient_pubkey)?];
+
+        if solana_util::get_nft_soulbound() {
+            let associated_token_account =
+                get_associated_token_address(recipient_pubkey, mint_pubkey);
+
+            instructions.push(freeze_account(
+                &TOKEN_PROGRAM_ID,                // token program
+                &associated_token_account,        // user's ATA
+                mint_pubkey,                      // mint
+                &self.authority_keypair.pubkey(), // freeze authority
+                &[],                              // signers
+            )?);
+        }
 
-        Ok(transaction_signature)
+        Ok(instructions)
     }
 
-    /// Mints 1 token (NFT) to the recipient's ATA
-    pub async fn mint_token(
+    /// Builds the instruction to mint 1 token (NFT) to the recipient's ATA
+    fn mint_token_instruction(
         &self,
-        mint_keypair: &Keypair,
+        mint_pubkey: &Pubkey,
         recipient_pubkey: &Pubkey,
-    ) -> Result<Signature> {
-
+    ) -> Result<Instruction> {
         // Find ATA for this mint + user
-        let associated_token_account =
-            get_associated_token_address(&recipient_pubkey, &mint_keypair.pubkey());
+        let associated_token_account = get_associated_token_address(recipient_pubkey, mint_pubkey);
 
         // Mint 1 token to ATA
         let mint_to_ix = mint_to_checked(
-            &TOKEN_PROGRAM_ID,                   // token program
-            &mint_keypair.pubkey(),             // mint
-            &associated_token_account,          // user's ATA
-            &self.authority_keypair.pubkey(),   // mint authority
-            &[&self.authority_keypair.pubkey(), &mint_keypair.pubkey()], // signers
-            1,                                  // amount
-            0,                                  // decimals
+            &TOKEN_PROGRAM_ID,                                // token program
+            mint_pubkey,                                      // mint
+            &associated_token_account,                        // user's ATA
+            &self.authority_keypair.pubkey(),                 // mint authority
+            &[&self.authority_keypair.pubkey(), mint_pubkey], // signers
+            1,                                                // amount
+            0,                                                // decimals
         )?;
 
-        // Build and sign transaction
-        let mut transaction =
-            Transaction::new_with_payer(&[mint_to_ix], Some(&self.authority_keypair.pubkey()));
+        Ok(mint_to_ix)
+    }
 
-        transaction.sign(
-            &[&self.authority_keypair, mint_keypair],
-            self.rpc_client.get_latest_blockhash().await?,
+    /// Builds the Metaplex instructions creating metadata + master edition for the
+    /// streak NFT mint, attached to and verified in the collection if one is configured
+    fn create_metadata_instructions(
+        &self,
+        mint_pubkey: &Pubkey,
+        metadata: &NftMetadata,
+    ) -> Vec<Instruction> {
+        let collection_mint = solana_util::get_nft_collection_mint();
+
+        let mut builder = self.metadata_builder(
+            mint_pubkey,
+            metadata.name.clone(),
+            metadata.symbol.clone(),
+            metadata.uri.clone(),
         );
 
-        // Send transaction
-        let transaction_signature = self
-            .rpc_client
-            .send_and_confirm_transaction(&transaction)
-            .await?;
+        let Some(collection_mint) = collection_mint else {
+            return vec![builder.instruction()];
+        };
 
-        Ok(transaction_signature)
-    }
+        // Attach the collection (unverified until the verify instruction below)
+        let create_ix = builder
+            .collection(Collection {
+                verified: false,
+                key: collection_mint,
+            })
+            .instruction();
 
-    /// Creates metadata + master edition for the mint (Metaplex NFT)
-    pub async fn create_metadata(&self, mint_keypair: &Keypair) -> Result<Signature> {
+        // Verify the collection membership, signed by the collection update authority
+        let (metadata_pubkey, _) = Metadata::find_pda(mint_pubkey);
+        let (collection_metadata_pubkey, _) = Metadata::find_pda(&collection_mint);
+        let (collection_master_edition_pubkey, _) = MasterEdition::find_pda(&collection_mint);
+
+        let verify_ix = VerifyCollectionV1Builder::new()
+            .authority(self.authority_keypair.pubkey()) // collection update authority
+            .metadata(metadata_pubkey) // NFT metadata PDA
+            .collection_mint(collection_mint) // collection mint
+            .collection_metadata(Some(collection_metadata_pubkey)) // collection metadata PDA
+            .collection_master_edition(Some(collection_master_edition_pubkey)) // edition PDA
+            .instruction();
 
+        vec![create_ix, verify_ix]
+    }
+
+    /// Prepares the Metaplex create metadata + master edition builder for the mint
+    fn metadata_builder(
+        &self,
+        mint_pubkey: &Pubkey,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> CreateV1Builder {
         // PDA for metadata
-        let (metadata_pubkey, _) = Metadata::find_pda(&mint_keypair.pubkey());
+        let (metadata_pubkey, _) = Metadata::find_pda(mint_pubkey);
 
         // PDA for master edition
         let (master_edition_pda, _bump) = Pubkey::find_program_address(
             &[
                 b"metadata",
                 TOKEN_METADATA_PROGRAM_ID.as_ref(),
-                mint_keypair.pubkey().as_ref(),
+                mint_pubkey.as_ref(),
                 b"edition",
             ],
             &TOKEN_METADATA_PROGRAM_ID,
         );
 
         // Build Metaplex create metadata instruction
-        let create_ix = CreateV1Builder::new()
+        let mut builder = CreateV1Builder::new();
+        builder
             .metadata(metadata_pubkey)                     // metadata PDA
-            .mint(mint_keypair.pubkey(), true)            // mint + signer
+            .mint(*mint_pubkey, true)                     // mint + signer
             .authority(self.authority_keypair.pubkey())   // mint authority
-            .payer(self.authority_keypair.pubkey())       // payer
+            .payer(self.rent_payer_keypair.pubkey())      // payer
             .update_authority(self.authority_keypair.pubkey(), true) // update authority
             .is_mutable(true)                             // allow updates
             .primary_sale_happened(false)                 // primary sale flag
-            .name(solana_util::get_nft_name())            // NFT name
-            .symbol(solana_util::get_nft_symbol())        // symbol
-            .uri(solana_util::get_nft_meta_uri())         // metadata URI
+            .name(name)                                   // NFT name
+            .symbol(symbol)                               // symbol
+            .uri(uri)                                     // metadata URI
             .seller_fee_basis_points(0)                   // royalties
             .token_standard(TokenStandard::NonFungible)    // NFT
             .print_supply(PrintSupply::Zero)               // no printing
             .master_edition(Some(master_edition_pda))      // master edition PDA
-            .spl_token_program(Some(TOKEN_PROGRAM_ID))
-            .instruction();
+            .spl_token_program(Some(TOKEN_PROGRAM_ID));
 
-        // Build and sign transaction
-        let mut transaction =
-            Transaction::new_with_payer(&[create_ix], Some(&self.authority_keypair.pubkey()));
+        builder
+    }
 
-        transaction.sign(
-            &[&self.authority_keypair, &mint_keypair], // both must sign
+    /// Builds a transaction paid by the fee payer, signed by the keypairs it requires
+    /// among the fee payer, rent payer and `signers`
+    async fn create_transaction(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Transaction> {
+        let mut all_signers = vec![&self.fee_payer_keypair, &self.rent_payer_keypair];
+        all_signers.extend(signers);
+
+        solana_util::create_transaction(
+            instructions,
+            &self.fee_payer_keypair.pubkey(),
+            &all_signers,
             self.rpc_client.get_latest_blockhash().await?,
-        );
+        )
+    }
+
+    /// Builds, signs and sends a transaction paid by the fee payer
+    async fn send_transaction(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Signature> {
+        let transaction = self.create_transaction(instructions, signers).await?;
 
         // Send transaction
         let transaction_signature = self
@@ -209,34 +575,15 @@ impl NftApi {
 
         Ok(transaction_signature)
     }
+}
 
-    /// Full workflow: mint NFT → create ATA → send NFT → create metadata
-    pub async fn mint_nft_to_recipient(&self, recipient_pubkey: &Pubkey) -> Result<()> {
-
-        // 1) Create mint
-        let (mint_keypair, mint_signature) = self.create_mint().await?;
-        println!(
-            "✅ Mint: {}, Signature: {}",
-            &mint_keypair.pubkey(),
-            mint_signature
-        );
-
-        // 2) Create user's ATA
-        let token_account_signature = self
-            .create_token_account(&mint_keypair.pubkey(), &recipient_pubkey)
-            .await?;
-        println!("✅ Token Account, Signature: {}", token_account_signature);
-
-        // 3) Mint 1 token to recipient
-        let one_token_signature = self
-            .mint_token(&mint_keypair, &recipient_pubkey)
-            .await?;
-        println!("✅ Token (NFT), Signature: {}", one_token_signature);
-
-        // 4) Create metadata + master edition
-        let metadata_signature = self.create_metadata(&mint_keypair).await?;
-        println!("✅ Metadata, Signature: {}", metadata_signature);
-
-        Ok(())
+impl Default for NftApi {
+    fn default() -> Self {
+        Self::new()
     }
 }
+
+/// Strips the zero padding Metaplex stores strings with
+fn trim_metadata_string(value: &str) -> String {
+    value.trim_end_matches('\0').to_string()
+}
@@ -0,0 +1,116 @@
+use crate::utils::solana_util;
+use anyhow::{Context, Result};
+use resvg::tiny_skia::{Pixmap, Transform};
+use resvg::usvg::{Options, Tree};
+
+/// Fonts bundled for rasterizing badges, so the PNG does not depend on the fonts of the host.
+const BADGE_FONTS: [&[u8]; 2] = [
+    include_bytes!("../../templates/fonts/DejaVuSans.ttf"),
+    include_bytes!("../../templates/fonts/DejaVuSans-Bold.ttf"),
+];
+
+/// Family of the bundled fonts, used for any font family of the template.
+const BADGE_FONT_FAMILY: &str = "DejaVu Sans";
+
+/// Rendered badge artwork of a streak NFT.
+pub struct NftBadge {
+    pub bytes: Vec<u8>,
+    pub extension: &'static str,
+    pub content_type: &'static str,
+}
+
+pub struct NftBadgeApi {
+    template: Option<String>,
+    png: bool,
+}
+
+impl NftBadgeApi {
+    /// Creates new API wrapper, loading the SVG badge template if configured
+    pub fn new() -> Self {
+        let template = solana_util::get_nft_badge_template_path().map(|path| {
+            std::fs::read_to_string(&path)
+                .unwrap_or_else(|_| panic!("Failed to read NFT badge template at {}", path))
+        });
+
+        Self {
+            template,
+            png: solana_util::get_nft_badge_png(),
+        }
+    }
+
+    /// Renders the badge artwork for an achievement, or returns `None`
+    /// if no badge template is configured.
+    ///
+    /// Rendering is deterministic: the same placeholders always produce the same bytes
+    /// (PNGs only use the bundled fonts), so a badge can be regenerated and verified.
+    ///
+    /// # Arguments
+    /// * `placeholders` - Values for `{placeholder}` occurrences in the template.
+    pub fn render(&self, placeholders: &[(&str, String)]) -> Result<Option<NftBadge>> {
+        let Some(template) = &self.template else {
+            return Ok(None);
+        };
+
+        let svg = placeholders
+            .iter()
+            .fold(template.clone(), |svg, (placeholder, replacement)| {
+                svg.replace(&format!("{{{}}}", placeholder), &escape_xml(replacement))
+            });
+
+        if !self.png {
+            return Ok(Some(NftBadge {
+                bytes: svg.into_bytes(),
+                extension: "svg",
+                content_type: "image/svg+xml",
+            }));
+        }
+
+        Ok(Some(NftBadge {
+            bytes: rasterize(&svg)?,
+            extension: "png",
+            content_type: "image/png",
+        }))
+    }
+}
+
+impl Default for NftBadgeApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Rasterizes an SVG document to PNG at its own size, using only the bundled fonts for text.
+fn rasterize(svg: &str) -> Result<Vec<u8>> {
+    let mut options = Options {
+        font_family: BADGE_FONT_FAMILY.to_string(),
+        ..Options::default()
+    };
+
+    let fontdb = options.fontdb_mut();
+    for font in BADGE_FONTS {
+        fontdb.load_font_data(font.to_vec());
+    }
+    fontdb.set_sans_serif_family(BADGE_FONT_FAMILY);
+    fontdb.set_serif_family(BADGE_FONT_FAMILY);
+    fontdb.set_monospace_family(BADGE_FONT_FAMILY);
+
+    let tree = Tree::from_str(svg, &options).context("Invalid NFT badge SVG")?;
+
+    let size = tree.size().to_int_size();
+    let mut pixmap = Pixmap::new(size.width(), size.height()).context("Invalid NFT badge size")?;
+    resvg::render(&tree, Transform::default(), &mut pixmap.as_mut());
+
+    pixmap
+        .encode_png()
+        .context("Failed to encode NFT badge as PNG")
+}
+
+/// Escapes a placeholder value for use in SVG text and attributes.
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,272 @@
+use crate::models::kafka::SolanaQuizNftRequested;
+use crate::models::nft::{NftMetadata, NftMetadataTemplate, NftTier};
+use crate::services::nft_badge_api::{NftBadge, NftBadgeApi};
+use crate::utils::solana_util;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use mpl_token_metadata::{MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH};
+use serde_json::{Value, json};
+use solana_program::pubkey::Pubkey;
+use std::path::PathBuf;
+use tokio::fs;
+
+/// Placeholder value for stats that are unknown for an achievement.
+const UNKNOWN_VALUE: &str = "n/a";
+
+/// Badge color for tiers without their own color.
+const DEFAULT_TIER_COLOR: &str = "#9945FF";
+
+pub struct NftMetadataApi {
+    template: Option<NftMetadataTemplate>,
+    metadata_dir: PathBuf,
+    badge_api: NftBadgeApi,
+}
+
+impl NftMetadataApi {
+    /// Creates new API wrapper, loading the metadata template if configured
+    pub fn new() -> Self {
+        let template = solana_util::get_nft_metadata_template_path().map(|path| {
+            let content = std::fs::read_to_string(&path)
+                .unwrap_or_else(|_| panic!("Failed to read NFT metadata template at {}", path));
+
+            serde_json::from_str(&content)
+                .unwrap_or_else(|e| panic!("Invalid NFT metadata template at {}: {}", path, e))
+        });
+
+        Self {
+            template,
+            metadata_dir: solana_util::get_nft_metadata_dir(),
+            badge_api: NftBadgeApi::new(),
+        }
+    }
+
+    /// Returns the fixed NFT metadata from the environment
+    pub fn fixed_metadata() -> NftMetadata {
+        NftMetadata {
+            name: solana_util::get_nft_name(),
+            symbol: solana_util::get_nft_symbol(),
+            uri: solana_util::get_nft_meta_uri(),
+        }
+    }
+
+    /// Prepares the metadata for a new streak NFT.
+    ///
+    /// With a template, renders the off-chain JSON document for the achievement,
+    /// stores it as `<metadata dir>/<mint>.json` and returns its own URI under
+    /// `SOLANA_NFT_METADATA_BASE_URI`. Without a template, returns the fixed metadata.
+    ///
+    /// If a badge template is configured, the badge artwork is stored next to the
+    /// document as `<mint>.svg` (or `.png`) and referenced as its image.
+    ///
+    /// # Arguments
+    /// * `mint_pubkey` - Address of the NFT mint.
+    /// * `request` - The achievement the NFT is minted for.
+    pub async fn prepare(
+        &self,
+        mint_pubkey: &Pubkey,
+        request: &SolanaQuizNftRequested,
+    ) -> Result<NftMetadata> {
+        let Some(template) = &self.template else {
+            return Ok(Self::fixed_metadata());
+        };
+
+        let placeholders = render_placeholders(&template.tiers, mint_pubkey, request);
+        let mut document = render_value(&template.metadata, &placeholders);
+
+        let name = document
+            .get("name")
+            .and_then(Value::as_str)
+            .context("NFT metadata template has no name")?
+            .to_string();
+        let symbol = document
+            .get("symbol")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(solana_util::get_nft_symbol);
+        let base_uri = solana_util::get_nft_metadata_base_uri();
+        let base_uri = base_uri.trim_end_matches('/');
+        // An evolving passport keeps its files, so version its URIs per achievement
+        let version = if solana_util::get_nft_passport() {
+            format!("?v={}", request.quiz_id)
+        } else {
+            String::new()
+        };
+        let uri = format!("{}/{}.json{}", base_uri, mint_pubkey, version);
+
+        if name.len() > MAX_NAME_LENGTH {
+            bail!("NFT name \"{}\" exceeds {} bytes", name, MAX_NAME_LENGTH);
+        }
+        if symbol.len() > MAX_SYMBOL_LENGTH {
+            bail!(
+                "NFT symbol \"{}\" exceeds {} bytes",
+                symbol,
+                MAX_SYMBOL_LENGTH
+            );
+        }
+        if uri.len() > MAX_URI_LENGTH {
+            bail!("NFT metadata URI {} exceeds {} bytes", uri, MAX_URI_LENGTH);
+        }
+
+        fs::create_dir_all(&self.metadata_dir).await?;
+
+        // Store the badge artwork and reference it as the NFT image
+        if let Some(badge) = self.badge_api.render(&placeholders)? {
+            let file_name = format!("{}.{}", mint_pubkey, badge.extension);
+            fs::write(self.metadata_dir.join(&file_name), &badge.bytes)
+                .await
+                .with_context(|| format!("Failed to store NFT badge for {}", mint_pubkey))?;
+
+            set_image(
+                &mut document,
+                &format!("{}/{}{}", base_uri, file_name, version),
+                badge.content_type,
+            );
+        }
+
+        // Store the rendered document, so it is served under the NFT's own URI
+        fs::write(
+            self.metadata_dir.join(format!("{}.json", mint_pubkey)),
+            serde_json::to_vec_pretty(&document)?,
+        )
+        .await
+        .with_context(|| format!("Failed to store NFT metadata for {}", mint_pubkey))?;
+
+        Ok(NftMetadata { name, symbol, uri })
+    }
+}
+
+impl NftMetadataApi {
+    /// Renders the badge artwork of an achievement exactly as it is rendered for the mint,
+    /// or returns `None` if no badge template is configured.
+    pub fn render_badge(
+        &self,
+        mint_pubkey: &Pubkey,
+        request: &SolanaQuizNftRequested,
+    ) -> Result<Option<NftBadge>> {
+        let tiers = self
+            .template
+            .as_ref()
+            .map(|template| template.tiers.as_slice())
+            .unwrap_or_default();
+
+        self.badge_api
+            .render(&render_placeholders(tiers, mint_pubkey, request))
+    }
+}
+
+impl Default for NftMetadataApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds the placeholder values for an achievement:
+/// `{tier}`, `{tier_color}`, `{streak}`, `{date}`, `{quiz_count}`, `{accuracy}`,
+/// `{wallet}`, `{wallet_short}`, `{quiz_id}`, `{mint}`.
+fn render_placeholders(
+    tiers: &[NftTier],
+    mint_pubkey: &Pubkey,
+    request: &SolanaQuizNftRequested,
+) -> Vec<(&'static str, String)> {
+    // Highest tier reached by the streak
+    let tier = tiers
+        .iter()
+        .filter(|tier| tier.min_streak <= request.streak_days)
+        .max_by_key(|tier| tier.min_streak);
+
+    let tier_name = tier
+        .map(|tier| tier.name.clone())
+        .unwrap_or_else(|| UNKNOWN_VALUE.to_string());
+    let tier_color = tier
+        .and_then(|tier| tier.color.clone())
+        .unwrap_or_else(|| DEFAULT_TIER_COLOR.to_string());
+
+    // Achievement date, or today if the request does not carry one
+    let date = request
+        .achieved_at
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+        .unwrap_or_else(Utc::now)
+        .format("%Y-%m-%d")
+        .to_string();
+
+    let quiz_count = request
+        .total_quizzes
+        .map(|total_quizzes| total_quizzes.to_string())
+        .unwrap_or_else(|| UNKNOWN_VALUE.to_string());
+
+    // Accuracy in whole percent
+    let accuracy = match (request.correct_answers, request.total_questions) {
+        (Some(correct_answers), Some(total_questions)) if total_questions > 0 => {
+            ((correct_answers * 100 + total_questions / 2) / total_questions).to_string()
+        }
+        _ => UNKNOWN_VALUE.to_string(),
+    };
+
+    // Wallet shortened to its first and last 4 characters
+    let wallet = &request.user_wallet;
+    let wallet_short = match (
+        wallet.get(..4),
+        wallet.get(wallet.len().saturating_sub(4)..),
+    ) {
+        (Some(start), Some(end)) if wallet.len() > 8 => format!("{}...{}", start, end),
+        _ => wallet.clone(),
+    };
+
+    vec![
+        ("tier", tier_name),
+        ("tier_color", tier_color),
+        ("streak", request.streak_days.to_string()),
+        ("date", date),
+        ("quiz_count", quiz_count),
+        ("accuracy", accuracy),
+        ("wallet", request.user_wallet.clone()),
+        ("wallet_short", wallet_short),
+        ("quiz_id", request.quiz_id.to_string()),
+        ("mint", mint_pubkey.to_string()),
+    ]
+}
+
+/// Replaces `{placeholder}` occurrences in all string values of the template.
+fn render_value(value: &Value, placeholders: &[(&str, String)]) -> Value {
+    match value {
+        Value::String(text) => {
+            let rendered =
+                placeholders
+                    .iter()
+                    .fold(text.clone(), |text, (placeholder, replacement)| {
+                        text.replace(&format!("{{{}}}", placeholder), replacement)
+                    });
+
+            Value::String(rendered)
+        }
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| render_value(value, placeholders))
+                .collect(),
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| (key.clone(), render_value(value, placeholders)))
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+/// Points the metadata document's image (and its file list) at the given URI.
+fn set_image(document: &mut Value, image_uri: &str, content_type: &str) {
+    let Some(fields) = document.as_object_mut() else {
+        return;
+    };
+
+    fields.insert("image".to_string(), Value::String(image_uri.to_string()));
+    fields.insert(
+        "properties".to_string(),
+        json!({
+            "files": [{ "uri": image_uri, "type": content_type }],
+            "category": "image",
+        }),
+    );
+}
@@ -0,0 +1,356 @@
+use crate::metrics;
+use crate::utils::solana_util;
+use anyhow::Result;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Fee of a transaction signature in lamports.
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Maximum number of payers funded per transaction.
+const FUND_TRANSFERS_PER_TRANSACTION: usize = 20;
+
+/// Rotation and tracked balances of the pool, shared by every `PayerPoolApi` of the process.
+static POOL_STATE: LazyLock<Mutex<PoolState>> = LazyLock::new(Default::default);
+
+#[derive(Default)]
+struct PoolState {
+    /// Index of the payer the rotation continues from.
+    next: usize,
+    /// Balance of every payer, as last read from the chain minus the fees paid since.
+    balances: HashMap<Pubkey, TrackedBalance>,
+}
+
+struct TrackedBalance {
+    lamports: u64,
+    read_at: Instant,
+}
+
+pub struct PayerPoolApi {
+    rpc_client: RpcClient,
+    payers: Vec<Keypair>,
+    min_balance: u64,
+    refresh_interval: Duration,
+}
+
+/// API wrapper for the pool of keypairs paying the fees of reward transactions.
+///
+/// Every transaction is paid by the next payer of the pool holding at least
+/// `SOLANA_PAYER_POOL_MIN_BALANCE_LAMPORTS`, so transactions sent in parallel do not
+/// contend on the write lock and the balance of a single fee payer. Underfunded payers
+/// are skipped until `fund` (or a transfer) tops them up again.
+impl PayerPoolApi {
+    /// Creates a new instance using environment variables.
+    pub fn new() -> Self {
+        Self {
+            rpc_client: solana_util::create_rpc_client(),
+            payers: solana_util::get_payer_pool_keypairs(),
+            min_balance: solana_util::get_payer_pool_min_balance(),
+            refresh_interval: solana_util::get_payer_pool_refresh_interval(),
+        }
+    }
+
+    /// Creates a new instance with custom parameters (useful for testing).
+    pub fn _with_params(rpc_client: RpcClient, payers: Vec<Keypair>) -> Self {
+        Self {
+            rpc_client,
+            payers,
+            min_balance: solana_util::get_payer_pool_min_balance(),
+            refresh_interval: solana_util::get_payer_pool_refresh_interval(),
+        }
+    }
+
+    /// Returns the payers of the pool (empty when no pool is configured).
+    pub fn payers(&self) -> &[Keypair] {
+        &self.payers
+    }
+
+    /// Returns the next funded payer of the pool, rotating through the payers.
+    ///
+    /// # Returns
+    /// * `None` when no pool is configured or no payer is funded, the fee payer pays then
+    pub async fn acquire(&self) -> Option<&Keypair> {
+        if self.payers.is_empty() {
+            return None;
+        }
+
+        self.refresh().await;
+
+        let mut state = lock_state();
+        for offset in 0..self.payers.len() {
+            let index = (state.next + offset) % self.payers.len();
+            let payer = &self.payers[index];
+
+            let funded = state
+                .balances
+                .get(&payer.pubkey())
+                .is_some_and(|balance| balance.lamports >= self.min_balance);
+            if funded {
+                state.next = index + 1;
+                return Some(payer);
+            }
+        }
+
+        warn!(
+            "No payer of the pool holds {} lamports, the fee payer pays instead",
+            self.min_balance
+        );
+
+        None
+    }
+
+    /// Deducts the fee of a sent transaction from the tracked balance of its payer.
+    /// Payers outside of the pool are ignored.
+    ///
+    /// # Arguments
+    /// * `payer_pubkey` - the fee payer of the transaction
+    /// * `signatures` - number of signatures of the transaction
+    pub fn charge(&self, payer_pubkey: &Pubkey, signatures: usize) {
+        let mut state = lock_state();
+
+        if let Some(balance) = state.balances.get(payer_pubkey) {
+            let lamports = balance
+                .lamports
+                .saturating_sub(signatures as u64 * LAMPORTS_PER_SIGNATURE);
+            let read_at = balance.read_at;
+            self.track(&mut state, payer_pubkey, lamports, read_at);
+        }
+    }
+
+    /// Forgets the tracked balance of a payer whose transaction failed,
+    /// so it is read from the chain before the payer is used again.
+    pub fn invalidate(&self, payer_pubkey: &Pubkey) {
+        lock_state().balances.remove(payer_pubkey);
+    }
+
+    /// Reads the balances of all payers from the chain.
+    ///
+    /// # Returns
+    /// * The pubkey and balance in lamports of every payer, in pool order
+    pub async fn balances(&self) -> Result<Vec<(Pubkey, u64)>> {
+        let mut balances = vec![];
+
+        for payer in &self.payers {
+            let pubkey = payer.pubkey();
+            let lamports = self.rpc_client.get_balance(&pubkey).await?;
+            self.track(&mut lock_state(), &pubkey, lamports, Instant::now());
+            balances.push((pubkey, lamports));
+        }
+
+        Ok(balances)
+    }
+
+    /// Tops every payer of the pool up to `target_lamports` with transfers from `funder`.
+    ///
+    /// # Arguments
+    /// * `funder` - the keypair the SOL is transferred from, which also pays the fees
+    /// * `target_lamports` - balance every payer is topped up to
+    ///
+    /// # Returns
+    /// * The `Signature` of every funding transaction (none if all payers hold enough)
+    pub async fn fund(&self, funder: &Keypair, target_lamports: u64) -> Result<Vec<Signature>> {
+        let mut transfers = vec![];
+        for (pubkey, lamports) in self.balances().await? {
+            if lamports < target_lamports {
+                info!(
+                    "Funding payer {} with {} lamports (holds {})",
+                    pubkey,
+                    target_lamports - lamports,
+                    lamports
+                );
+                transfers.push(system_instruction::transfer(
+                    &funder.pubkey(),
+                    &pubkey,
+                    target_lamports - lamports,
+                ));
+            }
+        }
+
+        let mut signatures = vec![];
+        for chunk in transfers.chunks(FUND_TRANSFERS_PER_TRANSACTION) {
+            let transaction = solana_util::create_transaction(
+                chunk,
+                &funder.pubkey(),
+                &[funder],
+                self.rpc_client.get_latest_blockhash().await?,
+            )?;
+
+            signatures.push(
+                self.rpc_client
+                    .send_and_confirm_transaction(&transaction)
+                    .await?,
+            );
+        }
+
+        // Read the new balances, so the funded payers rejoin the rotation
+        if !signatures.is_empty() {
+            self.balances().await?;
+        }
+
+        Ok(signatures)
+    }
+
+    /// Reads the balances of the payers never read or last read longer than the refresh
+    /// interval ago. Failed reads keep the tracked balance.
+    async fn refresh(&self) {
+        let stale: Vec<Pubkey> = {
+            let state = lock_state();
+
+            self.payers
+                .iter()
+                .map(|payer| payer.pubkey())
+                .filter(|pubkey| {
+                    state
+                        .balances
+                        .get(pubkey)
+                        .is_none_or(|balance| balance.read_at.elapsed() >= self.refresh_interval)
+                })
+                .collect()
+        };
+
+        for pubkey in stale {
+            match self.rpc_client.get_balance(&pubkey).await {
+                Ok(lamports) => self.track(&mut lock_state(), &pubkey, lamports, Instant::now()),
+                Err(e) => warn!("Failed to read the balance of payer {}: {:?}", pubkey, e),
+            }
+        }
+    }
+
+    /// Records the balance of a payer, logging when it drops below the minimum
+    /// or is funded again.
+    fn track(&self, state: &mut PoolState, pubkey: &Pubkey, lamports: u64, read_at: Instant) {
+        let was_funded = state
+            .balances
+            .get(pubkey)
+            .map(|balance| balance.lamports >= self.min_balance);
+        let funded = lamports >= self.min_balance;
+
+        if !funded && was_funded != Some(false) {
+            warn!(
+                "Payer {} holds {} lamports (minimum {}), excluded from the pool until funded",
+                pubkey, lamports, self.min_balance
+            );
+        } else if funded && was_funded == Some(false) {
+            info!(
+                "Payer {} holds {} lamports, back in the pool",
+                pubkey, lamports
+            );
+        }
+
+        state
+            .balances
+            .insert(*pubkey, TrackedBalance { lamports, read_at });
+        metrics::PAYER_BALANCE
+            .with_label_values(&[&pubkey.to_string()])
+            .set(lamports as i64);
+    }
+}
+
+impl Default for PayerPoolApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Locks the pool state, which stays consistent even if a holder panicked.
+fn lock_state() -> MutexGuard<'static, PoolState> {
+    POOL_STATE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serializes the tests, which share the rotation of `POOL_STATE`.
+    static POOL_TEST: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    /// Balance returned by the mock RPC client for every payer.
+    const MOCK_BALANCE: u64 = 50;
+
+    /// Builds a pool of new payers, starting the rotation at the first one.
+    fn pool(size: usize) -> PayerPoolApi {
+        lock_state().next = 0;
+
+        PayerPoolApi {
+            rpc_client: RpcClient::new_mock("succeeds".to_string()),
+            payers: (0..size).map(|_| Keypair::new()).collect(),
+            min_balance: MOCK_BALANCE,
+            refresh_interval: Duration::from_secs(3600),
+        }
+    }
+
+    async fn acquire_index(pool: &PayerPoolApi) -> Option<usize> {
+        let payer = pool.acquire().await?.pubkey();
+
+        pool.payers().iter().position(|p| p.pubkey() == payer)
+    }
+
+    #[tokio::test]
+    async fn rotates_through_the_payers() {
+        let _serial = POOL_TEST.lock().await;
+        let pool = pool(3);
+
+        let mut order = vec![];
+        for _ in 0..4 {
+            order.push(acquire_index(&pool).await);
+        }
+
+        assert_eq!(order, vec![Some(0), Some(1), Some(2), Some(0)]);
+    }
+
+    #[tokio::test]
+    async fn skips_exhausted_payers() {
+        let _serial = POOL_TEST.lock().await;
+        let pool = pool(3);
+        assert_eq!(acquire_index(&pool).await, Some(0));
+
+        // The fee drops payer 1 below the minimum
+        pool.charge(&pool.payers()[1].pubkey(), 1);
+
+        assert_eq!(acquire_index(&pool).await, Some(2));
+        assert_eq!(acquire_index(&pool).await, Some(0));
+        assert_eq!(acquire_index(&pool).await, Some(2));
+
+        for payer in pool.payers() {
+            pool.charge(&payer.pubkey(), 1);
+        }
+        assert_eq!(acquire_index(&pool).await, None);
+    }
+
+    #[tokio::test]
+    async fn ignores_charges_of_other_payers() {
+        let _serial = POOL_TEST.lock().await;
+        let pool = pool(1);
+        let other = Keypair::new().pubkey();
+
+        pool.charge(&other, 1);
+
+        assert!(!lock_state().balances.contains_key(&other));
+        assert_eq!(acquire_index(&pool).await, Some(0));
+    }
+
+    #[tokio::test]
+    async fn invalidated_payers_rejoin_after_the_next_read() {
+        let _serial = POOL_TEST.lock().await;
+        let pool = pool(2);
+        let payer = pool.payers()[0].pubkey();
+        assert_eq!(acquire_index(&pool).await, Some(0));
+
+        pool.charge(&payer, 1);
+        assert_eq!(acquire_index(&pool).await, Some(1));
+        assert_eq!(acquire_index(&pool).await, Some(1));
+
+        // The balance is read from the chain again on the next acquire
+        pool.invalidate(&payer);
+        assert!(!lock_state().balances.contains_key(&payer));
+
+        assert_eq!(acquire_index(&pool).await, Some(0));
+        assert_eq!(lock_state().balances[&payer].lamports, MOCK_BALANCE);
+    }
+}
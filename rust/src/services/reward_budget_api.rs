@@ -0,0 +1,207 @@
+use crate::kafka::consumer_handlers::KafkaHeaders;
+use crate::metrics;
+use crate::models::reward::{
+    BudgetExceededAction, DeferredMessage, REWARD_BUDGETS_STORE, RewardBudget, RewardBudgetState,
+};
+use crate::storage::json_store::JsonStore;
+use crate::utils::{reward_util, token_util};
+use anyhow::Result;
+
+pub struct RewardBudgetApi {
+    store: JsonStore<RewardBudgetState>,
+    user_daily_tokens: Option<String>,
+    daily_tokens: Option<String>,
+    daily_nfts: Option<u64>,
+    action: BudgetExceededAction,
+}
+
+impl RewardBudgetApi {
+    /// Creates new API wrapper with the budgets from environment
+    pub fn new() -> Self {
+        Self {
+            store: JsonStore::new(REWARD_BUDGETS_STORE),
+            user_daily_tokens: reward_util::get_reward_budget_user_daily_tokens(),
+            daily_tokens: reward_util::get_reward_budget_daily_tokens(),
+            daily_nfts: reward_util::get_reward_budget_daily_nfts(),
+            action: reward_util::get_reward_budget_exceeded_action(),
+        }
+    }
+
+    /// Creates an API wrapper over the given store, with the budgets from environment
+    #[cfg(test)]
+    pub(crate) fn with_store(store: JsonStore<RewardBudgetState>) -> Self {
+        Self {
+            store,
+            ..Self::new()
+        }
+    }
+
+    /// Returns what to do with messages that exceed a budget.
+    pub fn action(&self) -> BudgetExceededAction {
+        self.action
+    }
+
+    /// Reserves a reward of `amount` base units against the user and global daily budgets.
+    ///
+    /// Returns the exhausted budget if the reward does not fit, in which case nothing is
+    /// reserved. A reward that is already reserved today (`key` is `wallet:quiz_id`)
+    /// is not counted again.
+    pub async fn reserve_tokens(
+        &self,
+        key: &str,
+        user_wallet: &str,
+        amount: u64,
+        decimals: u8,
+    ) -> Result<Option<RewardBudget>> {
+        let _lock = self.store.lock().await?;
+        let mut state = self.load_today().await?;
+
+        if state.rewards.contains_key(key) {
+            return Ok(None);
+        }
+
+        let user_tokens = state.user_tokens.get(user_wallet).copied().unwrap_or(0);
+
+        if let Some(limit) = &self.user_daily_tokens
+            && user_tokens.saturating_add(amount) > token_util::parse_token_amount(limit, decimals)?
+        {
+            return Ok(Some(exceeded(RewardBudget::UserDailyTokens)));
+        }
+
+        if let Some(limit) = &self.daily_tokens
+            && state.tokens.saturating_add(amount)
+                > token_util::parse_token_amount(limit, decimals)?
+        {
+            return Ok(Some(exceeded(RewardBudget::DailyTokens)));
+        }
+
+        state.rewards.insert(key.to_string(), amount);
+        state
+            .user_tokens
+            .insert(user_wallet.to_string(), user_tokens.saturating_add(amount));
+        state.tokens = state.tokens.saturating_add(amount);
+
+        self.store.save(&state).await?;
+
+        Ok(None)
+    }
+
+    /// Releases a reward reserved today, e.g. after its payout failed.
+    pub async fn release_tokens(&self, key: &str, user_wallet: &str) -> Result<()> {
+        let _lock = self.store.lock().await?;
+        let mut state = self.load_today().await?;
+
+        let Some(amount) = state.rewards.remove(key) else {
+            return Ok(());
+        };
+
+        if let Some(user_tokens) = state.user_tokens.get_mut(user_wallet) {
+            *user_tokens = user_tokens.saturating_sub(amount);
+        }
+        state.tokens = state.tokens.saturating_sub(amount);
+
+        self.store.save(&state).await
+    }
+
+    /// Reserves a new NFT mint against the daily NFT budget.
+    ///
+    /// Returns the exhausted budget if no NFT can be minted today.
+    pub async fn reserve_nft(&self) -> Result<Option<RewardBudget>> {
+        let _lock = self.store.lock().await?;
+        let mut state = self.load_today().await?;
+
+        if let Some(limit) = self.daily_nfts
+            && state.nfts >= limit
+        {
+            return Ok(Some(exceeded(RewardBudget::DailyNfts)));
+        }
+
+        state.nfts += 1;
+
+        self.store.save(&state).await?;
+
+        Ok(None)
+    }
+
+    /// Releases an NFT mint reserved today, e.g. after the mint failed.
+    pub async fn release_nft(&self) -> Result<()> {
+        let _lock = self.store.lock().await?;
+        let mut state = self.load_today().await?;
+
+        state.nfts = state.nfts.saturating_sub(1);
+
+        self.store.save(&state).await
+    }
+
+    /// Keeps a message until the next budget day, with its headers
+    /// (so a signed message can still be verified when it is republished).
+    pub async fn defer(
+        &self,
+        topic: &str,
+        key: &str,
+        payload: &str,
+        headers: &KafkaHeaders,
+    ) -> Result<()> {
+        let _lock = self.store.lock().await?;
+        let mut state = self.load_today().await?;
+
+        state.deferred.push(DeferredMessage {
+            day: state.day,
+            topic: topic.to_string(),
+            key: key.to_string(),
+            payload: payload.to_string(),
+            headers: headers.clone(),
+        });
+
+        self.store.save(&state).await
+    }
+
+    /// Removes and returns the messages deferred on previous days.
+    pub async fn take_due_deferred(&self) -> Result<Vec<DeferredMessage>> {
+        let _lock = self.store.lock().await?;
+        let mut state = self.load_today().await?;
+
+        let (due, deferred) = state
+            .deferred
+            .into_iter()
+            .partition(|message| message.day < state.day);
+        state.deferred = deferred;
+
+        if !due.is_empty() {
+            self.store.save(&state).await?;
+        }
+
+        Ok(due)
+    }
+
+    /// Loads the budget state, starting from zero usage on a new day.
+    async fn load_today(&self) -> Result<RewardBudgetState> {
+        let state = self.store.load().await?;
+        let today = reward_util::get_current_day();
+
+        if state.day == today {
+            return Ok(state);
+        }
+
+        Ok(RewardBudgetState {
+            day: today,
+            deferred: state.deferred,
+            ..Default::default()
+        })
+    }
+}
+
+impl Default for RewardBudgetApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Records an exhausted budget in the metrics.
+fn exceeded(budget: RewardBudget) -> RewardBudget {
+    metrics::BUDGET_EXCEEDED
+        .with_label_values(&[budget.name()])
+        .inc();
+
+    budget
+}
@@ -0,0 +1,76 @@
+use crate::models::kafka::SolanaQuizReward;
+use crate::models::reward::{HeldReward, REWARD_HOLDS_STORE, RewardRejection};
+use crate::storage::json_store::JsonStore;
+use anyhow::Result;
+use chrono::Utc;
+use std::collections::HashMap;
+
+pub struct RewardHoldApi {
+    store: JsonStore<HashMap<String, HeldReward>>,
+}
+
+impl RewardHoldApi {
+    /// Creates new API wrapper for the rewards held for manual review
+    pub fn new() -> Self {
+        Self {
+            store: JsonStore::new(REWARD_HOLDS_STORE),
+        }
+    }
+
+    /// Returns the key of a held reward (`wallet:quiz_id`).
+    pub fn key(user_wallet: &str, quiz_id: u64) -> String {
+        format!("{}:{}", user_wallet, quiz_id)
+    }
+
+    /// Keeps a reward for manual review, with the reason it was held.
+    /// A reward held again (same wallet and quiz) replaces the previous one.
+    pub async fn hold(&self, reward: &SolanaQuizReward, rejection: &RewardRejection) -> Result<()> {
+        let _lock = self.store.lock().await?;
+        let mut holds = self.store.load().await?;
+
+        holds.insert(
+            Self::key(&reward.user_wallet, reward.quiz_id),
+            HeldReward {
+                reward: reward.clone(),
+                reason: rejection.reason,
+                message: rejection.message.clone(),
+                held_at: Utc::now(),
+            },
+        );
+
+        self.store.save(&holds).await
+    }
+
+    /// Returns the rewards held for review, keyed by `wallet:quiz_id`.
+    pub async fn list(&self) -> Result<HashMap<String, HeldReward>> {
+        self.store.load().await
+    }
+
+    /// Removes and returns a held reward, or returns `None` if it is not held.
+    pub async fn take(&self, key: &str) -> Result<Option<HeldReward>> {
+        let _lock = self.store.lock().await?;
+        let mut holds = self.store.load().await?;
+
+        let held = holds.remove(key);
+        if held.is_some() {
+            self.store.save(&holds).await?;
+        }
+
+        Ok(held)
+    }
+
+    /// Puts a reward taken with `take` back, e.g. after its payout failed.
+    pub async fn restore(&self, key: &str, held: HeldReward) -> Result<()> {
+        let _lock = self.store.lock().await?;
+        let mut holds = self.store.load().await?;
+        holds.insert(key.to_string(), held);
+
+        self.store.save(&holds).await
+    }
+}
+
+impl Default for RewardHoldApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,303 @@
+use crate::models::kafka::SolanaQuizReward;
+use crate::models::reward::{RewardPolicy, RewardPolicyMode};
+use crate::utils::{reward_util, token_util};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use tracing::info;
+
+/// Precision of the reward multipliers (1/10000)
+const MULTIPLIER_SCALE: u128 = 10_000;
+
+pub struct RewardPolicyApi {
+    policy: Option<RewardPolicy>,
+    mode: RewardPolicyMode,
+}
+
+impl RewardPolicyApi {
+    /// Creates new API wrapper, loading the reward policy if configured
+    pub fn new() -> Self {
+        let policy = reward_util::get_reward_policy_path().map(|path| {
+            let content = std::fs::read_to_string(&path)
+                .unwrap_or_else(|_| panic!("Failed to read reward policy at {}", path));
+
+            let policy: RewardPolicy = serde_json::from_str(&content)
+                .unwrap_or_else(|e| panic!("Invalid reward policy at {}: {}", path, e));
+
+            let multipliers = policy
+                .streak_multipliers
+                .iter()
+                .map(|streak| streak.multiplier)
+                .chain(
+                    policy
+                        .promo_multipliers
+                        .iter()
+                        .map(|promo| promo.multiplier),
+                );
+            for multiplier in multipliers {
+                if !multiplier.is_finite() || multiplier < 0.0 {
+                    panic!(
+                        "Invalid multiplier {} in reward policy at {}",
+                        multiplier, path
+                    );
+                }
+            }
+
+            policy
+        });
+
+        Self {
+            policy,
+            mode: reward_util::get_reward_policy_mode(),
+        }
+    }
+
+    /// Returns the amount to pay for a reward, in base units of a mint with the given decimals.
+    ///
+    /// Without a policy, this is the amount computed by Node. In `verify` mode, Node's amount
+    /// is paid only if it matches the policy. In `authoritative` mode, the policy amount is paid.
+    pub fn reward_base_units(&self, reward: &SolanaQuizReward, decimals: u8) -> Result<u64> {
+        let Some(policy) = &self.policy else {
+            return reward.reward_base_units(decimals);
+        };
+
+        let policy_amount = compute_reward(policy, reward, decimals, Utc::now())?;
+
+        match self.mode {
+            RewardPolicyMode::Verify => {
+                let node_amount = reward.reward_base_units(decimals)?;
+                if node_amount != policy_amount {
+                    bail!(
+                        "Reward of {} tokens for quiz {} of {} does not match the reward policy ({} tokens)",
+                        token_util::format_token_amount(node_amount, decimals),
+                        reward.quiz_id,
+                        reward.user_wallet,
+                        token_util::format_token_amount(policy_amount, decimals)
+                    );
+                }
+
+                Ok(node_amount)
+            }
+            RewardPolicyMode::Authoritative => {
+                if let Ok(node_amount) = reward.reward_base_units(decimals)
+                    && node_amount != policy_amount
+                {
+                    info!(
+                        "Replacing reward of {} tokens for quiz {} of {} by the policy reward of {} tokens",
+                        token_util::format_token_amount(node_amount, decimals),
+                        reward.quiz_id,
+                        reward.user_wallet,
+                        token_util::format_token_amount(policy_amount, decimals)
+                    );
+                }
+
+                Ok(policy_amount)
+            }
+        }
+    }
+}
+
+impl Default for RewardPolicyApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Computes the reward of a quiz according to the policy, at the given time.
+fn compute_reward(
+    policy: &RewardPolicy,
+    reward: &SolanaQuizReward,
+    decimals: u8,
+    now: DateTime<Utc>,
+) -> Result<u64> {
+    let overflow = || format!("Policy reward for quiz {} is too large", reward.quiz_id);
+
+    let mut amount = policy
+        .tokens_per_correct_answer
+        .to_base_units(decimals)?
+        .checked_mul(reward.correct_answers)
+        .with_context(overflow)?;
+
+    // Bonus for answering all questions correctly
+    if reward.total_questions > 0
+        && reward.correct_answers == reward.total_questions
+        && let Some(bonus) = &policy.perfect_score_bonus
+    {
+        amount = amount
+            .checked_add(bonus.to_base_units(decimals)?)
+            .with_context(overflow)?;
+    }
+
+    // Highest multiplier reached by the streak
+    let streak_multiplier = policy
+        .streak_multipliers
+        .iter()
+        .filter(|streak| streak.min_streak <= reward.streak_days)
+        .max_by_key(|streak| streak.min_streak);
+    if let Some(streak) = streak_multiplier {
+        amount = apply_multiplier(amount, streak.multiplier).with_context(overflow)?;
+    }
+
+    // Promotions active right now
+    for promo in &policy.promo_multipliers {
+        if promo.starts_at <= now && now < promo.ends_at {
+            amount = apply_multiplier(amount, promo.multiplier).with_context(overflow)?;
+        }
+    }
+
+    if let Some(max_tokens) = &policy.max_tokens_per_reward {
+        amount = amount.min(max_tokens.to_base_units(decimals)?);
+    }
+
+    Ok(amount)
+}
+
+/// Multiplies an amount of base units, rounding down. Returns `None` on overflow.
+fn apply_multiplier(amount: u64, multiplier: f64) -> Option<u64> {
+    let scaled_multiplier = (multiplier * MULTIPLIER_SCALE as f64).round() as u128;
+
+    let amount = (amount as u128).checked_mul(scaled_multiplier)? / MULTIPLIER_SCALE;
+
+    u64::try_from(amount).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const DECIMALS: u8 = 2;
+
+    fn policy(json: &str) -> RewardPolicy {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn reward(total_questions: u64, correct_answers: u64, streak_days: u8) -> SolanaQuizReward {
+        SolanaQuizReward {
+            user_id: 1,
+            user_wallet: "wallet".to_string(),
+            quiz_id: 42,
+            total_questions,
+            correct_answers,
+            earned_tokens: None,
+            earned_base_units: None,
+            streak_days,
+        }
+    }
+
+    fn at(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, day, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn pays_per_correct_answer() {
+        let policy = policy(r#"{"tokens_per_correct_answer": "0.5"}"#);
+
+        let amount = compute_reward(&policy, &reward(10, 7, 1), DECIMALS, at(1)).unwrap();
+
+        assert_eq!(amount, 350);
+    }
+
+    #[test]
+    fn adds_the_perfect_score_bonus() {
+        let policy = policy(r#"{"tokens_per_correct_answer": 1, "perfect_score_bonus": 2}"#);
+
+        let perfect = compute_reward(&policy, &reward(5, 5, 1), DECIMALS, at(1)).unwrap();
+        let imperfect = compute_reward(&policy, &reward(5, 4, 1), DECIMALS, at(1)).unwrap();
+        let empty = compute_reward(&policy, &reward(0, 0, 1), DECIMALS, at(1)).unwrap();
+
+        assert_eq!(perfect, 700);
+        assert_eq!(imperfect, 400);
+        assert_eq!(empty, 0);
+    }
+
+    #[test]
+    fn applies_the_highest_streak_multiplier_reached() {
+        let policy = policy(
+            r#"{
+                "tokens_per_correct_answer": 1,
+                "streak_multipliers": [
+                    {"min_streak": 7, "multiplier": 2.0},
+                    {"min_streak": 3, "multiplier": 1.5}
+                ]
+            }"#,
+        );
+
+        let amount = |streak_days| {
+            compute_reward(&policy, &reward(10, 10, streak_days), DECIMALS, at(1)).unwrap()
+        };
+
+        assert_eq!(amount(2), 1000);
+        assert_eq!(amount(3), 1500);
+        assert_eq!(amount(6), 1500);
+        assert_eq!(amount(7), 2000);
+    }
+
+    #[test]
+    fn applies_promotions_while_active() {
+        let policy = policy(
+            r#"{
+                "tokens_per_correct_answer": 1,
+                "promo_multipliers": [{
+                    "name": "double weekend",
+                    "starts_at": "2025-06-07T00:00:00Z",
+                    "ends_at": "2025-06-09T00:00:00Z",
+                    "multiplier": 2.0
+                }]
+            }"#,
+        );
+        let reward = reward(10, 3, 1);
+        let ends_at = Utc.with_ymd_and_hms(2025, 6, 9, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            compute_reward(&policy, &reward, DECIMALS, at(6)).unwrap(),
+            300
+        );
+        assert_eq!(
+            compute_reward(&policy, &reward, DECIMALS, at(8)).unwrap(),
+            600
+        );
+        assert_eq!(
+            compute_reward(&policy, &reward, DECIMALS, ends_at).unwrap(),
+            300
+        );
+    }
+
+    #[test]
+    fn caps_the_reward() {
+        let policy = policy(
+            r#"{
+                "tokens_per_correct_answer": 1,
+                "streak_multipliers": [{"min_streak": 1, "multiplier": 3.0}],
+                "max_tokens_per_reward": 20
+            }"#,
+        );
+
+        let amount = compute_reward(&policy, &reward(10, 10, 1), DECIMALS, at(1)).unwrap();
+
+        assert_eq!(amount, 2000);
+    }
+
+    #[test]
+    fn rejects_overflowing_rewards() {
+        let policy = policy(&format!(
+            r#"{{"tokens_per_correct_answer": {}}}"#,
+            u64::MAX / 100
+        ));
+
+        assert!(compute_reward(&policy, &reward(10, 10, 1), DECIMALS, at(1)).is_err());
+    }
+
+    #[test]
+    fn multiplier_rounds_down() {
+        assert_eq!(apply_multiplier(100, 1.5), Some(150));
+        assert_eq!(apply_multiplier(3, 1.5), Some(4));
+        assert_eq!(apply_multiplier(1, 0.3333), Some(0));
+        assert_eq!(apply_multiplier(1000, 0.0), Some(0));
+    }
+
+    #[test]
+    fn multiplier_overflow_is_none() {
+        assert_eq!(apply_multiplier(u64::MAX, 1.0), Some(u64::MAX));
+        assert_eq!(apply_multiplier(u64::MAX, 2.0), None);
+    }
+}
@@ -0,0 +1,490 @@
+use crate::models::kafka::SolanaQuizReward;
+use crate::models::reward::{RewardRejection, RewardRejectionReason};
+use crate::models::sybil::{
+    SYBIL_LINKS_STORE, SYBIL_PROFILES_STORE, SybilAction, SybilFinding, SybilLinks, WalletProfile,
+};
+use crate::storage::json_store::JsonStore;
+use crate::utils::{reward_util, solana_util, token_util};
+use anyhow::Result;
+use chrono::Utc;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::UiTransactionEncoding;
+use std::collections::HashMap;
+
+/// Maximum number of signatures requested per RPC page.
+const SIGNATURES_PAGE_LIMIT: usize = 1000;
+
+/// Maximum number of signature pages read to find the first transaction of a wallet.
+/// A wallet with more signatures is old enough, and its funder is left unknown.
+const MAX_SIGNATURE_PAGES: usize = 10;
+
+/// Seconds in an hour.
+const SECONDS_PER_HOUR: i64 = 3600;
+
+pub struct RewardSybilApi {
+    rpc_client: RpcClient,
+    profiles: JsonStore<HashMap<String, WalletProfile>>,
+    links: JsonStore<SybilLinks>,
+    min_wallet_age_hours: Option<u64>,
+    min_sol_balance: Option<u64>,
+    max_users_per_wallet: Option<usize>,
+    max_users_per_funder: Option<usize>,
+    window_hours: u64,
+    cache_ttl_seconds: i64,
+    wallet_age_action: SybilAction,
+    sol_balance_action: SybilAction,
+    users_per_wallet_action: SybilAction,
+    users_per_funder_action: SybilAction,
+}
+
+impl RewardSybilApi {
+    /// Creates new API wrapper with the anti-sybil checks from environment
+    pub fn new() -> Self {
+        let min_sol_balance = reward_util::get_reward_sybil_min_sol_balance().map(|balance| {
+            token_util::parse_token_amount(&balance, token_util::SOL_DECIMALS)
+                .expect("REWARD_SYBIL_MIN_SOL_BALANCE must be a valid SOL amount")
+        });
+
+        Self {
+            rpc_client: solana_util::create_rpc_client(),
+            profiles: JsonStore::new(SYBIL_PROFILES_STORE),
+            links: JsonStore::new(SYBIL_LINKS_STORE),
+            min_wallet_age_hours: reward_util::get_reward_sybil_min_wallet_age_hours(),
+            min_sol_balance,
+            max_users_per_wallet: reward_util::get_reward_sybil_max_users_per_wallet(),
+            max_users_per_funder: reward_util::get_reward_sybil_max_users_per_funder(),
+            window_hours: reward_util::get_reward_sybil_window_hours(),
+            cache_ttl_seconds: reward_util::get_reward_sybil_cache_ttl_seconds(),
+            wallet_age_action: reward_util::get_reward_sybil_action("WALLET_AGE"),
+            sol_balance_action: reward_util::get_reward_sybil_action("SOL_BALANCE"),
+            users_per_wallet_action: reward_util::get_reward_sybil_action("USERS_PER_WALLET"),
+            users_per_funder_action: reward_util::get_reward_sybil_action("USERS_PER_FUNDER"),
+        }
+    }
+
+    /// Runs the configured anti-sybil checks on a reward.
+    ///
+    /// Returns the most severe failed check (reject, then hold, then flag), or `None`
+    /// if all checks pass. The user is recorded against the wallet and its funder
+    /// either way, so repeated attempts count towards the caps.
+    pub async fn check(&self, reward: &SolanaQuizReward) -> Result<Option<SybilFinding>> {
+        if self.min_wallet_age_hours.is_none()
+            && self.min_sol_balance.is_none()
+            && self.max_users_per_wallet.is_none()
+            && self.max_users_per_funder.is_none()
+        {
+            return Ok(None);
+        }
+
+        let wallet_pubkey = reward.user_wallet.parse::<Pubkey>()?;
+        let profile = self.load_profile(&wallet_pubkey).await?;
+        let now = Utc::now().timestamp();
+
+        let mut findings = vec![];
+
+        if let Some(min_age_hours) = self.min_wallet_age_hours {
+            let age_hours = profile
+                .first_signature_at
+                .map(|first_signature_at| (now - first_signature_at) / SECONDS_PER_HOUR)
+                .unwrap_or_default();
+
+            if age_hours < min_age_hours as i64 {
+                findings.push(finding(
+                    self.wallet_age_action,
+                    RewardRejectionReason::WalletTooNew,
+                    format!(
+                        "The wallet is {} hours old, the minimum is {} hours",
+                        age_hours, min_age_hours
+                    ),
+                ));
+            }
+        }
+
+        if let Some(min_balance) = self.min_sol_balance
+            && profile.balance < min_balance
+        {
+            findings.push(finding(
+                self.sol_balance_action,
+                RewardRejectionReason::BalanceTooLow,
+                format!(
+                    "The wallet holds {} SOL, the minimum is {} SOL",
+                    token_util::format_token_amount(profile.balance, token_util::SOL_DECIMALS),
+                    token_util::format_token_amount(min_balance, token_util::SOL_DECIMALS)
+                ),
+            ));
+        }
+
+        let (wallet_users, funder_users) = self
+            .record_user(reward, profile.funder.as_deref(), now)
+            .await?;
+
+        if let Some(max_users) = self.max_users_per_wallet
+            && wallet_users > max_users
+        {
+            findings.push(finding(
+                self.users_per_wallet_action,
+                RewardRejectionReason::WalletSharedByUsers,
+                format!(
+                    "{} users were rewarded to the wallet within {} hours, the maximum is {}",
+                    wallet_users, self.window_hours, max_users
+                ),
+            ));
+        }
+
+        if let (Some(max_users), Some(funder)) = (self.max_users_per_funder, &profile.funder)
+            && funder_users > max_users
+        {
+            findings.push(finding(
+                self.users_per_funder_action,
+                RewardRejectionReason::FunderSharedByUsers,
+                format!(
+                    "{} users were rewarded to wallets funded by {} within {} hours, \
+                     the maximum is {}",
+                    funder_users, funder, self.window_hours, max_users
+                ),
+            ));
+        }
+
+        Ok(most_severe(findings))
+    }
+
+    /// Returns the cached profile of a wallet, refreshing it once the cache expired.
+    async fn load_profile(&self, wallet_pubkey: &Pubkey) -> Result<WalletProfile> {
+        let mut profiles = self.profiles.load().await?;
+        let now = Utc::now().timestamp();
+
+        let cached = profiles.remove(&wallet_pubkey.to_string());
+        if let Some(profile) = &cached
+            && now - profile.checked_at < self.cache_ttl_seconds
+        {
+            return Ok(profile.clone());
+        }
+
+        // The first transaction never changes once known, only the balance does
+        let (first_signature_at, funder) = match cached {
+            Some(WalletProfile {
+                first_signature_at: Some(first_signature_at),
+                funder,
+                ..
+            }) => (Some(first_signature_at), funder),
+            _ => self.fetch_first_transaction(wallet_pubkey).await?,
+        };
+
+        let profile = WalletProfile {
+            first_signature_at,
+            funder,
+            balance: self.rpc_client.get_balance(wallet_pubkey).await?,
+            checked_at: now,
+        };
+
+        // Reload under the lock, so profiles cached meanwhile are kept
+        let _lock = self.profiles.lock().await?;
+        let mut profiles = self.profiles.load().await?;
+        profiles.insert(wallet_pubkey.to_string(), profile.clone());
+        self.profiles.save(&profiles).await?;
+
+        Ok(profile)
+    }
+
+    /// Finds the time of the wallet's first transaction and its fee payer (the funder),
+    /// paging backwards through the wallet's signatures.
+    async fn fetch_first_transaction(
+        &self,
+        wallet_pubkey: &Pubkey,
+    ) -> Result<(Option<i64>, Option<String>)> {
+        let mut before = None;
+        let mut oldest = None;
+
+        for _ in 0..MAX_SIGNATURE_PAGES {
+            let page = self
+                .rpc_client
+                .get_signatures_for_address_with_config(
+                    wallet_pubkey,
+                    GetConfirmedSignaturesForAddress2Config {
+                        before,
+                        until: None,
+                        limit: Some(SIGNATURES_PAGE_LIMIT),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                    },
+                )
+                .await?;
+
+            let page_len = page.len();
+            if let Some(status) = page.into_iter().last() {
+                before = Some(status.signature.parse::<Signature>()?);
+                oldest = Some(status);
+            }
+
+            // Reached the first transaction
+            if page_len < SIGNATURES_PAGE_LIMIT {
+                let Some(first) = oldest else {
+                    return Ok((None, None));
+                };
+                let funder = self
+                    .fetch_fee_payer(&first.signature.parse()?)
+                    .await?
+                    .filter(|funder| funder != wallet_pubkey);
+
+                return Ok((first.block_time, funder.map(|funder| funder.to_string())));
+            }
+        }
+
+        Ok((oldest.and_then(|status| status.block_time), None))
+    }
+
+    /// Fetches the fee payer of a confirmed transaction.
+    async fn fetch_fee_payer(&self, signature: &Signature) -> Result<Option<Pubkey>> {
+        let transaction = self
+            .rpc_client
+            .get_transaction_with_config(
+                signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Json),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await?;
+
+        Ok(transaction
+            .transaction
+            .transaction
+            .decode()
+            .and_then(|transaction| transaction.message.static_account_keys().first().copied()))
+    }
+
+    /// Records the user against the wallet and its funder, forgetting users seen before
+    /// the window. Returns the number of users of the wallet and of the funder.
+    async fn record_user(
+        &self,
+        reward: &SolanaQuizReward,
+        funder: Option<&str>,
+        now: i64,
+    ) -> Result<(usize, usize)> {
+        let _lock = self.links.lock().await?;
+        let mut links = self.links.load().await?;
+        let window_start = now - self.window_hours as i64 * SECONDS_PER_HOUR;
+
+        for users in links.wallets.values_mut().chain(links.funders.values_mut()) {
+            users.retain(|_, last_seen_at| *last_seen_at >= window_start);
+        }
+        links.wallets.retain(|_, users| !users.is_empty());
+        links.funders.retain(|_, users| !users.is_empty());
+
+        let wallet_users = links.wallets.entry(reward.user_wallet.clone()).or_default();
+        wallet_users.insert(reward.user_id, now);
+        let wallet_users = wallet_users.len();
+
+        let funder_users = match funder {
+            Some(funder) => {
+                let funder_users = links.funders.entry(funder.to_string()).or_default();
+                funder_users.insert(reward.user_id, now);
+                funder_users.len()
+            }
+            None => 0,
+        };
+
+        self.links.save(&links).await?;
+
+        Ok((wallet_users, funder_users))
+    }
+}
+
+impl Default for RewardSybilApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds the finding of a failed check.
+fn finding(action: SybilAction, reason: RewardRejectionReason, message: String) -> SybilFinding {
+    SybilFinding {
+        rejection: RewardRejection::new(reason, message),
+        action,
+    }
+}
+
+/// Returns the first of the most severe findings.
+fn most_severe(findings: Vec<SybilFinding>) -> Option<SybilFinding> {
+    // `max_by_key` returns the last maximum, so iterate in reverse
+    findings
+        .into_iter()
+        .rev()
+        .max_by_key(|finding| finding.action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALLET: &str = "11111111111111111111111111111112";
+    const NOW: i64 = 1_750_000_000;
+
+    fn api(name: &str) -> RewardSybilApi {
+        let store_path = |store: &str| {
+            let path = std::env::temp_dir().join(format!(
+                "reward_sybil_api_{}_{}_{}.json",
+                store,
+                name,
+                std::process::id()
+            ));
+            let _ = std::fs::remove_file(&path);
+            path
+        };
+
+        RewardSybilApi {
+            rpc_client: RpcClient::new_mock("succeeds".to_string()),
+            profiles: JsonStore::with_path(store_path("profiles")),
+            links: JsonStore::with_path(store_path("links")),
+            min_wallet_age_hours: None,
+            min_sol_balance: None,
+            max_users_per_wallet: None,
+            max_users_per_funder: None,
+            window_hours: 24,
+            cache_ttl_seconds: 3600,
+            wallet_age_action: SybilAction::Reject,
+            sol_balance_action: SybilAction::Reject,
+            users_per_wallet_action: SybilAction::Reject,
+            users_per_funder_action: SybilAction::Reject,
+        }
+    }
+
+    fn reward(user_id: u64) -> SolanaQuizReward {
+        SolanaQuizReward {
+            user_id,
+            user_wallet: WALLET.to_string(),
+            quiz_id: 42,
+            total_questions: 10,
+            correct_answers: 10,
+            earned_tokens: None,
+            earned_base_units: None,
+            streak_days: 1,
+        }
+    }
+
+    fn flag(reason: RewardRejectionReason) -> SybilFinding {
+        finding(SybilAction::Flag, reason, String::new())
+    }
+
+    fn hold(reason: RewardRejectionReason) -> SybilFinding {
+        finding(SybilAction::Hold, reason, String::new())
+    }
+
+    #[test]
+    fn picks_the_most_severe_finding() {
+        let most = most_severe(vec![
+            flag(RewardRejectionReason::WalletTooNew),
+            hold(RewardRejectionReason::BalanceTooLow),
+            flag(RewardRejectionReason::WalletSharedByUsers),
+        ])
+        .unwrap();
+
+        assert_eq!(most.action, SybilAction::Hold);
+        assert_eq!(most.rejection.reason, RewardRejectionReason::BalanceTooLow);
+    }
+
+    #[test]
+    fn picks_the_first_of_equally_severe_findings() {
+        let most = most_severe(vec![
+            flag(RewardRejectionReason::WalletTooNew),
+            hold(RewardRejectionReason::BalanceTooLow),
+            hold(RewardRejectionReason::WalletSharedByUsers),
+        ])
+        .unwrap();
+
+        assert_eq!(most.rejection.reason, RewardRejectionReason::BalanceTooLow);
+        assert!(most_severe(vec![]).is_none());
+    }
+
+    #[tokio::test]
+    async fn forgets_users_seen_before_the_window() {
+        let api = api("window");
+        let window = api.window_hours as i64 * SECONDS_PER_HOUR;
+
+        api.record_user(&reward(1), Some("funder"), NOW)
+            .await
+            .unwrap();
+        api.record_user(&reward(2), Some("funder"), NOW + window / 2)
+            .await
+            .unwrap();
+
+        // User 1 is still within the window
+        let counts = api
+            .record_user(&reward(3), Some("funder"), NOW + window)
+            .await
+            .unwrap();
+        assert_eq!(counts, (3, 3));
+
+        // User 1 fell out of the window, users 2 and 3 are kept
+        let counts = api
+            .record_user(&reward(3), None, NOW + window + 1)
+            .await
+            .unwrap();
+        assert_eq!(counts, (2, 0));
+
+        let links = api.links.load().await.unwrap();
+        assert!(!links.wallets[WALLET].contains_key(&1));
+        assert_eq!(links.funders["funder"].len(), 2);
+
+        // Wallets and funders without users in the window are dropped
+        api.record_user(&reward(4), None, NOW + 3 * window)
+            .await
+            .unwrap();
+        let links = api.links.load().await.unwrap();
+        assert_eq!(links.wallets[WALLET].len(), 1);
+        assert!(links.funders.is_empty());
+    }
+
+    #[tokio::test]
+    async fn reuses_the_cached_profile() {
+        let api = api("cache");
+        let wallet_pubkey = WALLET.parse::<Pubkey>().unwrap();
+        let cached = WalletProfile {
+            first_signature_at: Some(NOW),
+            funder: Some("funder".to_string()),
+            balance: 7,
+            checked_at: Utc::now().timestamp(),
+        };
+        api.profiles
+            .save(&HashMap::from([(WALLET.to_string(), cached.clone())]))
+            .await
+            .unwrap();
+
+        let profile = api.load_profile(&wallet_pubkey).await.unwrap();
+
+        // The balance is not refreshed (the mock RPC would return 50)
+        assert_eq!(profile.balance, 7);
+        assert_eq!(profile.checked_at, cached.checked_at);
+    }
+
+    #[tokio::test]
+    async fn refreshes_only_the_balance_of_an_expired_profile() {
+        let api = api("expired");
+        let wallet_pubkey = WALLET.parse::<Pubkey>().unwrap();
+        let expired = WalletProfile {
+            first_signature_at: Some(NOW),
+            funder: Some("funder".to_string()),
+            balance: 7,
+            checked_at: Utc::now().timestamp() - api.cache_ttl_seconds,
+        };
+        api.profiles
+            .save(&HashMap::from([(WALLET.to_string(), expired.clone())]))
+            .await
+            .unwrap();
+
+        let profile = api.load_profile(&wallet_pubkey).await.unwrap();
+
+        assert_eq!(profile.balance, 50);
+        assert!(profile.checked_at > expired.checked_at);
+        assert_eq!(profile.first_signature_at, Some(NOW));
+        assert_eq!(profile.funder.as_deref(), Some("funder"));
+
+        let profiles = api.profiles.load().await.unwrap();
+        assert_eq!(profiles[WALLET].balance, 50);
+    }
+}
@@ -0,0 +1,272 @@
+use crate::models::kafka::SolanaQuizReward;
+use crate::models::reward::{
+    REWARD_MILESTONES_STORE, REWARD_STREAKS_STORE, RewardRejection, RewardRejectionReason,
+    ValidationFailedAction, WalletStreak,
+};
+use crate::storage::json_store::JsonStore;
+use crate::utils::{reward_util, solana_util, token_util};
+use anyhow::Result;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+use solana_program::system_program;
+use std::collections::HashMap;
+
+pub struct RewardValidationApi {
+    rpc_client: RpcClient,
+    streaks: JsonStore<HashMap<String, WalletStreak>>,
+    milestones: JsonStore<HashMap<String, i64>>,
+    max_tokens: String,
+    max_questions: u64,
+    action: ValidationFailedAction,
+}
+
+impl RewardValidationApi {
+    /// Creates new API wrapper with the limits from environment
+    pub fn new() -> Self {
+        Self {
+            rpc_client: solana_util::create_rpc_client(),
+            streaks: JsonStore::new(REWARD_STREAKS_STORE),
+            milestones: JsonStore::new(REWARD_MILESTONES_STORE),
+            max_tokens: reward_util::get_reward_validation_max_tokens(),
+            max_questions: reward_util::get_reward_validation_max_questions(),
+            action: reward_util::get_reward_validation_failed_action(),
+        }
+    }
+
+    /// Returns what to do with rewards that fail validation.
+    pub fn action(&self) -> ValidationFailedAction {
+        self.action
+    }
+
+    /// Checks the answers, the wallet and (in off-chain mode) the streak of a reward.
+    ///
+    /// Returns the rejection if an invariant is violated. Errors are reserved
+    /// for failures to check (e.g. the RPC is unavailable).
+    pub async fn validate(&self, reward: &SolanaQuizReward) -> Result<Option<RewardRejection>> {
+        if reward.total_questions == 0
+            || reward.total_questions > self.max_questions
+            || reward.correct_answers > reward.total_questions
+        {
+            return Ok(Some(RewardRejection::new(
+                RewardRejectionReason::InvalidAnswers,
+                format!(
+                    "{} correct answers of {} questions (at most {} questions)",
+                    reward.correct_answers, reward.total_questions, self.max_questions
+                ),
+            )));
+        }
+
+        if let Some(rejection) = self.validate_wallet(&reward.user_wallet).await? {
+            return Ok(Some(rejection));
+        }
+
+        // On-chain, the program keeps the streak itself
+        if !solana_util::get_solana_on_chain()
+            && let Some(rejection) = self.validate_streak(reward).await?
+        {
+            return Ok(Some(rejection));
+        }
+
+        Ok(None)
+    }
+
+    /// Checks the amount to pay out, in base units of a mint with the given decimals.
+    ///
+    /// A zero amount is valid, the quiz is recorded without a payout.
+    pub fn validate_amount(&self, amount: u64, decimals: u8) -> Result<Option<RewardRejection>> {
+        let max_amount = token_util::parse_token_amount(&self.max_tokens, decimals)?;
+        if amount > max_amount {
+            return Ok(Some(RewardRejection::new(
+                RewardRejectionReason::AmountTooLarge,
+                format!(
+                    "Reward of {} tokens is above the maximum of {} tokens",
+                    token_util::format_token_amount(amount, decimals),
+                    self.max_tokens
+                ),
+            )));
+        }
+
+        Ok(None)
+    }
+
+    /// Records the streak of a paid reward, as the base for validating the next one.
+    pub async fn record_streak(&self, reward: &SolanaQuizReward) -> Result<()> {
+        let mut streaks = self.streaks.load().await?;
+
+        streaks.insert(
+            reward.user_wallet.clone(),
+            WalletStreak {
+                day: reward_util::get_current_day(),
+                streak: reward.streak_days,
+            },
+        );
+
+        self.streaks.save(&streaks).await
+    }
+
+    /// Records that a wallet reached the streak goal today.
+    ///
+    /// Only the first quiz of a day moves the streak, so further quizzes on the same day
+    /// report the goal again. Returns `false` for those, no new NFT is due.
+    pub async fn record_milestone(&self, user_wallet: &str) -> Result<bool> {
+        let mut milestones = self.milestones.load().await?;
+
+        let day = reward_util::get_current_day();
+        if milestones.get(user_wallet) == Some(&day) {
+            return Ok(false);
+        }
+
+        milestones.insert(user_wallet.to_string(), day);
+        self.milestones.save(&milestones).await?;
+
+        Ok(true)
+    }
+
+    /// Checks that the wallet is an on-curve address owned by the System Program.
+    /// A wallet that does not exist on-chain yet is accepted.
+    async fn validate_wallet(&self, user_wallet: &str) -> Result<Option<RewardRejection>> {
+        let Ok(wallet_pubkey) = user_wallet.parse::<Pubkey>() else {
+            return Ok(Some(RewardRejection::new(
+                RewardRejectionReason::InvalidWallet,
+                format!("{} is not a valid public key", user_wallet),
+            )));
+        };
+
+        // PDAs have no private key, so tokens sent there could never be used
+        if !wallet_pubkey.is_on_curve() {
+            return Ok(Some(RewardRejection::new(
+                RewardRejectionReason::InvalidWallet,
+                format!("{} is off-curve (e.g. a PDA)", user_wallet),
+            )));
+        }
+
+        let account = self
+            .rpc_client
+            .get_account_with_commitment(&wallet_pubkey, self.rpc_client.commitment())
+            .await?
+            .value;
+
+        if let Some(account) = account
+            && account.owner != system_program::ID
+        {
+            return Ok(Some(RewardRejection::new(
+                RewardRejectionReason::WalletNotSystemOwned,
+                format!("{} is owned by {}", user_wallet, account.owner),
+            )));
+        }
+
+        Ok(None)
+    }
+
+    /// Checks the streak of a reward against the last streak seen for its wallet.
+    async fn validate_streak(&self, reward: &SolanaQuizReward) -> Result<Option<RewardRejection>> {
+        let last = self.streaks.load().await?.remove(&reward.user_wallet);
+
+        Ok(check_streak(
+            reward.streak_days,
+            last.as_ref(),
+            reward_util::get_current_day(),
+        ))
+    }
+}
+
+impl Default for RewardValidationApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Checks that a streak grew by at most one day since the `last` streak seen for the wallet.
+///
+/// A streak of zero (a quiz without a perfect score) resets the streak, and a new streak
+/// of one day can start on any day, including the first reward seen for a wallet.
+fn check_streak(
+    streak_days: u8,
+    last: Option<&WalletStreak>,
+    today: i64,
+) -> Option<RewardRejection> {
+    let max_streak = match last {
+        None => 1,
+        Some(last) => match today - last.day {
+            0 => last.streak.max(1),
+            1 => last.streak.saturating_add(1),
+            _ => 1,
+        },
+    };
+
+    if streak_days <= max_streak {
+        return None;
+    }
+
+    let message = match last {
+        Some(last) => format!(
+            "Streak of {} days, at most {} expected after {} days on day {}",
+            streak_days, max_streak, last.streak, last.day
+        ),
+        None => format!(
+            "Streak of {} days, at most {} expected for a new wallet",
+            streak_days, max_streak
+        ),
+    };
+
+    Some(RewardRejection::new(
+        RewardRejectionReason::InvalidStreak,
+        message,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TODAY: i64 = 20_000;
+
+    fn accepts(streak_days: u8, last: Option<WalletStreak>) -> bool {
+        check_streak(streak_days, last.as_ref(), TODAY).is_none()
+    }
+
+    fn last(days_ago: i64, streak: u8) -> Option<WalletStreak> {
+        Some(WalletStreak {
+            day: TODAY - days_ago,
+            streak,
+        })
+    }
+
+    #[test]
+    fn zero_resets_the_streak() {
+        for last in [None, last(0, 3), last(1, 3), last(5, 3)] {
+            assert!(accepts(0, last));
+        }
+    }
+
+    #[test]
+    fn new_wallet_starts_at_most_one() {
+        assert!(accepts(1, None));
+        assert!(!accepts(2, None));
+        assert!(!accepts(6, None));
+    }
+
+    #[test]
+    fn next_day_grows_by_one() {
+        assert!(accepts(4, last(1, 3)));
+        assert!(accepts(1, last(1, 3)));
+        assert!(accepts(1, last(1, 0)));
+        assert!(!accepts(2, last(1, 0)));
+        assert!(accepts(u8::MAX, last(1, u8::MAX)));
+    }
+
+    #[test]
+    fn same_day_keeps_the_streak() {
+        assert!(accepts(3, last(0, 3)));
+        assert!(!accepts(4, last(0, 3)));
+        assert!(accepts(1, last(0, 0)));
+    }
+
+    #[test]
+    fn jump_is_rejected() {
+        assert!(!accepts(5, last(1, 3)));
+        assert!(!accepts(4, last(2, 3)));
+        assert!(accepts(1, last(2, 3)));
+        assert!(!accepts(7, last(30, 6)));
+    }
+}
@@ -1,39 +1,63 @@
+use crate::services::lookup_table_api::LookupTableApi;
+use crate::services::payer_pool_api::PayerPoolApi;
 use crate::utils::solana_util;
-use anyhow::Result;
+use anyhow::{Result, anyhow, bail};
+use futures::future::join_all;
 use mpl_token_metadata::accounts::Metadata;
 use mpl_token_metadata::instructions::CreateV1Builder;
 use mpl_token_metadata::types::TokenStandard;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_program::{
-    native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey,
-    system_instruction::create_account,
+    native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction::create_account,
 };
 use solana_sdk::{
+    address_lookup_table_account::AddressLookupTableAccount,
+    instruction::Instruction,
     signature::{Keypair, Signature},
     signer::Signer,
     transaction::Transaction,
 };
 use spl_associated_token_account::{
-    get_associated_token_address, instruction::create_associated_token_account_idempotent,
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
 };
-use spl_token::{
-    ID as TOKEN_PROGRAM_ID,
-    instruction::{initialize_mint2, mint_to_checked, transfer_checked},
+use spl_token_2022::{
+    extension::{
+        ExtensionType, StateWithExtensions, metadata_pointer,
+        transfer_fee::instruction::initialize_transfer_fee_config,
+    },
+    instruction::{
+        freeze_account, initialize_mint2, initialize_permanent_delegate, mint_to_checked,
+        thaw_account, transfer_checked,
+    },
     state::Mint,
 };
+use spl_token_metadata_interface::state::TokenMetadata;
+use tracing::warn;
+
+/// Estimated compute units of a reward transfer, including the creation of the
+/// recipient's token account.
+const TRANSFER_COMPUTE_UNITS: u64 = 40_000;
 
 pub struct SolanaApi {
     rpc_client: RpcClient,
     authority_keypair: Keypair,
+    fee_payer_keypair: Keypair,
+    rent_payer_keypair: Keypair,
+    payer_pool: PayerPoolApi,
     mint_account: Keypair,
+    token_program_id: Pubkey,
 }
 
 /// High-level API wrapper for a Solana client and keypairs.
 ///
 /// Encapsulates:
 /// - An async Solana `RpcClient`
-/// - The authority keypair (from environment)
+/// - The authority keypair (from environment): mint, freeze and treasury authority
+/// - The fee payer and rent payer keypairs (from environment, default to the authority)
+/// - The pool of payers of the token transfers (from environment, optional)
 /// - The mint account keypair (from environment)
+/// - The token program of the mint (SPL Token or Token-2022, from environment)
 ///
 /// Provides convenience methods such as requesting an airdrop.
 impl SolanaApi {
@@ -46,20 +70,29 @@ impl SolanaApi {
         Self {
             rpc_client,
             authority_keypair,
+            fee_payer_keypair: solana_util::get_fee_payer_keypair(),
+            rent_payer_keypair: solana_util::get_rent_payer_keypair(),
+            payer_pool: PayerPoolApi::new(),
             mint_account,
+            token_program_id: solana_util::get_token_program_id(),
         }
     }
 
     /// Creates a new instance with custom parameters (useful for testing).
+    /// The authority also pays fees and rent, without a payer pool.
     pub fn _with_params(
         rpc_client: RpcClient,
         authority_keypair: Keypair,
         mint_account: Keypair,
     ) -> Self {
         Self {
+            payer_pool: PayerPoolApi::_with_params(RpcClient::new(rpc_client.url()), vec![]),
             rpc_client,
+            fee_payer_keypair: authority_keypair.insecure_clone(),
+            rent_payer_keypair: authority_keypair.insecure_clone(),
             authority_keypair,
             mint_account,
+            token_program_id: solana_util::get_token_program_id(),
         }
     }
 
@@ -69,7 +102,7 @@ impl SolanaApi {
     ///
     /// # Arguments
     /// * `sol_amount` - amount of SOL to request
-    /// * `pubkey` - pubkey to receive SOL
+    /// * `pubkey` - pubkey to receive SOL (defaults to the fee payer)
     ///
     /// # Returns
     /// * `Signature` of the airdrop transaction
@@ -79,7 +112,7 @@ impl SolanaApi {
         pubkey: &Option<Pubkey>,
     ) -> Result<Signature> {
         // Determine which pubkey will receive the SOL
-        let final_pubkey = pubkey.unwrap_or_else(|| self.authority_keypair.pubkey());
+        let final_pubkey = pubkey.unwrap_or_else(|| self.fee_payer_keypair.pubkey());
 
         // Request the airdrop
         let transaction_signature = self
@@ -103,46 +136,117 @@ impl SolanaApi {
 
     /// Creates a new mint account and initializes it.
     ///
+    /// On Token-2022, the mint also gets the metadata pointer + token metadata extensions
+    /// (instead of Metaplex metadata), and optionally a transfer fee and a permanent delegate.
+    ///
     /// [How to Create a Token](https://solana.com/developers/cookbook/tokens/create-mint-account)
     ///
     /// # Returns
     /// * `Signature` of the mint creation transaction
     pub async fn create_mint(&self) -> Result<Signature> {
-        let mint_account_len = Mint::LEN;
+        let token_program_id = &self.token_program_id;
+        let mint_pubkey = self.mint_account.pubkey();
+        let authority_pubkey = self.authority_keypair.pubkey();
+        let rent_payer_pubkey = self.rent_payer_keypair.pubkey();
+
+        let mut extension_types = vec![];
+        let mut extension_instructions = vec![];
+        let mut token_metadata = None;
+
+        if *token_program_id == spl_token_2022::ID {
+            // Point the metadata at the mint itself, it is stored as a mint extension
+            extension_types.push(ExtensionType::MetadataPointer);
+            extension_instructions.push(metadata_pointer::instruction::initialize(
+                token_program_id,       // Token-2022 program
+                &mint_pubkey,           // mint account
+                Some(authority_pubkey), // pointer authority
+                Some(mint_pubkey),      // metadata address
+            )?);
+
+            if let Some((basis_points, maximum_fee)) = solana_util::get_token_transfer_fee() {
+                extension_types.push(ExtensionType::TransferFeeConfig);
+                extension_instructions.push(initialize_transfer_fee_config(
+                    token_program_id,        // Token-2022 program
+                    &mint_pubkey,            // mint account
+                    Some(&authority_pubkey), // fee config authority
+                    Some(&authority_pubkey), // withdraw withheld fees authority
+                    basis_points,            // fee in basis points
+                    maximum_fee,             // maximum fee in base units
+                )?);
+            }
+
+            if let Some(delegate) = solana_util::get_token_permanent_delegate() {
+                extension_types.push(ExtensionType::PermanentDelegate);
+                extension_instructions.push(initialize_permanent_delegate(
+                    token_program_id, // Token-2022 program
+                    &mint_pubkey,     // mint account
+                    &delegate,        // permanent delegate
+                )?);
+            }
+
+            token_metadata = Some(TokenMetadata {
+                update_authority: Some(authority_pubkey).try_into()?,
+                mint: mint_pubkey,
+                name: solana_util::get_token_name(),
+                symbol: solana_util::get_token_symbol(),
+                uri: solana_util::get_token_meta_uri(),
+                additional_metadata: vec![],
+            });
+        }
+
+        // The token metadata is written after the mint is initialized (the account grows),
+        // so the rent must already cover it
+        let mint_account_len = ExtensionType::try_calculate_account_len::<Mint>(&extension_types)?;
+        let token_metadata_len = match &token_metadata {
+            Some(token_metadata) => token_metadata.tlv_size_of()?,
+            None => 0,
+        };
         let mint_account_rent = self
             .rpc_client
-            .get_minimum_balance_for_rent_exemption(mint_account_len)
+            .get_minimum_balance_for_rent_exemption(mint_account_len + token_metadata_len)
             .await?;
 
         // Instruction to create a new account for the mint
         let create_mint_account_ix = create_account(
-            &self.authority_keypair.pubkey(), // payer: pays rent for the new account
-            &self.mint_account.pubkey(),      // new mint account pubkey
-            mint_account_rent,                // minimum rent-exempt balance
-            mint_account_len as u64,          // size of the mint account
-            &TOKEN_PROGRAM_ID,                // SPL token program
+            &rent_payer_pubkey,      // payer: pays rent for the new account
+            &mint_pubkey,            // new mint account pubkey
+            mint_account_rent,       // minimum rent-exempt balance
+            mint_account_len as u64, // size of the mint account
+            token_program_id,        // SPL token or Token-2022 program
         );
 
         // Instruction to initialize the mint
         let initialize_mint_ix = initialize_mint2(
-            &TOKEN_PROGRAM_ID,                      // SPL token program
-            &self.mint_account.pubkey(),            // mint account to initialize
-            &self.authority_keypair.pubkey(),       // mint authority
-            Some(&self.authority_keypair.pubkey()), // freeze authority (optional)
-            9,                                      // decimals
+            token_program_id,        // SPL token or Token-2022 program
+            &mint_pubkey,            // mint account to initialize
+            &authority_pubkey,       // mint authority
+            Some(&authority_pubkey), // freeze authority (optional)
+            9,                       // decimals
         )?;
 
-        // Create a transaction with the above instructions
-        let mut transaction = Transaction::new_with_payer(
-            &[create_mint_account_ix, initialize_mint_ix],
-            Some(&self.authority_keypair.pubkey()),
-        );
+        let mut instructions = vec![create_mint_account_ix];
+        instructions.extend(extension_instructions);
+        instructions.push(initialize_mint_ix);
+
+        // Instruction to write the token metadata into the mint (Token-2022)
+        if let Some(token_metadata) = token_metadata {
+            instructions.push(spl_token_metadata_interface::instruction::initialize(
+                token_program_id,      // Token-2022 program
+                &mint_pubkey,          // metadata account (the mint itself)
+                &authority_pubkey,     // update authority
+                &mint_pubkey,          // mint
+                &authority_pubkey,     // mint authority
+                token_metadata.name,   // token name
+                token_metadata.symbol, // token symbol
+                token_metadata.uri,    // metadata URI
+            ));
+        }
 
-        // Sign transaction with authority and mint keypairs
-        transaction.sign(
-            &[&self.authority_keypair, &self.mint_account],
-            self.rpc_client.get_latest_blockhash().await?,
-        );
+        // Create a transaction with the above instructions, paid by the fee payer
+        // and signed by the rent payer, authority and mint keypairs
+        let transaction = self
+            .create_transaction(&instructions, &[&self.mint_account])
+            .await?;
 
         // Send and confirm transaction
         let transaction_signature = self
@@ -163,21 +267,14 @@ impl SolanaApi {
     pub async fn create_token_account(&self) -> Result<Signature> {
         // Instruction to create an associated token account if it doesn't exist
         let create_ata_ix = create_associated_token_account_idempotent(
-            &self.authority_keypair.pubkey(), // payer
-            &self.authority_keypair.pubkey(), // wallet to hold tokens
-            &self.mint_account.pubkey(),      // mint of the token
-            &TOKEN_PROGRAM_ID,                // SPL token program
+            &self.rent_payer_keypair.pubkey(), // payer
+            &self.authority_keypair.pubkey(),  // wallet to hold tokens
+            &self.mint_account.pubkey(),       // mint of the token
+            &self.token_program_id,            // SPL token or Token-2022 program
         );
 
         // Build transaction with the instruction
-        let mut transaction =
-            Transaction::new_with_payer(&[create_ata_ix], Some(&self.authority_keypair.pubkey()));
-
-        // Sign transaction with authority keypair
-        transaction.sign(
-            &[&self.authority_keypair],
-            self.rpc_client.get_latest_blockhash().await?,
-        );
+        let transaction = self.create_transaction(&[create_ata_ix], &[]).await?;
 
         // Send transaction and wait for confirmation
         let transaction_signature = self
@@ -188,53 +285,52 @@ impl SolanaApi {
         Ok(transaction_signature)
     }
 
-    /// Mints `amount` tokens to the authority's associated token account.
-    /// The amount is adjusted according to the mint's decimals.
+    /// Reads the decimals of the reward token from its mint account.
+    pub async fn get_mint_decimals(&self) -> Result<u8> {
+        let mint_account = self
+            .rpc_client
+            .get_account(&self.mint_account.pubkey())
+            .await?;
+
+        // Token-2022 mints carry extensions after the base mint state
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data)?;
+
+        Ok(mint.base.decimals)
+    }
+
+    /// Mints `amount` base units of the token to the authority's associated token account.
     ///
     /// [How to Mint Tokens](https://solana.com/developers/cookbook/tokens/mint-tokens)
     ///
     /// # Arguments
-    /// * `amount` - number of tokens to mint (whole units)
+    /// * `amount` - amount to mint in base units (see `token_util::parse_token_amount`)
     ///
     /// # Returns
     /// * `Signature` of the mint transaction
     pub async fn mint_tokens(&self, amount: u64) -> Result<Signature> {
         // Compute the associated token account for the authority
-        let associated_token_account = get_associated_token_address(
+        let associated_token_account = get_associated_token_address_with_program_id(
             &self.authority_keypair.pubkey(),
             &self.mint_account.pubkey(),
+            &self.token_program_id,
         );
 
-        // Fetch the number of decimals for this token from the account
-        let mint_decimals = self
-            .rpc_client
-            .get_token_account_balance(&associated_token_account)
-            .await?
-            .decimals;
-
-        // Convert the requested amount to the smallest unit based on decimals
-        let amount_to_mint = amount * 10_u64.pow(mint_decimals as u32);
+        // Fetch the number of decimals for this token from the mint
+        let mint_decimals = self.get_mint_decimals().await?;
 
         // Instruction to mint tokens to the associated token account
         let mint_to_ix = mint_to_checked(
-            &TOKEN_PROGRAM_ID,                   // SPL Token program
+            &self.token_program_id,              // SPL token or Token-2022 program
             &self.mint_account.pubkey(),         // mint account
             &associated_token_account,           // recipient token account
             &self.authority_keypair.pubkey(),    // mint authority
             &[&self.authority_keypair.pubkey()], // signers
-            amount_to_mint,                      // amount in smallest units
+            amount,                              // amount in smallest units
             mint_decimals,                       // decimals
         )?;
 
         // Build transaction with the mint instruction
-        let mut transaction =
-            Transaction::new_with_payer(&[mint_to_ix], Some(&self.authority_keypair.pubkey()));
-
-        // Sign transaction with authority keypair
-        transaction.sign(
-            &[&self.authority_keypair],
-            self.rpc_client.get_latest_blockhash().await?,
-        );
+        let transaction = self.create_transaction(&[mint_to_ix], &[]).await?;
 
         // Send transaction and wait for confirmation
         let transaction_signature = self
@@ -251,28 +347,151 @@ impl SolanaApi {
     ///
     /// # Arguments
     /// * `recipient_pubkey` - the public key of the recipient wallet
-    /// * `amount` - the number of tokens to transfer (in human-readable units, e.g., 1 = 1 token)
+    /// * `amount` - the amount to transfer in base units (see `token_util::parse_token_amount`)
     ///
     /// # Returns
     /// * `Signature` of the transfer transaction
     pub async fn send_tokens(&self, recipient_pubkey: &Pubkey, amount: u64) -> Result<Signature> {
-        let sender = &self.authority_keypair; // authority and fee payer
+        // Fetch the decimals of the mint (e.g., 9 for most SPL tokens)
+        // Checked by the transfer instruction against the mint
+        let decimals = self.get_mint_decimals().await?;
+
+        let instructions = self
+            .transfer_instructions(recipient_pubkey, amount, decimals)
+            .await?;
+        let lookup_tables = LookupTableApi::new().load_active().await?;
+
+        self.send_v0_transaction(&instructions, &lookup_tables)
+            .await
+    }
+
+    /// Transfers SPL tokens from the authority to several recipients, packing as many
+    /// `transfer_checked` instructions per transaction as the size and compute limits allow.
+    /// The transactions are v0, with the accounts in the active lookup tables resolved through them.
+    ///
+    /// With a payer pool, the transactions are sent in parallel, one per payer.
+    /// When a transaction fails, each of its transfers is retried in its own transaction,
+    /// so one bad recipient does not fail the others. A transaction is only retried once it
+    /// definitely did not land (it failed, or its blockhash expired), otherwise its transfers fail.
+    ///
+    /// # Arguments
+    /// * `transfers` - the recipient wallets and amounts in base units
+    /// * `max_transfers` - maximum number of transfers per transaction
+    ///
+    /// # Returns
+    /// * The `Signature` of the transaction of every transfer, or its error, in order
+    pub async fn send_tokens_batch(
+        &self,
+        transfers: &[(Pubkey, u64)],
+        max_transfers: usize,
+    ) -> Result<Vec<Result<Signature>>> {
+        let decimals = self.get_mint_decimals().await?;
+        let lookup_tables = LookupTableApi::new().load_active().await?;
+
+        let mut payouts = vec![];
+        for (recipient_pubkey, amount) in transfers {
+            let instructions = self
+                .transfer_instructions(recipient_pubkey, *amount, decimals)
+                .await?;
+            payouts.push((*recipient_pubkey, instructions));
+        }
+
+        let mut results: Vec<Option<Result<Signature>>> = transfers.iter().map(|_| None).collect();
+
+        // Every payer of the pool takes as much space in a transaction as the fee payer
+        let payer_pubkey = self
+            .payer_pool
+            .payers()
+            .first()
+            .unwrap_or(&self.fee_payer_keypair)
+            .pubkey();
+        let batches = solana_util::pack_payouts(
+            &payouts,
+            &payer_pubkey,
+            &lookup_tables,
+            TRANSFER_COMPUTE_UNITS,
+            max_transfers,
+        );
+
+        // One transaction per payer of the pool is in flight at once
+        let lookup_tables = &lookup_tables;
+        for wave in batches.chunks(self.payer_pool.payers().len().max(1)) {
+            let sent = join_all(wave.iter().map(|indexes| {
+                let instructions: Vec<Instruction> = indexes
+                    .iter()
+                    .flat_map(|index| payouts[*index].1.iter().cloned())
+                    .collect();
+
+                async move {
+                    self.try_send_v0_transaction(&instructions, lookup_tables)
+                        .await
+                }
+            }))
+            .await;
+
+            for (indexes, result) in wave.iter().zip(sent) {
+                match result {
+                    Ok(Ok(signature)) => {
+                        for index in indexes {
+                            results[*index] = Some(Ok(signature));
+                        }
+                    }
+                    // Resending could pay the transfers twice
+                    Err(e) => {
+                        warn!(
+                            "Batch of {} transfers not resent, its status is unknown: {:?}",
+                            indexes.len(),
+                            e
+                        );
+
+                        for index in indexes {
+                            results[*index] = Some(Err(anyhow!("{:?}", e)));
+                        }
+                    }
+                    Ok(Err(e)) if indexes.len() == 1 => results[indexes[0]] = Some(Err(e)),
+                    Ok(Err(e)) => {
+                        warn!(
+                            "Batch of {} transfers failed, sending them one by one: {:?}",
+                            indexes.len(),
+                            e
+                        );
+
+                        for index in indexes {
+                            results[*index] = Some(
+                                self.send_v0_transaction(&payouts[*index].1, lookup_tables)
+                                    .await,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Builds the instructions transferring `amount` base units from the authority's
+    /// token account to the recipient's, creating the recipient's token account if needed.
+    async fn transfer_instructions(
+        &self,
+        recipient_pubkey: &Pubkey,
+        amount: u64,
+        decimals: u8,
+    ) -> Result<Vec<Instruction>> {
+        let sender = &self.authority_keypair; // treasury authority
         let mint_account = &self.mint_account; // token mint
 
         // Derive the associated token accounts (ATA) for sender and recipient
-        let sender_token_account =
-            get_associated_token_address(&sender.pubkey(), &mint_account.pubkey());
-        let recipient_token_account =
-            get_associated_token_address(&recipient_pubkey, &mint_account.pubkey());
-
-        // Fetch the decimals of the mint (e.g., 9 for most SPL tokens)
-        // Needed to convert human-readable `amount` into base units (lamports of the token)
-        let decimals = self
-            .rpc_client
-            .get_token_account_balance(&sender_token_account)
-            .await?
-            .decimals;
-        let transfer_amount = amount * 10_u64.pow(decimals as u32);
+        let sender_token_account = get_associated_token_address_with_program_id(
+            &sender.pubkey(),
+            &mint_account.pubkey(),
+            &self.token_program_id,
+        );
+        let recipient_token_account = get_associated_token_address_with_program_id(
+            recipient_pubkey,
+            &mint_account.pubkey(),
+            &self.token_program_id,
+        );
 
         let mut instructions = vec![];
 
@@ -284,32 +503,192 @@ impl SolanaApi {
             .is_err()
         {
             let create_recipient_ata_ix = create_associated_token_account_idempotent(
-                &sender.pubkey(),       // payer
-                &recipient_pubkey,      // wallet to hold tokens
-                &mint_account.pubkey(), // mint
-                &TOKEN_PROGRAM_ID,      // SPL token program
+                &self.rent_payer_keypair.pubkey(), // payer
+                recipient_pubkey,                  // wallet to hold tokens
+                &mint_account.pubkey(),            // mint
+                &self.token_program_id,            // SPL token or Token-2022 program
             );
             instructions.push(create_recipient_ata_ix);
         }
 
         // Build transfer instruction with decimal check for safety
         let transfer_ix = transfer_checked(
-            &TOKEN_PROGRAM_ID,        // SPL token program
+            &self.token_program_id,   // SPL token or Token-2022 program
             &sender_token_account,    // source ATA
             &mint_account.pubkey(),   // token mint
             &recipient_token_account, // destination ATA
             &sender.pubkey(),         // authority of sender
             &[&sender.pubkey()],      // signer seeds
-            transfer_amount,          // amount in base units
+            amount,                   // amount in base units
             decimals,                 // decimals to check
         )?;
         instructions.push(transfer_ix);
 
-        // Build the transaction with the transfer instruction
-        let mut transaction = Transaction::new_with_payer(&instructions, Some(&sender.pubkey()));
+        Ok(instructions)
+    }
+
+    /// Builds a legacy transaction paid by the fee payer and signed by the keypairs
+    /// it requires among the fee payer, rent payer, authority and `signers`.
+    async fn create_transaction(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Transaction> {
+        let mut all_signers = vec![
+            &self.fee_payer_keypair,
+            &self.rent_payer_keypair,
+            &self.authority_keypair,
+        ];
+        all_signers.extend(signers);
+
+        solana_util::create_transaction(
+            instructions,
+            &self.fee_payer_keypair.pubkey(),
+            &all_signers,
+            self.rpc_client.get_latest_blockhash().await?,
+        )
+    }
+
+    /// Sends a v0 transaction paid by the next payer of the pool (or the fee payer without one)
+    /// and signed by the keypairs it requires among the payer, rent payer and authority,
+    /// and waits for confirmation.
+    async fn send_v0_transaction(
+        &self,
+        instructions: &[Instruction],
+        lookup_tables: &[AddressLookupTableAccount],
+    ) -> Result<Signature> {
+        self.try_send_v0_transaction(instructions, lookup_tables)
+            .await?
+    }
+
+    /// Sends a v0 transaction like `send_v0_transaction`. If the confirmation fails, waits
+    /// until the transaction landed or can no longer land.
+    ///
+    /// # Returns
+    /// * The settled result: the signature, or the error of a transaction that did not land
+    /// * An error if the transaction could not be sent or settled, it must not be resent then
+    async fn try_send_v0_transaction(
+        &self,
+        instructions: &[Instruction],
+        lookup_tables: &[AddressLookupTableAccount],
+    ) -> Result<Result<Signature>> {
+        let payer = self
+            .payer_pool
+            .acquire()
+            .await
+            .unwrap_or(&self.fee_payer_keypair);
+
+        // Build the transaction with the instructions, paid by the payer
+        let recent_blockhash = self.rpc_client.get_latest_blockhash().await?;
+        let transaction = solana_util::create_v0_transaction(
+            instructions,
+            &payer.pubkey(),
+            &[payer, &self.rent_payer_keypair, &self.authority_keypair],
+            lookup_tables,
+            recent_blockhash,
+        )?;
+
+        // Send and confirm transaction, then track the balance of the payer
+        let result = match self
+            .rpc_client
+            .send_and_confirm_transaction(&transaction)
+            .await
+        {
+            Ok(transaction_signature) => Ok(transaction_signature),
+            Err(e) => {
+                let signature = transaction.signatures[0];
+                let landed = solana_util::settle_transaction(
+                    &self.rpc_client,
+                    &signature,
+                    &recent_blockhash,
+                )
+                .await;
+
+                match landed {
+                    Ok(true) => {
+                        warn!("Transaction {} landed despite: {:?}", signature, e);
+                        Ok(signature)
+                    }
+                    Ok(false) => Err(e.into()),
+                    Err(settle_error) => {
+                        self.payer_pool.invalidate(&payer.pubkey());
+                        bail!(
+                            "Transaction {} may have landed ({:?}), its status is unknown: {:?}",
+                            signature,
+                            e,
+                            settle_error
+                        );
+                    }
+                }
+            }
+        };
+
+        match &result {
+            Ok(_) => self
+                .payer_pool
+                .charge(&payer.pubkey(), transaction.signatures.len()),
+            Err(_) => self.payer_pool.invalidate(&payer.pubkey()),
+        }
 
-        // Sign with the sender's authority (who also pays for fees)
-        transaction.sign(&[&sender], self.rpc_client.get_latest_blockhash().await?);
+        Ok(result)
+    }
+
+    /// Freezes the user's token account of the reward token, so it can neither
+    /// send nor receive tokens (the authority is the freeze authority of the mint).
+    ///
+    /// # Arguments
+    /// * `owner_pubkey` - the wallet owning the token account
+    ///
+    /// # Returns
+    /// * `Signature` of the freeze transaction
+    pub async fn freeze_token_account(&self, owner_pubkey: &Pubkey) -> Result<Signature> {
+        self.set_token_account_frozen(owner_pubkey, true).await
+    }
+
+    /// Thaws the user's token account of the reward token frozen by `freeze_token_account`.
+    ///
+    /// # Arguments
+    /// * `owner_pubkey` - the wallet owning the token account
+    ///
+    /// # Returns
+    /// * `Signature` of the thaw transaction
+    pub async fn thaw_token_account(&self, owner_pubkey: &Pubkey) -> Result<Signature> {
+        self.set_token_account_frozen(owner_pubkey, false).await
+    }
+
+    /// Freezes or thaws the associated token account of a wallet.
+    async fn set_token_account_frozen(
+        &self,
+        owner_pubkey: &Pubkey,
+        frozen: bool,
+    ) -> Result<Signature> {
+        let authority = &self.authority_keypair; // freeze authority
+
+        let token_account = get_associated_token_address_with_program_id(
+            owner_pubkey,
+            &self.mint_account.pubkey(),
+            &self.token_program_id,
+        );
+
+        let instruction = if frozen {
+            freeze_account(
+                &self.token_program_id,      // SPL token or Token-2022 program
+                &token_account,              // account to freeze
+                &self.mint_account.pubkey(), // token mint
+                &authority.pubkey(),         // freeze authority
+                &[&authority.pubkey()],      // signers
+            )?
+        } else {
+            thaw_account(
+                &self.token_program_id,      // SPL token or Token-2022 program
+                &token_account,              // account to thaw
+                &self.mint_account.pubkey(), // token mint
+                &authority.pubkey(),         // freeze authority
+                &[&authority.pubkey()],      // signers
+            )?
+        };
+
+        let transaction = self.create_transaction(&[instruction], &[]).await?;
 
         // Send and confirm transaction
         let transaction_signature = self
@@ -323,11 +702,16 @@ impl SolanaApi {
     /// Creates Metaplex metadata for the NFT / token.
     ///
     /// This includes name, symbol, URI (image/metadata), seller fee, and token standard.
+    /// Not used for Token-2022 mints, which keep their metadata in the mint.
     ///
     /// # Returns
     /// * `Signature` of the transaction creating the metadata
     pub async fn create_metadata_accounts(&self) -> Result<Signature> {
-        let payer_pubkey = self.authority_keypair.pubkey();
+        if self.token_program_id == spl_token_2022::ID {
+            bail!("Token-2022 mints keep their metadata in the mint, it is set by create-mint");
+        }
+
+        let authority_pubkey = self.authority_keypair.pubkey();
         let mint_pubkey = self.mint_account.pubkey();
 
         // Derive the PDA for the metadata account
@@ -337,9 +721,9 @@ impl SolanaApi {
         let create_ix = CreateV1Builder::new()
             .metadata(metadata_pubkey) // Metadata account PDA
             .mint(mint_pubkey, true) // Mint account + signer
-            .authority(payer_pubkey) // Mint authority
-            .payer(payer_pubkey) // Payer of transaction fees
-            .update_authority(payer_pubkey, true) // Update authority with signer
+            .authority(authority_pubkey) // Mint authority
+            .payer(self.rent_payer_keypair.pubkey()) // Payer of the metadata account rent
+            .update_authority(authority_pubkey, true) // Update authority with signer
             .is_mutable(true) // Can metadata be updated later
             .primary_sale_happened(false) // Has primary sale occurred
             .name(solana_util::get_token_name()) // Token/NFT name
@@ -349,13 +733,10 @@ impl SolanaApi {
             .token_standard(TokenStandard::Fungible) // Fungible token
             .instruction();
 
-        // Build transaction with both authority and mint as signers
-        let mut transaction = Transaction::new_with_payer(&[create_ix], Some(&payer_pubkey));
-
-        transaction.sign(
-            &[&self.authority_keypair, &self.mint_account], // Mint + authority sign
-            self.rpc_client.get_latest_blockhash().await?,
-        );
+        // Build transaction with the payers, authority and mint as signers
+        let transaction = self
+            .create_transaction(&[create_ix], &[&self.mint_account])
+            .await?;
 
         // Send transaction and wait for confirmation
         let transaction_signature = self
@@ -366,3 +747,9 @@ impl SolanaApi {
         Ok(transaction_signature)
     }
 }
+
+impl Default for SolanaApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
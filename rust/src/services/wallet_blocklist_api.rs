@@ -0,0 +1,125 @@
+use crate::models::wallet::{AuditAction, BlockedWallet, WALLET_BLOCKLIST_STORE};
+use crate::services::audit_api::AuditApi;
+use crate::storage::json_store::JsonStore;
+use crate::utils::reward_util;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use solana_program::pubkey::Pubkey;
+use std::collections::HashMap;
+use tokio::fs;
+
+pub struct WalletBlocklistApi {
+    store: JsonStore<HashMap<String, BlockedWallet>>,
+    blocklist_path: Option<String>,
+    audit_api: AuditApi,
+}
+
+impl WalletBlocklistApi {
+    /// Creates new API wrapper with the blocklist file from environment
+    pub fn new() -> Self {
+        Self {
+            store: JsonStore::new(WALLET_BLOCKLIST_STORE),
+            blocklist_path: reward_util::get_reward_blocklist_path(),
+            audit_api: AuditApi::new(),
+        }
+    }
+
+    /// Returns why a wallet is blocked from receiving rewards, or `None` if it is not.
+    ///
+    /// A wallet is blocked if it was blocked at runtime (control topic or CLI),
+    /// or is listed in the `REWARD_BLOCKLIST_PATH` file. The file is read on every check,
+    /// so edits take effect without a restart.
+    pub async fn blocked_reason(&self, wallet: &str) -> Result<Option<String>> {
+        if let Some(blocked) = self.store.load().await?.remove(wallet) {
+            return Ok(Some(blocked.reason));
+        }
+
+        let Some(path) = &self.blocklist_path else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read the wallet blocklist at {}", path))?;
+
+        // One wallet per line, `#` starts a comment
+        let listed = content
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default().trim())
+            .any(|listed| listed == wallet);
+
+        Ok(listed.then(|| format!("Listed in {}", path)))
+    }
+
+    /// Blocks a wallet from receiving rewards and records it in the audit log.
+    ///
+    /// # Arguments
+    /// * `wallet` - The wallet to block.
+    /// * `reason` - Why the wallet is blocked.
+    /// * `actor` - Who blocks the wallet.
+    pub async fn block(&self, wallet: &str, reason: &str, actor: &str) -> Result<()> {
+        let wallet = wallet.parse::<Pubkey>()?.to_string();
+
+        {
+            let _lock = self.store.lock().await?;
+            let mut blocklist = self.store.load().await?;
+            blocklist.insert(
+                wallet.clone(),
+                BlockedWallet {
+                    reason: reason.to_string(),
+                    actor: actor.to_string(),
+                    blocked_at: Utc::now(),
+                },
+            );
+            self.store.save(&blocklist).await?;
+        }
+
+        self.audit_api
+            .record(
+                AuditAction::BlockWallet,
+                &wallet,
+                actor,
+                Some(reason.to_string()),
+                None,
+            )
+            .await
+    }
+
+    /// Unblocks a wallet blocked at runtime and records it in the audit log.
+    ///
+    /// Wallets listed in the blocklist file stay blocked until they are removed from it.
+    /// Returns whether the wallet was blocked.
+    ///
+    /// # Arguments
+    /// * `wallet` - The wallet to unblock.
+    /// * `reason` - Why the wallet is unblocked, if known.
+    /// * `actor` - Who unblocks the wallet.
+    pub async fn unblock(&self, wallet: &str, reason: Option<String>, actor: &str) -> Result<bool> {
+        let wallet = wallet.parse::<Pubkey>()?.to_string();
+
+        {
+            let _lock = self.store.lock().await?;
+            let mut blocklist = self.store.load().await?;
+            if blocklist.remove(&wallet).is_none() {
+                return Ok(false);
+            }
+            self.store.save(&blocklist).await?;
+        }
+
+        self.audit_api
+            .record(AuditAction::UnblockWallet, &wallet, actor, reason, None)
+            .await?;
+
+        Ok(true)
+    }
+
+    /// Returns the wallets blocked at runtime.
+    pub async fn list(&self) -> Result<HashMap<String, BlockedWallet>> {
+        self.store.load().await
+    }
+}
+
+impl Default for WalletBlocklistApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
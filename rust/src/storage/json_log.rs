@@ -0,0 +1,50 @@
+use crate::storage;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+
+/// A file-backed, append-only log of serializable values, one JSON document per line.
+///
+/// Used for records that must never be rewritten (e.g. the audit log).
+pub struct JsonLog<T> {
+    path: PathBuf,
+    _marker: PhantomData<T>,
+}
+
+impl<T> JsonLog<T>
+where
+    T: Serialize,
+{
+    /// Creates a log for `<STORAGE_DIR>/<name>.jsonl`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            path: storage::get_storage_dir().join(format!("{}.jsonl", name)),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Appends a value as a new line of the log.
+    pub async fn append(&self, value: &T) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let mut line = serde_json::to_vec(value)?;
+        line.push(b'\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+        file.write_all(&line)
+            .await
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use crate::storage;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use tokio::fs;
+
+/// A small file-backed store that keeps a single serializable value as JSON.
+///
+/// Used for worker state that must survive restarts (checkpoints, caches, queues).
+/// Writes go to a temporary file first and are then renamed over the target,
+/// so a crash never leaves a half-written state file behind.
+/// State changed by several processes (e.g. the worker and the CLI) is updated under `lock`.
+pub struct JsonStore<T> {
+    path: PathBuf,
+    _marker: PhantomData<T>,
+}
+
+impl<T> JsonStore<T>
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    /// Creates a store for `<STORAGE_DIR>/<name>.json`.
+    pub fn new(name: &str) -> Self {
+        Self::with_path(storage::get_storage_dir().join(format!("{}.json", name)))
+    }
+
+    /// Creates a store for the given file path.
+    pub fn with_path(path: PathBuf) -> Self {
+        Self {
+            path,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Loads the stored value, or returns `T::default()` if nothing was stored yet.
+    pub async fn load(&self) -> Result<T> {
+        match fs::read(&self.path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .with_context(|| format!("Failed to parse {}", self.path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", self.path.display())),
+        }
+    }
+
+    /// Takes an exclusive lock on the store (`<name>.json.lock`), held until the returned
+    /// guard is dropped, so a load → modify → save sequence is not interleaved with another
+    /// one of this or any other process.
+    pub async fn lock(&self) -> Result<JsonStoreLock> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let lock_path = self.path.with_extension("json.lock");
+        let file = tokio::task::spawn_blocking(move || -> Result<std::fs::File> {
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&lock_path)
+                .with_context(|| format!("Failed to open {}", lock_path.display()))?;
+            file.lock()
+                .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+
+            Ok(file)
+        })
+        .await??;
+
+        Ok(JsonStoreLock { _file: file })
+    }
+
+    /// Atomically replaces the stored value.
+    pub async fn save(&self, value: &T) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_vec_pretty(value)?).await?;
+        fs::rename(&tmp_path, &self.path)
+            .await
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+
+        Ok(())
+    }
+}
+
+/// Exclusive lock on a `JsonStore`, released when dropped.
+pub struct JsonStoreLock {
+    _file: std::fs::File,
+}
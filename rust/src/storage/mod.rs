@@ -0,0 +1,17 @@
+pub mod json_log;
+pub mod json_store;
+
+use crate::utils::config_util;
+use std::path::PathBuf;
+
+/// Default directory for the worker's local state files.
+const DEFAULT_STORAGE_DIR: &str = "./storage";
+
+/// Returns the directory for the worker's local state files
+/// from `STORAGE_DIR` env variable (defaults to `./storage`).
+pub fn get_storage_dir() -> PathBuf {
+    PathBuf::from(
+        config_util::get_optional_env("STORAGE_DIR")
+            .unwrap_or_else(|| DEFAULT_STORAGE_DIR.to_string()),
+    )
+}
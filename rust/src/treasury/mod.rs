@@ -0,0 +1,10 @@
+use crate::treasury::treasury_monitor::TreasuryMonitor;
+use anyhow::Result;
+
+pub mod treasury_monitor;
+
+/// Starts the treasury monitor and propagates any errors.
+/// This function typically runs for the lifetime of the application.
+pub async fn run() -> Result<()> {
+    TreasuryMonitor::new().run().await
+}
@@ -0,0 +1,261 @@
+use crate::metrics;
+use crate::models::wallet::AuditAction;
+use crate::services::audit_api::AuditApi;
+use crate::services::solana_api::SolanaApi;
+use crate::utils::{solana_util, token_util};
+use anyhow::{Context, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use std::collections::HashSet;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// Actor of the automatic mints in the audit log.
+const AUDIT_ACTOR: &str = "treasury-monitor";
+
+/// Label of the treasury token balance in `solana_quiz_treasury_low`.
+const TOKEN_BALANCE: &str = "token";
+
+/// Watches the SOL balance of the wallets paying for transactions and the reward token
+/// balance of the treasury (the authority's token account), exports them as metrics
+/// and warns when they drop below their thresholds.
+///
+/// With `SOLANA_TREASURY_MINT_WATERMARK`, the treasury is topped up by minting from the
+/// mint authority whenever it drops below the watermark, without ever exceeding the
+/// supply ceiling.
+pub struct TreasuryMonitor {
+    rpc_client: RpcClient,
+    solana_api: SolanaApi,
+    audit_api: AuditApi,
+    wallets: Vec<(&'static str, Pubkey)>,
+    mint_pubkey: Pubkey,
+    treasury_pubkey: Pubkey,
+    interval: Duration,
+    min_sol_balance: Option<u64>,
+}
+
+impl TreasuryMonitor {
+    /// Creates a new monitor using environment variables.
+    pub fn new() -> Self {
+        let authority_pubkey = solana_util::get_authority_keypair().pubkey();
+        let mint_pubkey = solana_util::get_mint_account().pubkey();
+
+        // The fee payer and rent payer are only watched when they are separate wallets
+        let mut wallets = vec![("authority", authority_pubkey)];
+        for (wallet, pubkey) in [
+            ("fee_payer", solana_util::get_fee_payer_keypair().pubkey()),
+            ("rent_payer", solana_util::get_rent_payer_keypair().pubkey()),
+        ] {
+            if wallets.iter().all(|(_, other)| *other != pubkey) {
+                wallets.push((wallet, pubkey));
+            }
+        }
+
+        let min_sol_balance = solana_util::get_solana_treasury_min_sol_balance().map(|balance| {
+            token_util::parse_token_amount(&balance, token_util::SOL_DECIMALS)
+                .expect("SOLANA_TREASURY_MIN_SOL_BALANCE must be a valid SOL amount")
+        });
+
+        Self {
+            rpc_client: solana_util::create_rpc_client(),
+            solana_api: SolanaApi::new(),
+            audit_api: AuditApi::new(),
+            wallets,
+            mint_pubkey,
+            treasury_pubkey: get_associated_token_address_with_program_id(
+                &authority_pubkey,
+                &mint_pubkey,
+                &solana_util::get_token_program_id(),
+            ),
+            interval: solana_util::get_solana_treasury_monitor_interval(),
+            min_sol_balance,
+        }
+    }
+
+    /// Checks the balances until the task is aborted.
+    pub async fn run(&self) -> Result<()> {
+        info!("Starting treasury monitor for {}", self.treasury_pubkey);
+
+        // Balances currently below their threshold, to warn once when they drop
+        let mut low = HashSet::new();
+
+        loop {
+            if let Err(e) = self.check_sol_balances(&mut low).await {
+                error!("Treasury monitor error: {:?}", e);
+            }
+            if let Err(e) = self.check_treasury(&mut low).await {
+                error!("Treasury monitor error: {:?}", e);
+            }
+
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
+    /// Reads the SOL balances of the watched wallets.
+    async fn check_sol_balances(&self, low: &mut HashSet<&'static str>) -> Result<()> {
+        for (wallet, pubkey) in &self.wallets {
+            let lamports = self.rpc_client.get_balance(pubkey).await?;
+            metrics::WALLET_SOL_BALANCE
+                .with_label_values(&[wallet])
+                .set(lamports as i64);
+
+            if let Some(min_sol_balance) = self.min_sol_balance {
+                let amount = token_util::format_token_amount(lamports, token_util::SOL_DECIMALS);
+                let threshold =
+                    token_util::format_token_amount(min_sol_balance, token_util::SOL_DECIMALS);
+
+                set_low(
+                    low,
+                    wallet,
+                    lamports < min_sol_balance,
+                    &format!("{} wallet {} holds {} SOL", wallet, pubkey, amount),
+                    &threshold,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the token balance of the treasury and the token supply,
+    /// and mints to the treasury if it dropped below the watermark.
+    async fn check_treasury(&self, low: &mut HashSet<&'static str>) -> Result<()> {
+        let balance = self
+            .rpc_client
+            .get_token_account_balance(&self.treasury_pubkey)
+            .await?;
+        let decimals = balance.decimals;
+        let balance = balance.amount.parse::<u64>()?;
+        let supply = self
+            .rpc_client
+            .get_token_supply(&self.mint_pubkey)
+            .await?
+            .amount
+            .parse::<u64>()?;
+
+        metrics::TREASURY_TOKEN_BALANCE.set(to_tokens(balance, decimals));
+        metrics::TOKEN_SUPPLY.set(to_tokens(supply, decimals));
+
+        if let Some(min_token_balance) = solana_util::get_solana_treasury_min_token_balance() {
+            let min_token_balance = token_util::parse_token_amount(&min_token_balance, decimals)
+                .context("SOLANA_TREASURY_MIN_TOKEN_BALANCE must be a valid token amount")?;
+
+            set_low(
+                low,
+                TOKEN_BALANCE,
+                balance < min_token_balance,
+                &format!(
+                    "Treasury {} holds {} tokens",
+                    self.treasury_pubkey,
+                    token_util::format_token_amount(balance, decimals)
+                ),
+                &token_util::format_token_amount(min_token_balance, decimals),
+            );
+        }
+
+        if let Some(watermark) = solana_util::get_solana_treasury_mint_watermark() {
+            let watermark = token_util::parse_token_amount(&watermark, decimals)
+                .context("SOLANA_TREASURY_MINT_WATERMARK must be a valid token amount")?;
+
+            if balance < watermark {
+                self.top_up(balance, supply, watermark, decimals).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Mints the tokens missing to the mint target into the treasury,
+    /// limited by the room left under the supply ceiling.
+    async fn top_up(&self, balance: u64, supply: u64, watermark: u64, decimals: u8) -> Result<()> {
+        let target = solana_util::get_solana_treasury_mint_target().context(
+            "SOLANA_TREASURY_MINT_TARGET must be set with SOLANA_TREASURY_MINT_WATERMARK",
+        )?;
+        let target = token_util::parse_token_amount(&target, decimals)
+            .context("SOLANA_TREASURY_MINT_TARGET must be a valid token amount")?;
+        let ceiling = solana_util::get_solana_treasury_mint_supply_ceiling().context(
+            "SOLANA_TREASURY_MINT_SUPPLY_CEILING must be set with SOLANA_TREASURY_MINT_WATERMARK",
+        )?;
+        let ceiling = token_util::parse_token_amount(&ceiling, decimals)
+            .context("SOLANA_TREASURY_MINT_SUPPLY_CEILING must be a valid token amount")?;
+
+        let amount = target
+            .saturating_sub(balance)
+            .min(ceiling.saturating_sub(supply));
+        if amount == 0 {
+            warn!(
+                "Treasury {} holds {} tokens (watermark {}), but the supply of {} tokens reached the ceiling",
+                self.treasury_pubkey,
+                token_util::format_token_amount(balance, decimals),
+                token_util::format_token_amount(watermark, decimals),
+                token_util::format_token_amount(supply, decimals)
+            );
+            return Ok(());
+        }
+
+        let reason = format!(
+            "Treasury holds {} tokens, below the watermark of {}",
+            token_util::format_token_amount(balance, decimals),
+            token_util::format_token_amount(watermark, decimals)
+        );
+        info!(
+            "{}, minting {} tokens",
+            reason,
+            token_util::format_token_amount(amount, decimals)
+        );
+
+        let signature = self.solana_api.mint_tokens(amount).await?;
+        info!("Transaction Signature: {}", signature);
+        metrics::TREASURY_AUTO_MINTS.inc();
+
+        self.audit_api
+            .record(
+                AuditAction::MintTokens,
+                &self.treasury_pubkey.to_string(),
+                AUDIT_ACTOR,
+                Some(format!(
+                    "{}, minted {} tokens",
+                    reason,
+                    token_util::format_token_amount(amount, decimals)
+                )),
+                Some(signature.to_string()),
+            )
+            .await
+    }
+}
+
+impl Default for TreasuryMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Updates the `solana_quiz_treasury_low` gauge of a balance,
+/// warning when it drops below its threshold and logging when it recovers.
+fn set_low(
+    low: &mut HashSet<&'static str>,
+    balance: &'static str,
+    is_low: bool,
+    status: &str,
+    threshold: &str,
+) {
+    metrics::TREASURY_LOW
+        .with_label_values(&[balance])
+        .set(is_low as i64);
+
+    if is_low && low.insert(balance) {
+        warn!("{}, below the alert threshold of {}", status, threshold);
+    } else if !is_low && low.remove(balance) {
+        info!(
+            "{}, back above the alert threshold of {}",
+            status, threshold
+        );
+    }
+}
+
+/// Converts base units to tokens for the metrics.
+fn to_tokens(base_units: u64, decimals: u8) -> f64 {
+    base_units as f64 / 10_f64.powi(decimals as i32)
+}
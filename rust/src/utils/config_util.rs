@@ -15,3 +15,14 @@ pub fn get_required_env(var: &str) -> Result<String> {
         Err(_) => bail!("Environment variable {var} is missing"),
     }
 }
+
+/// Gets an optional environment variable, treating missing or empty values as `None`.
+///
+/// # Arguments
+/// * `var` - name of the environment variable
+///
+/// # Returns
+/// * `Some(String)` value of the environment variable, or `None`
+pub fn get_optional_env(var: &str) -> Option<String> {
+    env::var(var).ok().filter(|val| !val.trim().is_empty())
+}
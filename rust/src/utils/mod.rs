@@ -1,2 +1,5 @@
 pub mod config_util;
+pub mod reward_util;
+pub mod server_util;
 pub mod solana_util;
+pub mod token_util;
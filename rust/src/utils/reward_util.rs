@@ -0,0 +1,273 @@
+use crate::models::reward::{
+    BudgetExceededAction, RewardPolicyMode, SignatureKey, ValidationFailedAction,
+};
+use crate::models::sybil::SybilAction;
+use crate::utils::config_util;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Seconds in a reward day (reward days start at midnight UTC)
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Returns the current reward day (days since the Unix epoch, UTC).
+pub fn get_current_day() -> i64 {
+    Utc::now().timestamp().div_euclid(SECONDS_PER_DAY)
+}
+
+/// Reads the path of the reward policy (JSON) from `REWARD_POLICY_PATH` env variable.
+/// Without a policy, the amounts computed by Node are paid as is.
+pub fn get_reward_policy_path() -> Option<String> {
+    config_util::get_optional_env("REWARD_POLICY_PATH")
+}
+
+/// Reads the `REWARD_POLICY_MODE` environment variable
+/// (`verify` or `authoritative`, defaults to `verify`).
+pub fn get_reward_policy_mode() -> RewardPolicyMode {
+    match config_util::get_optional_env("REWARD_POLICY_MODE").as_deref() {
+        None | Some("verify") => RewardPolicyMode::Verify,
+        Some("authoritative") => RewardPolicyMode::Authoritative,
+        Some(value) => panic!(
+            "REWARD_POLICY_MODE must be verify or authoritative, not {}",
+            value
+        ),
+    }
+}
+
+/// Reads the maximum tokens a single user can receive per day (decimal)
+/// from `REWARD_BUDGET_USER_DAILY_TOKENS` env variable.
+pub fn get_reward_budget_user_daily_tokens() -> Option<String> {
+    config_util::get_optional_env("REWARD_BUDGET_USER_DAILY_TOKENS")
+}
+
+/// Reads the maximum tokens paid out per day to all users (decimal)
+/// from `REWARD_BUDGET_DAILY_TOKENS` env variable.
+pub fn get_reward_budget_daily_tokens() -> Option<String> {
+    config_util::get_optional_env("REWARD_BUDGET_DAILY_TOKENS")
+}
+
+/// Reads the maximum streak NFTs minted per day from `REWARD_BUDGET_DAILY_NFTS` env variable.
+pub fn get_reward_budget_daily_nfts() -> Option<u64> {
+    config_util::get_optional_env("REWARD_BUDGET_DAILY_NFTS").map(|value| {
+        value
+            .parse::<u64>()
+            .expect("REWARD_BUDGET_DAILY_NFTS must be a valid number")
+    })
+}
+
+/// Reads the `REWARD_BUDGET_EXCEEDED_ACTION` environment variable
+/// (`defer`, `reject` or `hold`, defaults to `reject`).
+pub fn get_reward_budget_exceeded_action() -> BudgetExceededAction {
+    match config_util::get_optional_env("REWARD_BUDGET_EXCEEDED_ACTION").as_deref() {
+        None | Some("reject") => BudgetExceededAction::Reject,
+        Some("defer") => BudgetExceededAction::Defer,
+        Some("hold") => BudgetExceededAction::Hold,
+        Some(value) => panic!(
+            "REWARD_BUDGET_EXCEEDED_ACTION must be defer, reject or hold, not {}",
+            value
+        ),
+    }
+}
+
+/// Reads the maximum reward for a single quiz (decimal) from
+/// `REWARD_VALIDATION_MAX_TOKENS` env variable (defaults to `1000`).
+pub fn get_reward_validation_max_tokens() -> String {
+    config_util::get_optional_env("REWARD_VALIDATION_MAX_TOKENS")
+        .unwrap_or_else(|| "1000".to_string())
+}
+
+/// Reads the maximum number of questions in a quiz from
+/// `REWARD_VALIDATION_MAX_QUESTIONS` env variable (defaults to `100`).
+pub fn get_reward_validation_max_questions() -> u64 {
+    config_util::get_optional_env("REWARD_VALIDATION_MAX_QUESTIONS")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .expect("REWARD_VALIDATION_MAX_QUESTIONS must be a valid number")
+        })
+        .unwrap_or(100)
+}
+
+/// Reads the `REWARD_VALIDATION_FAILED_ACTION` environment variable
+/// (`reject` or `hold`, defaults to `reject`).
+pub fn get_reward_validation_failed_action() -> ValidationFailedAction {
+    match config_util::get_optional_env("REWARD_VALIDATION_FAILED_ACTION").as_deref() {
+        None | Some("reject") => ValidationFailedAction::Reject,
+        Some("hold") => ValidationFailedAction::Hold,
+        Some(value) => panic!(
+            "REWARD_VALIDATION_FAILED_ACTION must be reject or hold, not {}",
+            value
+        ),
+    }
+}
+
+/// Reads the keys of reward message signatures from `REWARD_SIGNATURE_KEYS` env variable.
+///
+/// The keys are a comma-separated list of `<key id>:<algorithm>:<base64 key>`, where the
+/// algorithm is `hmac-sha256` (with the shared secret) or `ed25519` (with the 32-byte
+/// public key). Listing the old and the new key at once allows rotating keys.
+pub fn get_reward_signature_keys() -> HashMap<String, SignatureKey> {
+    let Some(value) = config_util::get_optional_env("REWARD_SIGNATURE_KEYS") else {
+        return HashMap::new();
+    };
+
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let [key_id, algorithm, key] = entry.splitn(3, ':').collect::<Vec<_>>()[..] else {
+                panic!("REWARD_SIGNATURE_KEYS entries must be <key id>:<algorithm>:<base64 key>");
+            };
+            let key = STANDARD.decode(key).unwrap_or_else(|_| {
+                panic!("REWARD_SIGNATURE_KEYS key {} is not valid base64", key_id)
+            });
+
+            let key = match algorithm {
+                "hmac-sha256" => SignatureKey::HmacSha256(key),
+                "ed25519" => SignatureKey::Ed25519(key.try_into().unwrap_or_else(|_| {
+                    panic!(
+                        "REWARD_SIGNATURE_KEYS key {} is not a 32-byte public key",
+                        key_id
+                    )
+                })),
+                _ => panic!(
+                    "REWARD_SIGNATURE_KEYS algorithm must be hmac-sha256 or ed25519, not {}",
+                    algorithm
+                ),
+            };
+
+            (key_id.to_string(), key)
+        })
+        .collect()
+}
+
+/// Reads the `REWARD_SIGNATURE_STRICT` environment variable and returns whether
+/// unsigned reward messages are rejected.
+pub fn get_reward_signature_strict() -> bool {
+    config_util::get_optional_env("REWARD_SIGNATURE_STRICT")
+        .map(|value| value == "true" || value == "1")
+        .unwrap_or(false)
+}
+
+/// Reads how long a signed reward message is accepted after it was signed, from
+/// `REWARD_SIGNATURE_MAX_AGE_SECONDS` env variable (defaults to 5 minutes).
+pub fn get_reward_signature_max_age() -> u64 {
+    config_util::get_optional_env("REWARD_SIGNATURE_MAX_AGE_SECONDS")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .expect("REWARD_SIGNATURE_MAX_AGE_SECONDS must be a valid number")
+        })
+        .unwrap_or(300)
+}
+
+/// Reads the path of the wallet blocklist (one wallet per line, `#` starts a comment)
+/// from `REWARD_BLOCKLIST_PATH` env variable.
+pub fn get_reward_blocklist_path() -> Option<String> {
+    config_util::get_optional_env("REWARD_BLOCKLIST_PATH")
+}
+
+/// Reads the minimum age of a rewarded wallet, counted from its first transaction,
+/// from `REWARD_SYBIL_MIN_WALLET_AGE_HOURS` env variable.
+pub fn get_reward_sybil_min_wallet_age_hours() -> Option<u64> {
+    config_util::get_optional_env("REWARD_SYBIL_MIN_WALLET_AGE_HOURS").map(|value| {
+        value
+            .parse::<u64>()
+            .expect("REWARD_SYBIL_MIN_WALLET_AGE_HOURS must be a valid number")
+    })
+}
+
+/// Reads the minimum SOL balance of a rewarded wallet (decimal)
+/// from `REWARD_SYBIL_MIN_SOL_BALANCE` env variable.
+pub fn get_reward_sybil_min_sol_balance() -> Option<String> {
+    config_util::get_optional_env("REWARD_SYBIL_MIN_SOL_BALANCE")
+}
+
+/// Reads the maximum number of users rewarded to one wallet within the window
+/// from `REWARD_SYBIL_MAX_USERS_PER_WALLET` env variable.
+pub fn get_reward_sybil_max_users_per_wallet() -> Option<usize> {
+    config_util::get_optional_env("REWARD_SYBIL_MAX_USERS_PER_WALLET").map(|value| {
+        value
+            .parse::<usize>()
+            .expect("REWARD_SYBIL_MAX_USERS_PER_WALLET must be a valid number")
+    })
+}
+
+/// Reads the maximum number of users rewarded to wallets funded by one source within
+/// the window from `REWARD_SYBIL_MAX_USERS_PER_FUNDER` env variable.
+pub fn get_reward_sybil_max_users_per_funder() -> Option<usize> {
+    config_util::get_optional_env("REWARD_SYBIL_MAX_USERS_PER_FUNDER").map(|value| {
+        value
+            .parse::<usize>()
+            .expect("REWARD_SYBIL_MAX_USERS_PER_FUNDER must be a valid number")
+    })
+}
+
+/// Reads the window the users per wallet and funder are counted in
+/// from `REWARD_SYBIL_WINDOW_HOURS` env variable (defaults to `720`, 30 days).
+pub fn get_reward_sybil_window_hours() -> u64 {
+    config_util::get_optional_env("REWARD_SYBIL_WINDOW_HOURS")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .expect("REWARD_SYBIL_WINDOW_HOURS must be a valid number")
+        })
+        .unwrap_or(720)
+}
+
+/// Reads how long the on-chain profile of a wallet is cached
+/// from `REWARD_SYBIL_CACHE_TTL_SECONDS` env variable (defaults to `3600`).
+pub fn get_reward_sybil_cache_ttl_seconds() -> i64 {
+    config_util::get_optional_env("REWARD_SYBIL_CACHE_TTL_SECONDS")
+        .map(|value| {
+            value
+                .parse::<i64>()
+                .expect("REWARD_SYBIL_CACHE_TTL_SECONDS must be a valid number")
+        })
+        .unwrap_or(3600)
+}
+
+/// Reads the action of an anti-sybil check from `REWARD_SYBIL_<CHECK>_ACTION` env variable
+/// (`reject`, `flag` or `hold`, defaults to `reject`).
+///
+/// # Arguments
+/// * `check` - Name of the check, e.g. `WALLET_AGE`.
+pub fn get_reward_sybil_action(check: &str) -> SybilAction {
+    let var = format!("REWARD_SYBIL_{}_ACTION", check);
+
+    match config_util::get_optional_env(&var).as_deref() {
+        None | Some("reject") => SybilAction::Reject,
+        Some("flag") => SybilAction::Flag,
+        Some("hold") => SybilAction::Hold,
+        Some(value) => panic!("{} must be reject, flag or hold, not {}", var, value),
+    }
+}
+
+/// Reads how long rewards are collected before they are paid out together
+/// from `REWARD_BATCH_WINDOW_MS` env variable. Batching is off if unset or `0`.
+pub fn get_reward_batch_window() -> Option<Duration> {
+    config_util::get_optional_env("REWARD_BATCH_WINDOW_MS")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .expect("REWARD_BATCH_WINDOW_MS must be a valid number")
+        })
+        .filter(|window_ms| *window_ms > 0)
+        .map(Duration::from_millis)
+}
+
+/// Reads the maximum number of rewards collected into a batch
+/// from `REWARD_BATCH_MAX_SIZE` env variable (defaults to `20`).
+pub fn get_reward_batch_max_size() -> usize {
+    config_util::get_optional_env("REWARD_BATCH_MAX_SIZE")
+        .map(|value| {
+            value
+                .parse::<usize>()
+                .expect("REWARD_BATCH_MAX_SIZE must be a valid number")
+        })
+        .unwrap_or(20)
+        .max(1)
+}
@@ -0,0 +1,56 @@
+use crate::utils::config_util;
+use std::path::PathBuf;
+
+/// Reads the `METADATA_SERVER_ENABLED` environment variable and returns whether
+/// the built-in metadata HTTP server should run alongside the Kafka consumer.
+pub fn get_metadata_server_enabled() -> bool {
+    config_util::get_optional_env("METADATA_SERVER_ENABLED")
+        .map(|value| value == "true" || value == "1")
+        .unwrap_or(false)
+}
+
+/// Reads the metadata server bind address from `METADATA_SERVER_ADDR` env variable
+/// (defaults to `0.0.0.0:8080`).
+pub fn get_metadata_server_addr() -> String {
+    config_util::get_optional_env("METADATA_SERVER_ADDR")
+        .unwrap_or_else(|| "0.0.0.0:8080".to_string())
+}
+
+/// Reads the public base URL of the metadata server (as seen by wallets)
+/// from `METADATA_SERVER_PUBLIC_URL` env variable, without a trailing slash.
+pub fn get_metadata_server_public_url() -> String {
+    config_util::get_required_env("METADATA_SERVER_PUBLIC_URL")
+        .expect("METADATA_SERVER_PUBLIC_URL is not set")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Reads the directory with image assets and static metadata JSON
+/// from `METADATA_SERVER_ASSETS_DIR` env variable (defaults to `./assets`).
+pub fn get_metadata_server_assets_dir() -> PathBuf {
+    PathBuf::from(
+        config_util::get_optional_env("METADATA_SERVER_ASSETS_DIR")
+            .unwrap_or_else(|| "./assets".to_string()),
+    )
+}
+
+/// Returns the self-hosted URI for the given server path,
+/// or `None` if the metadata server is disabled.
+pub fn get_self_hosted_uri(path: &str) -> Option<String> {
+    get_metadata_server_enabled().then(|| format!("{}{}", get_metadata_server_public_url(), path))
+}
+
+/// Reads the `METRICS_SERVER_ENABLED` environment variable and returns whether
+/// the Prometheus metrics endpoint should run alongside the Kafka consumer.
+pub fn get_metrics_server_enabled() -> bool {
+    config_util::get_optional_env("METRICS_SERVER_ENABLED")
+        .map(|value| value == "true" || value == "1")
+        .unwrap_or(false)
+}
+
+/// Reads the metrics server bind address from `METRICS_SERVER_ADDR` env variable
+/// (defaults to `0.0.0.0:9100`).
+pub fn get_metrics_server_addr() -> String {
+    config_util::get_optional_env("METRICS_SERVER_ADDR")
+        .unwrap_or_else(|| "0.0.0.0:9100".to_string())
+}
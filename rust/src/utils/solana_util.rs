@@ -1,11 +1,22 @@
-use crate::utils::config_util;
+use crate::storage;
+use crate::utils::{config_util, server_util};
+use anyhow::Result;
 use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::address_lookup_table_account::AddressLookupTableAccount;
 use solana_sdk::commitment_config::CommitmentConfig;
-use solana_sdk::signature::{Keypair, read_keypair_file};
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::{Message, VersionedMessage, v0};
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer, read_keypair_file};
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use std::path::PathBuf;
+use std::time::Duration;
 
 /// Reads a keypair from a file at the given path.
 pub fn read_keypair(path: &str) -> Keypair {
-    read_keypair_file(path).expect(&format!("Failed to read keypair at {}", path))
+    read_keypair_file(path).unwrap_or_else(|_| panic!("Failed to read keypair at {}", path))
 }
 
 /// Returns the authority keypair from the environment variable.
@@ -16,6 +27,65 @@ pub fn get_authority_keypair() -> Keypair {
     read_keypair(&authority_keypair_path)
 }
 
+/// Returns the keypair paying transaction fees from `SOLANA_FEE_PAYER_KEYPAIR_PATH`
+/// env variable. Falls back to the authority keypair when it is not set.
+pub fn get_fee_payer_keypair() -> Keypair {
+    match config_util::get_optional_env("SOLANA_FEE_PAYER_KEYPAIR_PATH") {
+        Some(path) => read_keypair(&path),
+        None => get_authority_keypair(),
+    }
+}
+
+/// Returns the keypair paying the rent of new accounts from `SOLANA_RENT_PAYER_KEYPAIR_PATH`
+/// env variable. Falls back to the fee payer keypair when it is not set.
+pub fn get_rent_payer_keypair() -> Keypair {
+    match config_util::get_optional_env("SOLANA_RENT_PAYER_KEYPAIR_PATH") {
+        Some(path) => read_keypair(&path),
+        None => get_fee_payer_keypair(),
+    }
+}
+
+/// Returns the keypairs of the payer pool from `SOLANA_PAYER_POOL_KEYPAIR_PATHS`
+/// env variable (comma-separated paths). The pool is empty when it is not set.
+pub fn get_payer_pool_keypairs() -> Vec<Keypair> {
+    config_util::get_optional_env("SOLANA_PAYER_POOL_KEYPAIR_PATHS")
+        .map(|paths| {
+            paths
+                .split(',')
+                .map(str::trim)
+                .filter(|path| !path.is_empty())
+                .map(read_keypair)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Reads the balance (in lamports) below which a payer is excluded from the pool from
+/// `SOLANA_PAYER_POOL_MIN_BALANCE_LAMPORTS` env variable (defaults to 0.01 SOL).
+pub fn get_payer_pool_min_balance() -> u64 {
+    config_util::get_optional_env("SOLANA_PAYER_POOL_MIN_BALANCE_LAMPORTS")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .expect("SOLANA_PAYER_POOL_MIN_BALANCE_LAMPORTS must be a valid number")
+        })
+        .unwrap_or(10_000_000)
+}
+
+/// Reads how often the balances of the payer pool are read from the chain from
+/// `SOLANA_PAYER_POOL_REFRESH_INTERVAL_MS` env variable (defaults to 60 seconds).
+/// In between, the fees of the sent transactions are deducted from the last read balances.
+pub fn get_payer_pool_refresh_interval() -> Duration {
+    let value = config_util::get_optional_env("SOLANA_PAYER_POOL_REFRESH_INTERVAL_MS")
+        .unwrap_or_else(|| "60000".to_string());
+
+    Duration::from_millis(
+        value
+            .parse::<u64>()
+            .expect("SOLANA_PAYER_POOL_REFRESH_INTERVAL_MS must be a valid number"),
+    )
+}
+
 /// Returns the mint account keypair from the environment variable.
 pub fn get_mint_account() -> Keypair {
     let mint_keypair_path = config_util::get_required_env("SOLANA_MINT_KEYPAIR_PATH")
@@ -46,11 +116,56 @@ pub fn get_token_symbol() -> String {
 
 /// Reads the metadata URI (JSON with image and description)
 /// from `SOLANA_TOKEN_METADATA_URI` env variable.
+/// Falls back to the built-in metadata server when it is not set.
 pub fn get_token_meta_uri() -> String {
-    config_util::get_required_env("SOLANA_TOKEN_METADATA_URI")
+    config_util::get_optional_env("SOLANA_TOKEN_METADATA_URI")
+        .or_else(|| server_util::get_self_hosted_uri("/metadata/token.json"))
         .expect("SOLANA_TOKEN_METADATA_URI is not set")
 }
 
+/// Reads the `SOLANA_TOKEN_2022` environment variable and returns the program
+/// of the reward token: Token-2022 if enabled, the SPL Token program otherwise.
+pub fn get_token_program_id() -> Pubkey {
+    let token_2022 = config_util::get_optional_env("SOLANA_TOKEN_2022")
+        .map(|value| value == "true" || value == "1")
+        .unwrap_or(false);
+
+    if token_2022 {
+        spl_token_2022::ID
+    } else {
+        spl_token::ID
+    }
+}
+
+/// Reads the optional Token-2022 transfer fee of the reward token from
+/// `SOLANA_TOKEN_TRANSFER_FEE_BASIS_POINTS` and `SOLANA_TOKEN_TRANSFER_FEE_MAX`
+/// (maximum fee in base units) env variables.
+pub fn get_token_transfer_fee() -> Option<(u16, u64)> {
+    let basis_points = config_util::get_optional_env("SOLANA_TOKEN_TRANSFER_FEE_BASIS_POINTS")?
+        .parse::<u16>()
+        .expect("SOLANA_TOKEN_TRANSFER_FEE_BASIS_POINTS must be a valid number");
+
+    let maximum_fee = config_util::get_optional_env("SOLANA_TOKEN_TRANSFER_FEE_MAX")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .expect("SOLANA_TOKEN_TRANSFER_FEE_MAX must be a valid number")
+        })
+        .unwrap_or(u64::MAX);
+
+    Some((basis_points, maximum_fee))
+}
+
+/// Reads the optional Token-2022 permanent delegate of the reward token
+/// from `SOLANA_TOKEN_PERMANENT_DELEGATE` env variable.
+pub fn get_token_permanent_delegate() -> Option<Pubkey> {
+    config_util::get_optional_env("SOLANA_TOKEN_PERMANENT_DELEGATE").map(|value| {
+        value
+            .parse::<Pubkey>()
+            .expect("SOLANA_TOKEN_PERMANENT_DELEGATE must be a valid public key")
+    })
+}
+
 /// Reads the nft name from `SOLANA_NFT_NAME` env variable.
 pub fn get_nft_name() -> String {
     config_util::get_required_env("SOLANA_NFT_NAME").expect("SOLANA_NFT_NAME is not set")
@@ -63,11 +178,98 @@ pub fn get_nft_symbol() -> String {
 
 /// Reads the metadata URI (JSON with image and description)
 /// from `SOLANA_NFT_METADATA_URI` env variable.
+/// Falls back to the built-in metadata server when it is not set.
 pub fn get_nft_meta_uri() -> String {
-    config_util::get_required_env("SOLANA_NFT_METADATA_URI")
+    config_util::get_optional_env("SOLANA_NFT_METADATA_URI")
+        .or_else(|| server_util::get_self_hosted_uri("/metadata/nft.json"))
         .expect("SOLANA_NFT_METADATA_URI is not set")
 }
 
+/// Reads the NFT metadata template path from `SOLANA_NFT_METADATA_TEMPLATE_PATH` env variable.
+/// The fixed name and URI from the environment are used when it is not set.
+pub fn get_nft_metadata_template_path() -> Option<String> {
+    config_util::get_optional_env("SOLANA_NFT_METADATA_TEMPLATE_PATH")
+}
+
+/// Reads the base URI under which rendered NFT metadata documents are served
+/// from `SOLANA_NFT_METADATA_BASE_URI` env variable.
+/// Falls back to the built-in metadata server when it is not set.
+pub fn get_nft_metadata_base_uri() -> String {
+    config_util::get_optional_env("SOLANA_NFT_METADATA_BASE_URI")
+        .or_else(|| server_util::get_self_hosted_uri("/metadata/nft"))
+        .expect("SOLANA_NFT_METADATA_BASE_URI is not set")
+}
+
+/// Reads the directory for rendered NFT metadata documents from
+/// `SOLANA_NFT_METADATA_DIR` env variable (defaults to `<STORAGE_DIR>/nft_metadata`).
+pub fn get_nft_metadata_dir() -> PathBuf {
+    config_util::get_optional_env("SOLANA_NFT_METADATA_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| storage::get_storage_dir().join("nft_metadata"))
+}
+
+/// Reads the SVG badge template path from `SOLANA_NFT_BADGE_TEMPLATE_PATH` env variable.
+/// Streak NFTs keep the template's image when it is not set.
+pub fn get_nft_badge_template_path() -> Option<String> {
+    config_util::get_optional_env("SOLANA_NFT_BADGE_TEMPLATE_PATH")
+}
+
+/// Reads the `SOLANA_NFT_BADGE_FORMAT` environment variable (`svg` or `png`, defaults to `svg`)
+/// and returns whether badges should be rasterized to PNG.
+pub fn get_nft_badge_png() -> bool {
+    config_util::get_optional_env("SOLANA_NFT_BADGE_FORMAT")
+        .map(|value| value.eq_ignore_ascii_case("png"))
+        .unwrap_or(false)
+}
+
+/// Reads the `SOLANA_NFT_SOULBOUND` environment variable and returns whether
+/// streak NFTs should be non-transferable (their token account is frozen).
+pub fn get_nft_soulbound() -> bool {
+    config_util::get_optional_env("SOLANA_NFT_SOULBOUND")
+        .map(|value| value == "true" || value == "1")
+        .unwrap_or(false)
+}
+
+/// Reads the metadata URI of revoked streak NFTs from `SOLANA_NFT_REVOKED_METADATA_URI`
+/// env variable. Falls back to the built-in metadata server when it is not set.
+pub fn get_nft_revoked_meta_uri() -> String {
+    config_util::get_optional_env("SOLANA_NFT_REVOKED_METADATA_URI")
+        .or_else(|| server_util::get_self_hosted_uri("/metadata/revoked.json"))
+        .expect("SOLANA_NFT_REVOKED_METADATA_URI is not set")
+}
+
+/// Reads the `SOLANA_NFT_PASSPORT` environment variable and returns whether each user
+/// holds a single evolving "streak passport" NFT instead of a new NFT per milestone.
+pub fn get_nft_passport() -> bool {
+    config_util::get_optional_env("SOLANA_NFT_PASSPORT")
+        .map(|value| value == "true" || value == "1")
+        .unwrap_or(false)
+}
+
+/// Reads the collection NFT name from `SOLANA_NFT_COLLECTION_NAME` env variable.
+pub fn get_nft_collection_name() -> String {
+    config_util::get_required_env("SOLANA_NFT_COLLECTION_NAME")
+        .expect("SOLANA_NFT_COLLECTION_NAME is not set")
+}
+
+/// Reads the collection metadata URI from `SOLANA_NFT_COLLECTION_METADATA_URI` env variable.
+/// Falls back to the built-in metadata server when it is not set.
+pub fn get_nft_collection_meta_uri() -> String {
+    config_util::get_optional_env("SOLANA_NFT_COLLECTION_METADATA_URI")
+        .or_else(|| server_util::get_self_hosted_uri("/metadata/collection.json"))
+        .expect("SOLANA_NFT_COLLECTION_METADATA_URI is not set")
+}
+
+/// Reads the collection mint from `SOLANA_NFT_COLLECTION_MINT` env variable.
+/// Streak NFTs are minted without a collection when it is not set.
+pub fn get_nft_collection_mint() -> Option<Pubkey> {
+    config_util::get_optional_env("SOLANA_NFT_COLLECTION_MINT").map(|value| {
+        value
+            .parse::<Pubkey>()
+            .expect("SOLANA_NFT_COLLECTION_MINT must be a valid public key")
+    })
+}
+
 /// Reads the `SOLANA_ON_CHAIN` environment variable and returns whether
 /// token rewards should be sent via on-chain Solana transactions.
 pub fn get_solana_on_chain() -> bool {
@@ -84,3 +286,420 @@ pub fn get_solana_streak_days() -> u8 {
         .parse::<u8>()
         .expect("SOLANA_STREAK_DAYS must be a valid number")
 }
+
+/// Reads the `SOLANA_INDEXER_ENABLED` environment variable and returns whether
+/// the on-chain program indexer should run alongside the Kafka consumer.
+pub fn get_solana_indexer_enabled() -> bool {
+    config_util::get_optional_env("SOLANA_INDEXER_ENABLED")
+        .map(|value| value == "true" || value == "1")
+        .unwrap_or(false)
+}
+
+/// Reads the indexer polling interval from `SOLANA_INDEXER_POLL_INTERVAL_MS`
+/// env variable (defaults to 5 seconds).
+pub fn get_solana_indexer_poll_interval() -> Duration {
+    let value = config_util::get_optional_env("SOLANA_INDEXER_POLL_INTERVAL_MS")
+        .unwrap_or_else(|| "5000".to_string());
+
+    Duration::from_millis(
+        value
+            .parse::<u64>()
+            .expect("SOLANA_INDEXER_POLL_INTERVAL_MS must be a valid number"),
+    )
+}
+
+/// Reads the signature of the program transaction the indexer starts after, when it has
+/// no checkpoint yet, from `SOLANA_INDEXER_START_SIGNATURE` env variable.
+/// Without one, the indexer starts from the latest program transaction.
+pub fn get_solana_indexer_start_signature() -> Option<Signature> {
+    config_util::get_optional_env("SOLANA_INDEXER_START_SIGNATURE").map(|value| {
+        value
+            .parse::<Signature>()
+            .expect("SOLANA_INDEXER_START_SIGNATURE must be a valid signature")
+    })
+}
+
+/// Reads the `SOLANA_TREASURY_MONITOR_ENABLED` environment variable and returns whether
+/// the treasury monitor should run alongside the Kafka consumer.
+pub fn get_solana_treasury_monitor_enabled() -> bool {
+    config_util::get_optional_env("SOLANA_TREASURY_MONITOR_ENABLED")
+        .map(|value| value == "true" || value == "1")
+        .unwrap_or(false)
+}
+
+/// Reads the treasury monitor polling interval from `SOLANA_TREASURY_MONITOR_INTERVAL_MS`
+/// env variable (defaults to 60 seconds).
+pub fn get_solana_treasury_monitor_interval() -> Duration {
+    let value = config_util::get_optional_env("SOLANA_TREASURY_MONITOR_INTERVAL_MS")
+        .unwrap_or_else(|| "60000".to_string());
+
+    Duration::from_millis(
+        value
+            .parse::<u64>()
+            .expect("SOLANA_TREASURY_MONITOR_INTERVAL_MS must be a valid number"),
+    )
+}
+
+/// Reads the SOL balance (decimal) below which the authority, fee payer and rent payer
+/// raise an alert from `SOLANA_TREASURY_MIN_SOL_BALANCE` env variable.
+pub fn get_solana_treasury_min_sol_balance() -> Option<String> {
+    config_util::get_optional_env("SOLANA_TREASURY_MIN_SOL_BALANCE")
+}
+
+/// Reads the treasury token balance (decimal) below which an alert is raised
+/// from `SOLANA_TREASURY_MIN_TOKEN_BALANCE` env variable.
+pub fn get_solana_treasury_min_token_balance() -> Option<String> {
+    config_util::get_optional_env("SOLANA_TREASURY_MIN_TOKEN_BALANCE")
+}
+
+/// Reads the treasury token balance (decimal) below which tokens are minted automatically
+/// from `SOLANA_TREASURY_MINT_WATERMARK` env variable. Nothing is minted when it is not set.
+pub fn get_solana_treasury_mint_watermark() -> Option<String> {
+    config_util::get_optional_env("SOLANA_TREASURY_MINT_WATERMARK")
+}
+
+/// Reads the treasury token balance (decimal) automatic mints top up to
+/// from `SOLANA_TREASURY_MINT_TARGET` env variable.
+pub fn get_solana_treasury_mint_target() -> Option<String> {
+    config_util::get_optional_env("SOLANA_TREASURY_MINT_TARGET")
+}
+
+/// Reads the total token supply (decimal) automatic mints never exceed
+/// from `SOLANA_TREASURY_MINT_SUPPLY_CEILING` env variable.
+pub fn get_solana_treasury_mint_supply_ceiling() -> Option<String> {
+    config_util::get_optional_env("SOLANA_TREASURY_MINT_SUPPLY_CEILING")
+}
+
+/// Maximum compute units a transaction can consume.
+const MAX_TRANSACTION_COMPUTE_UNITS: u64 = 1_400_000;
+
+/// Byte size of a transaction signature.
+const SIGNATURE_LEN: usize = 64;
+
+/// Reads the number of rewards after which a user's token account is added to the
+/// active address lookup table from `SOLANA_LOOKUP_TABLE_HOT_THRESHOLD` env variable.
+/// Token accounts are only added by the `extend-lookup-table` command when it is not set.
+pub fn get_solana_lookup_table_hot_threshold() -> Option<u64> {
+    config_util::get_optional_env("SOLANA_LOOKUP_TABLE_HOT_THRESHOLD").map(|value| {
+        value
+            .parse::<u64>()
+            .expect("SOLANA_LOOKUP_TABLE_HOT_THRESHOLD must be a valid number")
+    })
+}
+
+/// Compiles the instructions into a v0 message, resolving the accounts found
+/// in the address lookup tables through them.
+pub fn compile_v0_message(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> Result<VersionedMessage> {
+    let message = v0::Message::try_compile(payer, instructions, lookup_tables, recent_blockhash)?;
+
+    Ok(VersionedMessage::V0(message))
+}
+
+/// Returns the keypairs among `signers` whose signature the message requires,
+/// once each, so the same keypair may be given for several roles.
+fn required_signers<'a>(message_keys: &[Pubkey], signers: &[&'a Keypair]) -> Vec<&'a Keypair> {
+    let mut required: Vec<&Keypair> = vec![];
+
+    for key in message_keys {
+        if let Some(signer) = signers.iter().find(|signer| signer.pubkey() == *key) {
+            required.push(signer);
+        }
+    }
+
+    required
+}
+
+/// Builds a signed legacy transaction with the instructions.
+///
+/// # Arguments
+/// * `instructions` - The instructions of the transaction.
+/// * `payer` - The fee payer, who must be one of the signers.
+/// * `signers` - The keypairs that may sign the transaction, only those required sign.
+/// * `recent_blockhash` - A recent blockhash.
+pub fn create_transaction(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    signers: &[&Keypair],
+    recent_blockhash: Hash,
+) -> Result<Transaction> {
+    let message = Message::new(instructions, Some(payer));
+    let num_signers = message.header.num_required_signatures as usize;
+    let signers = required_signers(&message.account_keys[..num_signers], signers);
+
+    let mut transaction = Transaction::new_unsigned(message);
+    transaction.try_sign(&signers, recent_blockhash)?;
+
+    Ok(transaction)
+}
+
+/// Builds a signed v0 transaction with the instructions.
+///
+/// # Arguments
+/// * `instructions` - The instructions of the transaction.
+/// * `payer` - The fee payer, who must be one of the signers.
+/// * `signers` - The keypairs that may sign the transaction, only those required sign.
+/// * `lookup_tables` - The address lookup tables the accounts are resolved through.
+/// * `recent_blockhash` - A recent blockhash.
+pub fn create_v0_transaction(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    signers: &[&Keypair],
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> Result<VersionedTransaction> {
+    let message = compile_v0_message(instructions, payer, lookup_tables, recent_blockhash)?;
+    let num_signers = message.header().num_required_signatures as usize;
+    let signers = required_signers(&message.static_account_keys()[..num_signers], signers);
+
+    Ok(VersionedTransaction::try_new(message, &signers)?)
+}
+
+/// Interval between the status checks of `settle_transaction`.
+const SETTLE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Waits until a transaction whose confirmation failed (e.g. timed out) is settled:
+/// it was processed, or its blockhash expired so it can no longer land.
+///
+/// # Arguments
+/// * `rpc_client` - The RPC client the transaction was sent with.
+/// * `signature` - The signature of the transaction.
+/// * `recent_blockhash` - The blockhash the transaction was signed with.
+///
+/// # Returns
+/// * `true` if the transaction landed and succeeded, `false` if it failed or never landed.
+///   Errors mean the outcome is unknown, so the transaction must not be sent again.
+pub async fn settle_transaction(
+    rpc_client: &RpcClient,
+    signature: &Signature,
+    recent_blockhash: &Hash,
+) -> Result<bool> {
+    loop {
+        // Check the blockhash first, so a transaction processed right before it expires is seen
+        let expired = !rpc_client
+            .is_blockhash_valid(recent_blockhash, CommitmentConfig::processed())
+            .await?;
+
+        let status = rpc_client
+            .get_signature_statuses_with_history(&[*signature])
+            .await?
+            .value
+            .into_iter()
+            .next()
+            .flatten();
+
+        if let Some(status) = status {
+            return Ok(status.err.is_none());
+        }
+
+        if expired {
+            return Ok(false);
+        }
+
+        tokio::time::sleep(SETTLE_POLL_INTERVAL).await;
+    }
+}
+
+/// Returns the serialized size of a v0 transaction with the given instructions,
+/// or `usize::MAX` if they cannot be compiled into one transaction.
+pub fn get_transaction_size(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> usize {
+    let Ok(message) = compile_v0_message(instructions, payer, lookup_tables, Hash::default())
+    else {
+        return usize::MAX;
+    };
+    let num_signatures = message.header().num_required_signatures as usize;
+
+    // Compact length of the signatures, the signatures, then the message
+    1 + num_signatures * SIGNATURE_LEN + message.serialize().len()
+}
+
+/// Packs the instructions of several payouts into as few transactions as possible.
+///
+/// The instructions of a payout always go in the same transaction, a wallet is paid
+/// at most once per transaction, and every v0 transaction stays within the packet size
+/// (accounts in the lookup tables take one byte instead of 32), the compute limit
+/// (estimated per payout) and `max_payouts`.
+///
+/// # Arguments
+/// * `payouts` - The paid wallet and the instructions of every payout.
+/// * `payer` - The fee payer of the transactions.
+/// * `lookup_tables` - The address lookup tables the transactions are compiled with.
+/// * `compute_units_per_payout` - Estimated compute units of a payout.
+/// * `max_payouts` - Maximum number of payouts per transaction.
+///
+/// # Returns
+/// * The indexes of the payouts of every transaction, in order.
+pub fn pack_payouts(
+    payouts: &[(Pubkey, Vec<Instruction>)],
+    payer: &Pubkey,
+    lookup_tables: &[AddressLookupTableAccount],
+    compute_units_per_payout: u64,
+    max_payouts: usize,
+) -> Vec<Vec<usize>> {
+    let max_payouts = max_payouts
+        .min((MAX_TRANSACTION_COMPUTE_UNITS / compute_units_per_payout.max(1)) as usize)
+        .max(1);
+
+    let mut transactions: Vec<Vec<usize>> = vec![];
+    let mut indexes: Vec<usize> = vec![];
+    let mut instructions: Vec<Instruction> = vec![];
+
+    for (index, (wallet, payout_instructions)) in payouts.iter().enumerate() {
+        let mut candidate = instructions.clone();
+        candidate.extend(payout_instructions.iter().cloned());
+
+        let fits = indexes.len() < max_payouts
+            && indexes.iter().all(|other| payouts[*other].0 != *wallet)
+            && get_transaction_size(&candidate, payer, lookup_tables) <= PACKET_DATA_SIZE;
+
+        if fits || indexes.is_empty() {
+            indexes.push(index);
+            instructions = candidate;
+        } else {
+            transactions.push(std::mem::take(&mut indexes));
+            indexes.push(index);
+            instructions = payout_instructions.clone();
+        }
+    }
+
+    if !indexes.is_empty() {
+        transactions.push(indexes);
+    }
+
+    transactions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::AccountMeta;
+
+    /// A payout to `wallet` with one instruction carrying `data_len` bytes of data
+    /// and `extra_accounts` other accounts.
+    fn payout(
+        wallet: Pubkey,
+        data_len: usize,
+        extra_accounts: &[Pubkey],
+    ) -> (Pubkey, Vec<Instruction>) {
+        let mut accounts = vec![AccountMeta::new(wallet, false)];
+        accounts.extend(
+            extra_accounts
+                .iter()
+                .map(|account| AccountMeta::new(*account, false)),
+        );
+        let instruction =
+            Instruction::new_with_bytes(Pubkey::new_unique(), &vec![0; data_len], accounts);
+
+        (wallet, vec![instruction])
+    }
+
+    fn payouts(count: usize) -> Vec<(Pubkey, Vec<Instruction>)> {
+        (0..count)
+            .map(|_| payout(Pubkey::new_unique(), 8, &[]))
+            .collect()
+    }
+
+    #[test]
+    fn packs_nothing_without_payouts() {
+        assert!(pack_payouts(&[], &Pubkey::new_unique(), &[], 10_000, 10).is_empty());
+    }
+
+    #[test]
+    fn respects_max_payouts() {
+        let packed = pack_payouts(&payouts(5), &Pubkey::new_unique(), &[], 10_000, 2);
+
+        assert_eq!(packed, vec![vec![0, 1], vec![2, 3], vec![4]]);
+    }
+
+    #[test]
+    fn respects_the_compute_limit() {
+        let compute_units_per_payout = MAX_TRANSACTION_COMPUTE_UNITS / 3;
+
+        let packed = pack_payouts(
+            &payouts(4),
+            &Pubkey::new_unique(),
+            &[],
+            compute_units_per_payout,
+            10,
+        );
+
+        assert_eq!(packed, vec![vec![0, 1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn pays_a_wallet_once_per_transaction() {
+        let wallet = Pubkey::new_unique();
+        let payouts = vec![
+            payout(wallet, 8, &[]),
+            payout(Pubkey::new_unique(), 8, &[]),
+            payout(wallet, 8, &[]),
+        ];
+
+        let packed = pack_payouts(&payouts, &Pubkey::new_unique(), &[], 10_000, 10);
+
+        assert_eq!(packed, vec![vec![0, 1], vec![2]]);
+    }
+
+    #[test]
+    fn respects_the_packet_size() {
+        let payouts: Vec<_> = (0..3)
+            .map(|_| payout(Pubkey::new_unique(), 400, &[]))
+            .collect();
+        let payer = Pubkey::new_unique();
+
+        let packed = pack_payouts(&payouts, &payer, &[], 10_000, 10);
+
+        assert_eq!(packed, vec![vec![0, 1], vec![2]]);
+        for indexes in &packed {
+            let instructions: Vec<_> = indexes
+                .iter()
+                .flat_map(|index| payouts[*index].1.clone())
+                .collect();
+            assert!(get_transaction_size(&instructions, &payer, &[]) <= PACKET_DATA_SIZE);
+        }
+    }
+
+    #[test]
+    fn keeps_an_oversized_payout_alone() {
+        let payouts = vec![
+            payout(Pubkey::new_unique(), 8, &[]),
+            payout(Pubkey::new_unique(), PACKET_DATA_SIZE, &[]),
+            payout(Pubkey::new_unique(), 8, &[]),
+        ];
+
+        let packed = pack_payouts(&payouts, &Pubkey::new_unique(), &[], 10_000, 10);
+
+        assert_eq!(packed, vec![vec![0], vec![1], vec![2]]);
+    }
+
+    #[test]
+    fn fits_more_payouts_with_lookup_tables() {
+        let shared: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let payouts: Vec<_> = (0..20)
+            .map(|_| payout(Pubkey::new_unique(), 8, &shared))
+            .collect();
+        let lookup_table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: payouts
+                .iter()
+                .map(|(wallet, _)| *wallet)
+                .chain(shared.iter().copied())
+                .collect(),
+        };
+        let payer = Pubkey::new_unique();
+
+        let without_tables = pack_payouts(&payouts, &payer, &[], 10_000, 20);
+        let with_tables = pack_payouts(&payouts, &payer, &[lookup_table], 10_000, 20);
+
+        assert!(with_tables.len() < without_tables.len());
+        assert_eq!(with_tables.concat(), (0..20).collect::<Vec<_>>());
+    }
+}
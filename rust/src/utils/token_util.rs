@@ -0,0 +1,149 @@
+use anyhow::{Context, Result, bail};
+
+/// Decimals of SOL amounts (lamports per SOL).
+pub const SOL_DECIMALS: u8 = 9;
+
+/// Converts a decimal number of tokens (e.g. `"5"` or `"0.25"`) into base units.
+///
+/// Fails if the amount is malformed, has more fractional digits than the mint's
+/// decimals (trailing zeros aside), or does not fit into `u64` base units.
+///
+/// # Arguments
+/// * `amount` - Number of tokens as a decimal string.
+/// * `decimals` - Decimals of the token mint.
+pub fn parse_token_amount(amount: &str, decimals: u8) -> Result<u64> {
+    let amount = amount.trim();
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+        bail!("Invalid token amount \"{}\"", amount);
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > decimals as usize {
+        bail!(
+            "Token amount \"{}\" has more than {} decimal places",
+            amount,
+            decimals
+        );
+    }
+
+    let overflow = || format!("Token amount \"{}\" is too large", amount);
+
+    let whole_units = match whole {
+        "" => 0,
+        whole => whole.parse::<u64>().with_context(overflow)?,
+    };
+    let fraction_units = match fraction {
+        "" => 0,
+        fraction => fraction.parse::<u64>().with_context(overflow)?,
+    };
+
+    whole_units
+        .checked_mul(base_units_per_token(decimals)?)
+        .and_then(|units| {
+            let padding = 10_u64.checked_pow((decimals as usize - fraction.len()) as u32)?;
+            units.checked_add(fraction_units.checked_mul(padding)?)
+        })
+        .with_context(overflow)
+}
+
+/// Converts a whole number of tokens into base units.
+pub fn tokens_to_base_units(tokens: u64, decimals: u8) -> Result<u64> {
+    tokens
+        .checked_mul(base_units_per_token(decimals)?)
+        .with_context(|| format!("Token amount {} is too large", tokens))
+}
+
+/// Formats an amount of base units as a decimal number of tokens (e.g. `0.25`).
+pub fn format_token_amount(base_units: u64, decimals: u8) -> String {
+    let digits = format!("{:0>width$}", base_units, width = decimals as usize + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// Returns `10^decimals`, the number of base units in one token.
+fn base_units_per_token(decimals: u8) -> Result<u64> {
+    10_u64
+        .checked_pow(decimals as u32)
+        .with_context(|| format!("Unsupported token decimals {}", decimals))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_whole_and_decimal_amounts() {
+        assert_eq!(parse_token_amount("5", 2).unwrap(), 500);
+        assert_eq!(parse_token_amount("0.25", 2).unwrap(), 25);
+        assert_eq!(parse_token_amount(".5", 2).unwrap(), 50);
+        assert_eq!(parse_token_amount("3.", 2).unwrap(), 300);
+        assert_eq!(parse_token_amount(" 1.5 ", 9).unwrap(), 1_500_000_000);
+        assert_eq!(parse_token_amount("7", 0).unwrap(), 7);
+    }
+
+    #[test]
+    fn accepts_trailing_zeros() {
+        assert_eq!(parse_token_amount("1.50", 2).unwrap(), 150);
+        assert_eq!(parse_token_amount("1.5000", 2).unwrap(), 150);
+        assert_eq!(parse_token_amount("2.000", 0).unwrap(), 2);
+        assert_eq!(parse_token_amount("0010", 2).unwrap(), 1000);
+    }
+
+    #[test]
+    fn rejects_too_many_decimals() {
+        assert!(parse_token_amount("1.234", 2).is_err());
+        assert!(parse_token_amount("0.5", 0).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_amounts() {
+        for amount in [
+            "", " ", ".", "-1", "+1", "1e3", "1,5", "1.2.3", "abc", "0x10",
+        ] {
+            assert!(parse_token_amount(amount, 2).is_err(), "{:?}", amount);
+        }
+    }
+
+    #[test]
+    fn rejects_overflowing_amounts() {
+        assert_eq!(
+            parse_token_amount("18446744073709551615", 0).unwrap(),
+            u64::MAX
+        );
+        assert!(parse_token_amount("18446744073709551616", 0).is_err());
+        assert!(parse_token_amount("18446744073709551615", 1).is_err());
+        assert!(parse_token_amount("18446744073709551.616", 3).is_err());
+        assert!(parse_token_amount("1", 20).is_err());
+    }
+
+    #[test]
+    fn formats_amounts_without_trailing_zeros() {
+        assert_eq!(format_token_amount(0, 2), "0");
+        assert_eq!(format_token_amount(500, 2), "5");
+        assert_eq!(format_token_amount(150, 2), "1.5");
+        assert_eq!(format_token_amount(5, 2), "0.05");
+        assert_eq!(format_token_amount(7, 0), "7");
+        assert_eq!(format_token_amount(u64::MAX, 9), "18446744073.709551615");
+    }
+
+    #[test]
+    fn formatted_amounts_parse_back() {
+        for (base_units, decimals) in [(0, 9), (1, 9), (1_500_000_000, 9), (u64::MAX, 9), (42, 0)] {
+            let formatted = format_token_amount(base_units, decimals);
+
+            assert_eq!(
+                parse_token_amount(&formatted, decimals).unwrap(),
+                base_units
+            );
+        }
+    }
+}